@@ -1,10 +1,11 @@
-use std::sync::OnceLock;
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use tokio::sync::mpsc;
 
-use crate::app::{AppState, UsageData};
-use crate::config::AuthMethod;
+use crate::app::{AccountState, AppState, OrgChoice, UsageData};
+use crate::config::{AccountConfig, AuthMethod, Settings};
 use crate::event::Event;
 use crate::oauth;
 
@@ -14,36 +15,149 @@ pub(crate) fn http_client() -> &'static reqwest::Client {
     CLIENT.get_or_init(reqwest::Client::new)
 }
 
+/// Production host for session-key (claude.ai) usage requests.
+const SESSION_API_BASE: &str = "https://claude.ai";
+
+/// Build a request URL from an optional base override, falling back to `default_base`.
+pub(crate) fn api_url(base_override: Option<&str>, default_base: &str, path: &str) -> String {
+    let base = base_override.unwrap_or(default_base).trim_end_matches('/');
+    format!("{base}{path}")
+}
+
+/// Whether a base override sends any account's requests somewhere other than its
+/// production host. The one override replaces the claude.ai and api.anthropic.com
+/// hosts alike, so spelling out one of them still moves the other account type.
+pub fn is_non_default_api(base_override: Option<&str>, accounts: &[AccountState]) -> bool {
+    let Some(base) = base_override else {
+        return false;
+    };
+    let base = base.trim_end_matches('/');
+    accounts.iter().any(|account| {
+        let production = match account.config.auth_method {
+            AuthMethod::SessionKey => SESSION_API_BASE,
+            AuthMethod::OAuth => oauth::OAUTH_API_BASE,
+        };
+        base != production
+    })
+}
+
+/// Settings that shape a usage request, captured from `AppState` at dispatch time.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FetchOptions {
+    /// Host override for usage endpoints; `None` means production.
+    pub api_base: Option<String>,
+    /// Treat a response without a `five_hour` bucket as 0% instead of an error.
+    pub allow_missing_five_hour: bool,
+    /// Per-account header overrides from the config.
+    pub headers: BTreeMap<String, String>,
+    /// Claude Code keychain service re-read when an OAuth token is rejected.
+    pub keychain_service: String,
+}
+
+impl FetchOptions {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            api_base: settings.api_base_url.clone(),
+            allow_missing_five_hour: settings.allow_missing_five_hour,
+            headers: BTreeMap::new(),
+            keychain_service: settings.claude_code_keychain_service.clone(),
+        }
+    }
+
+    pub fn for_account(settings: &Settings, account: &AccountConfig) -> Self {
+        Self {
+            headers: account.headers.clone(),
+            ..Self::from_settings(settings)
+        }
+    }
+
+    /// Apply the header overrides, replacing built-in headers of the same name.
+    /// Entries that aren't valid HTTP header names/values are skipped.
+    pub fn apply_headers(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        request.headers(extra_header_map(&self.headers))
+    }
+}
+
+fn extra_header_map(headers: &BTreeMap<String, String>) -> reqwest::header::HeaderMap {
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                map.insert(name, value);
+            }
+            _ => eprintln!("[headers] Skipping invalid header override '{name}'"),
+        }
+    }
+    map
+}
+
 pub fn spawn_fetch_all(app: &AppState, tx: &mpsc::UnboundedSender<Event>) {
-    for (i, account) in app.accounts.iter().enumerate() {
+    let fetchable = app.accounts.iter().filter(|a| !a.credential_missing());
+    for (i, account) in fetchable.enumerate() {
         let tx = tx.clone();
         let account_name = account.config.name.clone();
         let org_id = account.config.org_id.clone();
         let auth_method = account.config.auth_method.clone();
         let cached_token = account.cached_token.clone();
-        let stagger = Duration::from_millis(100 * i as u64);
+        let options = FetchOptions::for_account(&app.settings, &account.config);
+        let stagger = Duration::from_millis(app.settings.fetch_stagger_ms.saturating_mul(i as u64));
 
         tokio::spawn(async move {
             tokio::time::sleep(stagger).await;
-            fetch_with_refresh(&tx, &account_name, &org_id, &auth_method, cached_token).await;
+            fetch_with_refresh(&tx, &account_name, &org_id, &auth_method, cached_token, &options).await;
         });
     }
 }
 
+/// Fetch every account concurrently for `--once`, abandoning any still running at
+/// `deadline` with a "Timeout" error so one hung request can't hold up the output.
+/// No token refresh: nothing would persist the new credential.
+pub(crate) async fn fetch_all_with_deadline(
+    accounts: &[AccountState],
+    settings: &Settings,
+    deadline: Duration,
+) -> Vec<(String, Result<UsageData, String>)> {
+    let until = tokio::time::Instant::now() + deadline;
+    let fetches = accounts.iter().map(|account| {
+        let account_name = account.config.name.clone();
+        let org_id = account.config.org_id.clone();
+        let auth_method = account.config.auth_method.clone();
+        let cached_token = account.cached_token.clone();
+        let options = FetchOptions::for_account(settings, &account.config);
+        async move {
+            let fetch = fetch_account_usage(&org_id, &auth_method, cached_token.as_deref(), &options);
+            let result = tokio::time::timeout_at(until, fetch)
+                .await
+                .unwrap_or_else(|_| Err("Timeout".to_string()));
+            (account_name, result)
+        }
+    });
+    futures::future::join_all(fetches).await
+}
+
+/// Fetch one account, looked up by name at call time so an index captured before a
+/// delete or rename can't fetch the wrong account. Unknown names are ignored.
 pub fn spawn_fetch_one(
     app: &AppState,
-    index: usize,
+    account_name: &str,
     tx: &mpsc::UnboundedSender<Event>,
 ) {
-    if let Some(account) = app.accounts.get(index) {
+    let account = app.accounts.iter().find(|a| a.config.name == account_name);
+    if let Some(account) = account.filter(|a| !a.credential_missing()) {
         let tx = tx.clone();
         let account_name = account.config.name.clone();
         let org_id = account.config.org_id.clone();
         let auth_method = account.config.auth_method.clone();
         let cached_token = account.cached_token.clone();
+        let options = FetchOptions::for_account(&app.settings, &account.config);
 
         tokio::spawn(async move {
-            fetch_with_refresh(&tx, &account_name, &org_id, &auth_method, cached_token).await;
+            fetch_with_refresh(&tx, &account_name, &org_id, &auth_method, cached_token, &options).await;
         });
     }
 }
@@ -55,8 +169,25 @@ async fn fetch_with_refresh(
     org_id: &str,
     auth_method: &AuthMethod,
     cached_token: Option<String>,
+    options: &FetchOptions,
 ) {
-    let result = fetch_account_usage(org_id, auth_method, cached_token.as_deref()).await;
+    let started = Instant::now();
+    let newer_credential = || claude_code_credential_for(account_name, org_id, cached_token.as_deref(), options);
+    let (result, adopted) =
+        fetch_retrying_rejected(org_id, auth_method, cached_token.as_deref(), options, newer_credential).await;
+    if let Some(new_cred) = adopted {
+        eprintln!("[refresh] Picked up Claude Code's newer token for {account_name}");
+        let _ = tx.send(Event::TokenRefreshed {
+            account_name: account_name.to_string(),
+            raw_credential: new_cred,
+        });
+        let _ = tx.send(Event::UsageResult {
+            account_name: account_name.to_string(),
+            result,
+            elapsed: started.elapsed(),
+        });
+        return;
+    }
 
     // If expired and OAuth with a refresh token available, try refreshing
     if result.is_err() && *auth_method == AuthMethod::OAuth {
@@ -79,10 +210,11 @@ async fn fetch_with_refresh(
                     });
 
                     // Retry the fetch with the fresh token
-                    let retry = fetch_account_usage(org_id, auth_method, Some(&new_cred)).await;
+                    let retry = fetch_account_usage(org_id, auth_method, Some(&new_cred), options).await;
                     let _ = tx.send(Event::UsageResult {
                         account_name: account_name.to_string(),
                         result: retry,
+                        elapsed: started.elapsed(),
                     });
                     return;
                 }
@@ -94,34 +226,182 @@ async fn fetch_with_refresh(
     let _ = tx.send(Event::UsageResult {
         account_name: account_name.to_string(),
         result,
+        elapsed: started.elapsed(),
     });
 }
 
+/// Fetch usage. A rejected OAuth token may already have been refreshed by Claude Code
+/// in the background, so ask `newer_credential` for its token and retry once before
+/// the caller spends our refresh token. Returns the result and the credential the
+/// retry used, if any, for the caller to persist.
+async fn fetch_retrying_rejected<F, Fut>(
+    org_id: &str,
+    auth_method: &AuthMethod,
+    cached_token: Option<&str>,
+    options: &FetchOptions,
+    newer_credential: F,
+) -> (Result<UsageData, String>, Option<String>)
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Option<String>>,
+{
+    let result = fetch_account_usage(org_id, auth_method, cached_token, options).await;
+    let rejected = matches!(&result, Err(e) if e == EXPIRED_ERROR);
+    if !rejected || *auth_method != AuthMethod::OAuth {
+        return (result, None);
+    }
+    match newer_credential().await {
+        Some(new_cred) => {
+            let retry = fetch_account_usage(org_id, auth_method, Some(&new_cred), options).await;
+            (retry, Some(new_cred))
+        }
+        None => (result, None),
+    }
+}
+
+/// Claude Code's current credential, if it holds a different token than ours that
+/// belongs to this account (see `owns_profile`). Costs one keychain read and one
+/// profile call.
+async fn claude_code_credential_for(
+    account_name: &str,
+    org_id: &str,
+    cached_token: Option<&str>,
+    options: &FetchOptions,
+) -> Option<String> {
+    let service = options.keychain_service.clone();
+    let raw = tokio::task::spawn_blocking(move || oauth::read_claude_code_credential(&service))
+        .await
+        .ok()?
+        .ok()?;
+    let cc_token = oauth::normalize_stored_token(&raw);
+    if cached_token.map(oauth::normalize_stored_token).as_deref() == Some(cc_token.as_str()) {
+        return None; // same token we just had rejected
+    }
+    // Claude Code may be logged into a different account
+    let profile = oauth::fetch_profile(&cc_token).await.ok()?;
+    owns_profile(account_name, org_id, &profile).then_some(raw)
+}
+
+/// Whether the login `profile` is this account: the same org (when the account has
+/// one), and the account is named after the email, ignoring case, as imported. A
+/// same-email import in a second org carries a " (<org>)" suffix, which still matches.
+pub(crate) fn owns_profile(account_name: &str, org_id: &str, profile: &oauth::OAuthProfile) -> bool {
+    if !org_id.is_empty() && org_id != profile.org_id {
+        return false;
+    }
+    let name = account_name.to_lowercase();
+    let email = profile.email.to_lowercase();
+    name.strip_prefix(&email)
+        .is_some_and(|rest| rest.is_empty() || (rest.starts_with(" (") && rest.ends_with(')')))
+}
+
 /// Shared fetch logic. Uses cached token from memory — no keychain reads.
 async fn fetch_account_usage(
     org_id: &str,
     auth_method: &AuthMethod,
     cached_token: Option<&str>,
+    options: &FetchOptions,
 ) -> Result<UsageData, String> {
+    let body = fetch_usage_body(org_id, auth_method, cached_token, options).await?;
+    oauth::parse_usage_response(&body, options.allow_missing_five_hour)
+        .map_err(|e| humanize_error(&e))
+}
+
+/// Fetch the unparsed usage JSON with whichever auth the account uses.
+async fn fetch_usage_body(
+    org_id: &str,
+    auth_method: &AuthMethod,
+    cached_token: Option<&str>,
+    options: &FetchOptions,
+) -> Result<serde_json::Value, String> {
     let token = cached_token
         .ok_or_else(|| "No token cached — re-import (i)".to_string())?;
     let result = match auth_method {
         AuthMethod::SessionKey => {
-            fetch_usage_session_key(token, org_id).await
+            fetch_session_usage_body(token, org_id, options).await
         }
         AuthMethod::OAuth => {
             let normalized = oauth::normalize_stored_token(token);
-            oauth::fetch_oauth_usage(&normalized).await
+            oauth::fetch_oauth_usage_body(&normalized, options).await
         }
     };
     result.map_err(|e| humanize_error(&e))
 }
 
+/// Re-fetch one account and send back the pretty-printed response body, with
+/// anything secret-looking masked, for the raw JSON viewer.
+/// `org_override` fetches against a different org_id without touching the config.
+pub fn spawn_fetch_raw(
+    app: &AppState,
+    index: usize,
+    org_override: Option<String>,
+    tx: &mpsc::UnboundedSender<Event>,
+) {
+    if let Some(account) = app.accounts.get(index) {
+        let tx = tx.clone();
+        let (account_name, org_id) = match org_override {
+            Some(org_id) => (format!("{} @ {org_id}", account.config.name), org_id),
+            None => (account.config.name.clone(), account.config.org_id.clone()),
+        };
+        let auth_method = account.config.auth_method.clone();
+        let cached_token = account.cached_token.clone();
+        let options = FetchOptions::for_account(&app.settings, &account.config);
+
+        tokio::spawn(async move {
+            let result = fetch_usage_body(&org_id, &auth_method, cached_token.as_deref(), &options)
+                .await
+                .map(|mut body| {
+                    mask_secrets(&mut body);
+                    serde_json::to_string_pretty(&body).unwrap_or_else(|_| body.to_string())
+                });
+            let _ = tx.send(Event::RawUsageResult {
+                account_name,
+                result,
+            });
+        });
+    }
+}
+
+/// The usage body for `raw --name`, pretty-printed and masked like the viewer's.
+pub(crate) async fn fetch_raw_body(
+    settings: &Settings,
+    account: &AccountConfig,
+    token: &str,
+) -> Result<String, String> {
+    let options = FetchOptions::for_account(settings, account);
+    let mut body = fetch_usage_body(&account.org_id, &account.auth_method, Some(token), &options).await?;
+    mask_secrets(&mut body);
+    Ok(serde_json::to_string_pretty(&body).unwrap_or_else(|_| body.to_string()))
+}
+
+/// Replace the string value of any key that looks like it holds a credential.
+/// Numbers such as `tokens_used` are counts, not secrets, and stay visible.
+fn mask_secrets(value: &mut serde_json::Value) {
+    const SECRET_HINTS: [&str; 6] = ["token", "secret", "key", "session", "cookie", "authorization"];
+    match value {
+        serde_json::Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                let lower = k.to_lowercase();
+                if SECRET_HINTS.iter().any(|hint| lower.contains(hint)) && v.is_string() {
+                    *v = serde_json::Value::String("***".to_string());
+                } else {
+                    mask_secrets(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(mask_secrets),
+        _ => {}
+    }
+}
+
+/// What a rejected (401/403) token reports as; also how a rejection is recognized.
+const EXPIRED_ERROR: &str = "Expired — re-import (i)";
+
 /// Turn common API errors into short, actionable messages.
 fn humanize_error(e: &anyhow::Error) -> String {
     let msg = format!("{e:#}");
     if msg.contains("401") || msg.contains("403") {
-        "Expired — re-import (i)".to_string()
+        EXPIRED_ERROR.to_string()
     } else if msg.contains("429") {
         "Rate limited — try later".to_string()
     } else if msg.contains("timed out") || msg.contains("timeout") {
@@ -135,63 +415,28 @@ fn humanize_error(e: &anyhow::Error) -> String {
 
 /// Import OAuth credentials from Claude Code's keychain, identify the account,
 /// and send the result back.
-pub fn spawn_oauth_import(tx: &mpsc::UnboundedSender<Event>) {
+pub fn spawn_oauth_import(app: &AppState, tx: &mpsc::UnboundedSender<Event>) {
     let tx = tx.clone();
+    let service = app.settings.claude_code_keychain_service.clone();
     tokio::spawn(async move {
-        let result = do_oauth_import().await;
+        let result = do_oauth_import(&service).await;
         let _ = tx.send(Event::OAuthImportResult {
             result: result.map_err(|e| format!("{e:#}")),
         });
     });
 }
 
-async fn do_oauth_import() -> anyhow::Result<Vec<crate::event::OAuthImportData>> {
+async fn do_oauth_import(service: &str) -> anyhow::Result<Vec<crate::event::OAuthImportData>> {
     // Read all Claude Code raw credentials from macOS Keychain
     // (default + alternate config-directory instances)
-    let credentials = oauth::read_all_claude_code_credentials()?;
+    let credentials = oauth::read_all_claude_code_credentials(service)?;
 
     let mut results = Vec::new();
     for raw_credential in credentials {
-        let access_token = oauth::normalize_stored_token(&raw_credential);
-
-        // Try profile with current access token
-        match oauth::fetch_profile(&access_token).await {
-            Ok(profile) => {
-                results.push(crate::event::OAuthImportData {
-                    name: profile.email,
-                    org_id: profile.org_id,
-                    raw_credential,
-                });
-                continue;
-            }
-            Err(_) => {}
-        }
-
-        // Access token expired — try refreshing before giving up
-        if let Some(refresh_tok) = oauth::extract_refresh_token(&raw_credential) {
-            eprintln!("[import] Access token expired, attempting refresh...");
-            if let Ok(refreshed) = oauth::refresh_access_token(&refresh_tok).await {
-                let new_cred = oauth::update_credential_json(
-                    &raw_credential,
-                    &refreshed.access_token,
-                    refreshed.refresh_token.as_deref(),
-                    refreshed.expires_at,
-                );
-                match oauth::fetch_profile(&refreshed.access_token).await {
-                    Ok(profile) => {
-                        eprintln!("[import] Refreshed token for {}", profile.email);
-                        results.push(crate::event::OAuthImportData {
-                            name: profile.email,
-                            org_id: profile.org_id,
-                            raw_credential: new_cred,
-                        });
-                        continue;
-                    }
-                    Err(_) => {}
-                }
-            }
+        // Credentials whose access and refresh tokens both fail are skipped
+        if let Some(data) = identify_credential(raw_credential).await {
+            results.push(data);
         }
-        // Both access and refresh failed — skip this credential
     }
 
     if results.is_empty() {
@@ -202,6 +447,66 @@ async fn do_oauth_import() -> anyhow::Result<Vec<crate::event::OAuthImportData>>
     Ok(results)
 }
 
+/// Look up which account a raw credential belongs to, refreshing it first if the
+/// access token has expired. Returns None if neither token works.
+async fn identify_credential(raw_credential: String) -> Option<crate::event::OAuthImportData> {
+    let access_token = oauth::normalize_stored_token(&raw_credential);
+
+    // Try profile with current access token
+    if let Ok(profile) = oauth::fetch_profile(&access_token).await {
+        return Some(crate::event::OAuthImportData {
+            name: profile.email,
+            org_id: profile.org_id,
+            plan: profile.plan,
+            raw_credential,
+        });
+    }
+
+    // Access token expired — try refreshing before giving up
+    let refresh_tok = oauth::extract_refresh_token(&raw_credential)?;
+    eprintln!("[import] Access token expired, attempting refresh...");
+    let refreshed = oauth::refresh_access_token(&refresh_tok).await.ok()?;
+    let new_cred = oauth::update_credential_json(
+        &raw_credential,
+        &refreshed.access_token,
+        refreshed.refresh_token.as_deref(),
+        refreshed.expires_at,
+    );
+    let profile = oauth::fetch_profile(&refreshed.access_token).await.ok()?;
+    eprintln!("[import] Refreshed token for {}", profile.email);
+    Some(crate::event::OAuthImportData {
+        name: profile.email,
+        org_id: profile.org_id,
+        plan: profile.plan,
+        raw_credential: new_cred,
+    })
+}
+
+/// Import a credential JSON blob pasted by the user (e.g. copied from another
+/// machine). Reports through the same event as the keychain import.
+pub fn spawn_credential_import(raw_credential: String, tx: &mpsc::UnboundedSender<Event>) {
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let result = identify_credential(raw_credential)
+            .await
+            .map(|data| vec![data])
+            .ok_or_else(|| "Credential was rejected — token and refresh token both failed".to_string());
+        let _ = tx.send(Event::OAuthImportResult { result });
+    });
+}
+
+/// Identify a credential pasted to repair `account_name` (`K`), so it can be checked
+/// against that account before it's stored.
+pub fn spawn_identify_replacement(account_name: String, raw_credential: String, tx: &mpsc::UnboundedSender<Event>) {
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let result = identify_credential(raw_credential)
+            .await
+            .ok_or_else(|| "Credential was rejected — token and refresh token both failed".to_string());
+        let _ = tx.send(Event::ReplacementIdentified { account_name, result });
+    });
+}
+
 /// Start an independent OAuth login flow. Opens the browser, captures the callback,
 /// exchanges the code for tokens, and identifies the account.
 pub fn spawn_oauth_login(tx: &mpsc::UnboundedSender<Event>) {
@@ -318,6 +623,7 @@ async fn do_oauth_login() -> anyhow::Result<crate::event::OAuthImportData> {
     Ok(crate::event::OAuthImportData {
         name: profile.email,
         org_id: profile.org_id,
+        plan: profile.plan,
         raw_credential,
     })
 }
@@ -336,10 +642,11 @@ pub fn spawn_detect_logged_in(app: &AppState, tx: &mpsc::UnboundedSender<Event>)
             })
         })
         .collect();
+    let service = app.settings.claude_code_keychain_service.clone();
 
     tokio::spawn(async move {
         let result = tokio::task::spawn_blocking(move || {
-            let cc_token = oauth::read_claude_code_access_token().ok()?;
+            let cc_token = oauth::read_claude_code_access_token(&service).ok()?;
             for (name, token) in &oauth_accounts {
                 if *token == cc_token {
                     return Some(name.clone());
@@ -359,22 +666,294 @@ pub fn spawn_detect_logged_in(app: &AppState, tx: &mpsc::UnboundedSender<Event>)
     });
 }
 
-async fn fetch_usage_session_key(session_key: &str, org_id: &str) -> anyhow::Result<UsageData> {
-    let client = http_client();
-    let url = format!(
-        "https://claude.ai/api/organizations/{}/usage",
-        org_id
-    );
+/// Keep an imported account in step with Claude Code: if Claude Code's keychain holds
+/// a token none of our accounts have (it refreshed externally), identify its owner and,
+/// when that account is tracked here, adopt the new credential.
+pub fn spawn_claude_code_sync(app: &AppState, tx: &mpsc::UnboundedSender<Event>) {
+    /// Last token identified and its owner, so a login that isn't tracked here
+    /// costs one profile request per token rather than one per sync interval.
+    static CLAUDE_CODE_OWNER: Mutex<Option<(String, oauth::OAuthProfile)>> = Mutex::new(None);
 
-    let resp = client
-        .get(&url)
+    let tx = tx.clone();
+    let known: Vec<SyncCandidate> = app
+        .accounts
+        .iter()
+        .filter(|a| a.config.auth_method == AuthMethod::OAuth)
+        .map(|a| SyncCandidate {
+            name: a.config.name.clone(),
+            org_id: a.config.org_id.clone(),
+            token: a
+                .cached_token
+                .as_deref()
+                .map(oauth::normalize_stored_token)
+                .unwrap_or_default(),
+        })
+        .collect();
+    if known.is_empty() {
+        return;
+    }
+    let service = app.settings.claude_code_keychain_service.clone();
+
+    tokio::spawn(async move {
+        let read = move || oauth::read_claude_code_credential(&service);
+        let raw = match tokio::task::spawn_blocking(read).await {
+            Ok(Ok(raw)) => raw,
+            _ => return,
+        };
+        let cc_token = oauth::normalize_stored_token(&raw);
+        let lookup = |token: String| async move { oauth::fetch_profile(&token).await };
+        if let Some(name) = claude_code_sync_target(&known, &cc_token, &CLAUDE_CODE_OWNER, lookup).await {
+            eprintln!("[sync] Claude Code token changed for {name}, adopting it");
+            let _ = tx.send(Event::ClaudeCodeSynced {
+                account_name: name,
+                raw_credential: raw,
+            });
+        }
+    });
+}
+
+/// An OAuth account Claude Code's token might belong to.
+struct SyncCandidate {
+    name: String,
+    org_id: String,
+    /// Normalized cached token; empty if none.
+    token: String,
+}
+
+/// The tracked account `cc_token` belongs to (see `owns_profile`), or `None` if an
+/// account already holds it or its owner isn't tracked. Owners are looked up with
+/// `lookup_owner` and remembered in `owner_cache`; a failed lookup isn't cached, so
+/// it's retried.
+async fn claude_code_sync_target<F, Fut>(
+    known: &[SyncCandidate],
+    cc_token: &str,
+    owner_cache: &Mutex<Option<(String, oauth::OAuthProfile)>>,
+    lookup_owner: F,
+) -> Option<String>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<oauth::OAuthProfile>>,
+{
+    if known.iter().any(|account| account.token == cc_token) {
+        return None; // already in sync
+    }
+    let cached = owner_cache
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(token, _)| token == cc_token)
+        .map(|(_, owner)| owner.clone());
+    let owner = match cached {
+        Some(owner) => owner,
+        None => {
+            let owner = lookup_owner(cc_token.to_string()).await.ok()?;
+            *owner_cache.lock().unwrap() = Some((cc_token.to_string(), owner.clone()));
+            owner
+        }
+    };
+    known
+        .iter()
+        .find(|account| owns_profile(&account.name, &account.org_id, &owner))
+        .map(|account| account.name.clone())
+}
+
+/// Run the user's `on_swap_command` for the newly active account, through the shell
+/// so it can carry its own arguments. The account name and org_id are passed as `$1`
+/// and `$2` and as `CLAUDE_TRACKER_ACCOUNT` / `CLAUDE_TRACKER_ORG_ID`. Output is
+/// discarded — it would draw over the TUI.
+pub fn spawn_swap_hook(
+    command: String,
+    account: &AccountConfig,
+    tx: &mpsc::UnboundedSender<Event>,
+) {
+    let tx = tx.clone();
+    let account_name = account.name.clone();
+    let org_id = account.org_id.clone();
+    tokio::spawn(async move {
+        let result = run_swap_hook(&command, &account_name, &org_id).await;
+        let _ = tx.send(Event::SwapHookFinished {
+            account_name,
+            result,
+        });
+    });
+}
+
+pub(crate) async fn run_swap_hook(command: &str, account_name: &str, org_id: &str) -> Result<(), String> {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", command, account_name, org_id]);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", command, "sh", account_name, org_id]);
+        cmd
+    };
+    let status = cmd
+        .env("CLAUDE_TRACKER_ACCOUNT", account_name)
+        .env("CLAUDE_TRACKER_ORG_ID", org_id)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .await
+        .map_err(|e| format!("could not run: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(match status.code() {
+            Some(code) => format!("exited with status {code}"),
+            None => "killed by a signal".to_string(),
+        })
+    }
+}
+
+/// Best-effort desktop notification: `osascript` on macOS, `notify-send` elsewhere.
+/// Failures (e.g. no notification daemon) are ignored.
+pub fn notify_desktop(title: &str, body: &str) {
+    #[cfg(target_os = "macos")]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("osascript");
+        cmd.args(["-e", &format!("display notification {body:?} with title {title:?}")]);
+        cmd
+    };
+    #[cfg(not(target_os = "macos"))]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("notify-send");
+        cmd.args([title, body]);
+        cmd
+    };
+    let _ = cmd
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+}
+
+/// claude.ai's usage page. It shows whichever org the browser session has selected;
+/// there's no URL parameter to pick one.
+pub const USAGE_PAGE_URL: &str = "https://claude.ai/settings/usage";
+
+/// Open `url` in the default browser. Over SSH a browser would open on the wrong
+/// machine, if at all, so that's reported as an error too.
+pub fn open_in_browser(url: &str) -> Result<(), String> {
+    if std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some() {
+        return Err("remote session".to_string());
+    }
+    webbrowser::open(url).map_err(|e| e.to_string())
+}
+
+/// Check a session key and org_id with one usage fetch before the account is saved.
+/// On a 403/404 the key's org list tells a wrong org_id apart from a bad key.
+pub fn spawn_validate_account(
+    settings: &Settings,
+    session_key: String,
+    org_id: String,
+    tx: &mpsc::UnboundedSender<Event>,
+) {
+    let tx = tx.clone();
+    let options = FetchOptions::from_settings(settings);
+    tokio::spawn(async move {
+        let result = validate_session_account(&session_key, &org_id, &options).await;
+        let _ = tx.send(Event::AccountValidated { result });
+    });
+}
+
+const BAD_SESSION_KEY: &str = "Session key rejected — check it's the full sessionKey cookie";
+
+async fn validate_session_account(
+    session_key: &str,
+    org_id: &str,
+    options: &FetchOptions,
+) -> Result<(), String> {
+    let err = match fetch_session_usage_body(session_key, org_id, options).await {
+        Ok(_) => return Ok(()),
+        Err(e) => e,
+    };
+    match http_status(&format!("{err:#}")) {
+        Some(401) => Err(BAD_SESSION_KEY.to_string()),
+        Some(403 | 404) => match fetch_session_orgs(session_key, options).await {
+            Ok(orgs) if !orgs.iter().any(|org| org.uuid == org_id) => Err(format!(
+                "Session key works, but it has no org '{org_id}' ({} available)",
+                orgs.len()
+            )),
+            Ok(_) => Err(format!("Org found, but usage fetch failed: {}", humanize_error(&err))),
+            Err(_) => Err(BAD_SESSION_KEY.to_string()),
+        },
+        _ => Err(format!("Couldn't check: {}", humanize_error(&err))),
+    }
+}
+
+/// The status code from an "HTTP 404 Not Found" error message.
+fn http_status(msg: &str) -> Option<u16> {
+    let rest = &msg[msg.find("HTTP ")? + 5..];
+    rest.get(..3)?.parse().ok()
+}
+
+/// Look up the orgs a session key belongs to, for the Add dialog's org picker (Ctrl+O).
+pub fn spawn_lookup_orgs(settings: &Settings, session_key: String, tx: &mpsc::UnboundedSender<Event>) {
+    let tx = tx.clone();
+    let options = FetchOptions::from_settings(settings);
+    tokio::spawn(async move {
+        let result = lookup_orgs(&session_key, &options).await;
+        let _ = tx.send(Event::OrgsFound { result });
+    });
+}
+
+async fn lookup_orgs(session_key: &str, options: &FetchOptions) -> Result<Vec<OrgChoice>, String> {
+    match fetch_session_orgs(session_key, options).await {
+        Ok(orgs) if orgs.is_empty() => Err("No organizations found for this session key".to_string()),
+        Ok(orgs) => Ok(orgs),
+        Err(e) if http_status(&format!("{e:#}")) == Some(401) => Err(BAD_SESSION_KEY.to_string()),
+        Err(e) => Err(format!("Org lookup failed: {}", humanize_error(&e))),
+    }
+}
+
+/// The orgs a session key belongs to.
+async fn fetch_session_orgs(session_key: &str, options: &FetchOptions) -> anyhow::Result<Vec<OrgChoice>> {
+    let url = api_url(options.api_base.as_deref(), SESSION_API_BASE, "/api/organizations");
+    let resp = session_request(&url, session_key, options).send().await?;
+    let status = resp.status();
+    if !status.is_success() {
+        anyhow::bail!("HTTP {}", status.as_u16());
+    }
+    let orgs: Vec<serde_json::Value> = resp.json().await?;
+    Ok(orgs
+        .iter()
+        .filter_map(|org| {
+            let uuid = org.get("uuid")?.as_str()?.to_string();
+            let name = org.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string();
+            Some(OrgChoice { uuid, name })
+        })
+        .collect())
+}
+
+/// A claude.ai GET with the browser-like headers session-key endpoints expect.
+fn session_request(url: &str, session_key: &str, options: &FetchOptions) -> reqwest::RequestBuilder {
+    let request = http_client()
+        .get(url)
         .header("Cookie", format!("sessionKey={}", session_key))
         .header("Accept", "application/json")
         .header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.3 Safari/605.1.15")
         .header("Referer", "https://claude.ai/")
-        .timeout(Duration::from_secs(10))
-        .send()
-        .await?;
+        .timeout(Duration::from_secs(10));
+    options.apply_headers(request)
+}
+
+async fn fetch_session_usage_body(
+    session_key: &str,
+    org_id: &str,
+    options: &FetchOptions,
+) -> anyhow::Result<serde_json::Value> {
+    let url = api_url(
+        options.api_base.as_deref(),
+        SESSION_API_BASE,
+        &format!("/api/organizations/{}/usage", org_id),
+    );
+
+    let resp = session_request(&url, session_key, options).send().await?;
 
     let status = resp.status();
     if !status.is_success() {
@@ -399,25 +978,502 @@ async fn fetch_usage_session_key(session_key: &str, org_id: &str) -> anyhow::Res
         ));
     }
 
-    let body: serde_json::Value = resp.json().await?;
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    let raw_body = resp.text().await?;
+    parse_json_body(content_type.as_deref(), &raw_body)
+}
 
-    let five_hour = body
-        .get("five_hour")
-        .ok_or_else(|| anyhow::anyhow!("Missing five_hour field"))?;
+/// Parse a response body as JSON, rejecting HTML pages (e.g. a Cloudflare challenge)
+/// with a readable error instead of an opaque serde failure.
+fn parse_json_body(content_type: Option<&str>, body: &str) -> anyhow::Result<serde_json::Value> {
+    let is_html = content_type.is_some_and(|ct| ct.contains("text/html"))
+        || body.trim_start().starts_with('<');
+    let is_json = content_type.is_none_or(|ct| ct.contains("json"));
 
-    let utilization = oauth::parse_utilization(five_hour);
-    let resets_at = oauth::parse_resets_at(five_hour);
+    if is_html || !is_json {
+        let preview: String = body.chars().take(500).collect();
+        eprintln!(
+            "[session/usage] Non-JSON response | content-type: {} | body: {preview}",
+            content_type.unwrap_or("none"),
+        );
+        if is_html {
+            return Err(anyhow::anyhow!(
+                "Got HTML, not JSON — session key may be invalid or you're being challenged"
+            ));
+        }
+        return Err(anyhow::anyhow!(
+            "Got {}, not JSON",
+            content_type.unwrap_or("an unknown content type")
+        ));
+    }
 
-    let (weekly_utilization, weekly_resets_at) = body
-        .get("seven_day")
-        .filter(|v| !v.is_null())
-        .map(|seven_day| (Some(oauth::parse_utilization(seven_day)), oauth::parse_resets_at(seven_day)))
-        .unwrap_or((None, None));
+    Ok(serde_json::from_str(body)?)
+}
 
-    Ok(UsageData {
-        utilization,
-        resets_at,
-        weekly_utilization,
-        weekly_resets_at,
-    })
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    const CLOUDFLARE_PAGE: &str = "<!DOCTYPE html><html><head><title>Just a moment...</title></head>\
+        <body>Checking your browser</body></html>";
+
+    #[test]
+    fn html_content_type_is_rejected_with_clear_message() {
+        let err = parse_json_body(Some("text/html; charset=UTF-8"), CLOUDFLARE_PAGE).unwrap_err();
+        assert!(format!("{err}").starts_with("Got HTML, not JSON"));
+    }
+
+    #[test]
+    fn html_body_without_content_type_is_rejected() {
+        let err = parse_json_body(None, CLOUDFLARE_PAGE).unwrap_err();
+        assert!(format!("{err}").starts_with("Got HTML, not JSON"));
+    }
+
+    #[test]
+    fn html_body_mislabelled_as_json_is_rejected() {
+        let err = parse_json_body(Some("application/json"), CLOUDFLARE_PAGE).unwrap_err();
+        assert!(format!("{err}").starts_with("Got HTML, not JSON"));
+    }
+
+    #[test]
+    fn other_content_types_are_named_in_the_error() {
+        let err = parse_json_body(Some("text/plain"), "upstream timeout").unwrap_err();
+        assert_eq!(format!("{err}"), "Got text/plain, not JSON");
+    }
+
+    #[test]
+    fn mask_secrets_hides_credential_like_keys_recursively() {
+        let mut body = serde_json::json!({
+            "five_hour": {"utilization": 10},
+            "session_key": "sk-ant-secret",
+            "nested": [{"access_token": "tok", "resets_at": null}],
+        });
+        mask_secrets(&mut body);
+        assert_eq!(body["session_key"], "***");
+        assert_eq!(body["nested"][0]["access_token"], "***");
+        assert_eq!(body["five_hour"]["utilization"], 10);
+        assert!(body["nested"][0]["resets_at"].is_null());
+    }
+
+    // -------------------------------------------------------------------------
+    // Canned-response HTTP server: fetches are pointed at it through the
+    // api_base override, so the full request/parse path runs without network.
+    // -------------------------------------------------------------------------
+    pub(crate) async fn serve_once(status: &str, content_type: &str, body: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 8192];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+        format!("http://{addr}")
+    }
+
+    /// Like `serve_once`, for a request that's retried: answers one connection per
+    /// `(status, body)` in order, all as JSON.
+    async fn serve_each(responses: &[(&str, &str)]) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let responses: Vec<String> = responses
+            .iter()
+            .map(|(status, body)| {
+                format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+            })
+            .collect();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    fn options_for(base: String) -> FetchOptions {
+        FetchOptions {
+            api_base: Some(base),
+            allow_missing_five_hour: false,
+            headers: BTreeMap::new(),
+            keychain_service: oauth::DEFAULT_KEYCHAIN_SERVICE.to_string(),
+        }
+    }
+
+    #[test]
+    fn header_overrides_skip_invalid_entries() {
+        let mut headers = BTreeMap::new();
+        headers.insert("User-Agent".to_string(), "custom/1.0".to_string());
+        headers.insert("bad header".to_string(), "x".to_string());
+        headers.insert("X-Newline".to_string(), "a\nb".to_string());
+
+        let map = extra_header_map(&headers);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["user-agent"], "custom/1.0");
+    }
+
+    #[tokio::test]
+    async fn session_fetch_parses_usage_from_server() {
+        let base = serve_once(
+            "200 OK",
+            "application/json",
+            r#"{"five_hour":{"utilization":37,"resets_at":"2030-01-01T00:00:00Z"},"seven_day":null}"#,
+        )
+        .await;
+        let usage = fetch_account_usage("org", &AuthMethod::SessionKey, Some("sk"), &options_for(base))
+            .await
+            .unwrap();
+        assert_eq!(usage.utilization, 37);
+        assert!(usage.resets_at.is_some());
+        assert_eq!(usage.weekly_utilization, None);
+    }
+
+    #[tokio::test]
+    async fn session_fetch_html_challenge_is_reported() {
+        let base = serve_once("200 OK", "text/html", CLOUDFLARE_PAGE).await;
+        let err = fetch_account_usage("org", &AuthMethod::SessionKey, Some("sk"), &options_for(base))
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Got HTML, not JSON"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn oauth_fetch_unauthorized_is_humanized() {
+        let base = serve_once("401 Unauthorized", "application/json", r#"{"error":"expired"}"#).await;
+        let err = fetch_account_usage("org", &AuthMethod::OAuth, Some("tok"), &options_for(base))
+            .await
+            .unwrap_err();
+        assert_eq!(err, "Expired — re-import (i)");
+    }
+
+    #[tokio::test]
+    async fn oauth_fetch_malformed_body_is_an_error() {
+        let base = serve_once("200 OK", "application/json", "{not json").await;
+        let result = fetch_account_usage("org", &AuthMethod::OAuth, Some("tok"), &options_for(base)).await;
+        assert!(result.is_err());
+    }
+
+    /// Like `serve_once`, but answers successive connections with successive responses.
+    async fn serve_in_order(responses: &[(&str, &str)]) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let responses: Vec<String> = responses
+            .iter()
+            .map(|(status, body)| {
+                format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+            })
+            .collect();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn validation_tells_wrong_org_from_bad_key() {
+        let base = serve_in_order(&[
+            ("404 Not Found", r#"{"error":"not found"}"#),
+            ("200 OK", r#"[{"uuid":"org-real","name":"Personal"}]"#),
+        ])
+        .await;
+        let err = validate_session_account("sk", "org-typo", &options_for(base)).await.unwrap_err();
+        assert_eq!(err, "Session key works, but it has no org 'org-typo' (1 available)");
+
+        let base = serve_once("401 Unauthorized", "application/json", "{}").await;
+        let err = validate_session_account("sk", "org-real", &options_for(base)).await.unwrap_err();
+        assert_eq!(err, BAD_SESSION_KEY);
+
+        let base = serve_once("200 OK", "application/json", r#"{"five_hour":null}"#).await;
+        assert_eq!(validate_session_account("sk", "org-real", &options_for(base)).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn org_lookup_lists_orgs_or_explains_failure() {
+        let base = serve_once(
+            "200 OK",
+            "application/json",
+            r#"[{"uuid":"org-a","name":"Personal"},{"uuid":"org-b"},{"name":"no uuid"}]"#,
+        )
+        .await;
+        let orgs = lookup_orgs("sk", &options_for(base)).await.unwrap();
+        assert_eq!(
+            orgs,
+            vec![
+                OrgChoice { uuid: "org-a".to_string(), name: "Personal".to_string() },
+                OrgChoice { uuid: "org-b".to_string(), name: String::new() },
+            ]
+        );
+
+        let base = serve_once("401 Unauthorized", "application/json", "{}").await;
+        assert_eq!(lookup_orgs("sk", &options_for(base)).await.unwrap_err(), BAD_SESSION_KEY);
+
+        let base = serve_once("200 OK", "application/json", "[]").await;
+        assert!(lookup_orgs("sk", &options_for(base)).await.unwrap_err().contains("No organizations"));
+    }
+
+    #[tokio::test]
+    async fn deadline_reports_hung_account_as_timeout() {
+        // Accepts the connection and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let hung = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let _socket = listener.accept().await;
+            std::future::pending::<()>().await;
+        });
+        let account = AccountState {
+            cached_token: Some("sk".to_string()),
+            ..crate::app::tests::test_account("hung")
+        };
+        let settings = Settings {
+            api_base_url: Some(hung),
+            ..Settings::default()
+        };
+
+        let started = std::time::Instant::now();
+        let results = fetch_all_with_deadline(&[account], &settings, Duration::from_millis(200)).await;
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "hung");
+        assert_eq!(results[0].1.as_ref().unwrap_err(), "Timeout");
+    }
+
+    #[tokio::test]
+    async fn raw_body_is_pretty_printed_and_masked() {
+        let base = serve_once(
+            "200 OK",
+            "application/json",
+            r#"{"five_hour":{"utilization":37},"session_key":"sk-secret"}"#,
+        )
+        .await;
+        let settings = Settings {
+            api_base_url: Some(base),
+            ..Settings::default()
+        };
+        let account = AccountConfig {
+            name: "work".to_string(),
+            org_id: "org".to_string(),
+            ..Default::default()
+        };
+        let body = fetch_raw_body(&settings, &account, "sk").await.unwrap();
+        assert!(body.contains("\n  \"five_hour\": {"), "{body}");
+        assert!(body.contains("\"session_key\": \"***\""), "{body}");
+    }
+
+    #[test]
+    fn masking_keeps_numeric_token_counts() {
+        let mut body = serde_json::json!({
+            "five_hour": {"utilization": 40, "tokens_used": 1200, "tokens_limit": 3000},
+            "access_token": "at-secret",
+        });
+        mask_secrets(&mut body);
+        assert_eq!(body["five_hour"]["tokens_used"], 1200);
+        assert_eq!(body["five_hour"]["tokens_limit"], 3000);
+        assert_eq!(body["access_token"], "***");
+    }
+
+    #[test]
+    fn http_status_reads_code_from_error_text() {
+        assert_eq!(http_status("HTTP 404 Not Found"), Some(404));
+        assert_eq!(http_status("HTTP 429 Too Many Requests (retry-after: 30)"), Some(429));
+        assert_eq!(http_status("connection refused"), None);
+    }
+
+    #[tokio::test]
+    async fn fetch_without_cached_token_never_hits_network() {
+        let options = options_for("http://127.0.0.1:9".to_string());
+        let err = fetch_account_usage("org", &AuthMethod::OAuth, None, &options).await.unwrap_err();
+        assert_eq!(err, "No token cached — re-import (i)");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn swap_hook_gets_account_and_reports_exit_status() {
+        let check = r#"[ "$1" = work ] && [ "$CLAUDE_TRACKER_ORG_ID" = org-1 ]"#;
+        assert_eq!(run_swap_hook(check, "work", "org-1").await, Ok(()));
+        assert_eq!(
+            run_swap_hook("exit 3", "work", "org-1").await,
+            Err("exited with status 3".to_string())
+        );
+    }
+
+    #[test]
+    fn override_is_non_default_unless_it_is_every_accounts_own_host() {
+        let session = crate::app::tests::test_account("session");
+        let oauth = AccountState {
+            config: AccountConfig {
+                auth_method: AuthMethod::OAuth,
+                ..Default::default()
+            },
+            ..crate::app::tests::test_account("oauth")
+        };
+        let both = [session.clone(), oauth.clone()];
+
+        assert!(!is_non_default_api(None, &both));
+        assert!(!is_non_default_api(Some("https://claude.ai/"), std::slice::from_ref(&session)));
+        assert!(!is_non_default_api(Some("https://api.anthropic.com"), std::slice::from_ref(&oauth)));
+        assert!(is_non_default_api(Some("https://claude.ai"), &both), "OAuth requests go to claude.ai");
+        assert!(is_non_default_api(Some("https://staging.example.com"), std::slice::from_ref(&session)));
+    }
+
+    fn candidate(name: &str, org_id: &str, token: &str) -> SyncCandidate {
+        SyncCandidate {
+            name: name.to_string(),
+            org_id: org_id.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    fn profile(email: &str, org_id: &str) -> oauth::OAuthProfile {
+        oauth::OAuthProfile {
+            email: email.to_string(),
+            org_id: org_id.to_string(),
+            plan: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn claude_code_sync_looks_up_an_untracked_login_once() {
+        let known = vec![candidate("work@x.com", "org-w", "old-token")];
+        let cache = Mutex::new(None);
+        let lookups = std::sync::atomic::AtomicUsize::new(0);
+        let lookup = |_: String| {
+            lookups.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async { Ok(profile("personal@x.com", "org-p")) }
+        };
+
+        assert_eq!(claude_code_sync_target(&known, "cc-token", &cache, lookup).await, None);
+        assert_eq!(claude_code_sync_target(&known, "cc-token", &cache, lookup).await, None);
+        assert_eq!(lookups.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        claude_code_sync_target(&known, "newer-token", &cache, lookup).await;
+        assert_eq!(lookups.load(std::sync::atomic::Ordering::SeqCst), 2, "A new token is looked up");
+    }
+
+    #[tokio::test]
+    async fn claude_code_sync_adopts_tracked_owner_and_skips_in_sync_tokens() {
+        let known = vec![candidate("work@x.com", "org-w", "old-token")];
+        let cache = Mutex::new(None);
+        let owner = |_: String| async { Ok(profile("Work@x.com", "org-w")) };
+        let unreachable = |_: String| async { panic!("token already held; no lookup expected") };
+
+        assert_eq!(
+            claude_code_sync_target(&known, "cc-token", &cache, owner).await.as_deref(),
+            Some("work@x.com")
+        );
+        assert_eq!(claude_code_sync_target(&known, "old-token", &cache, unreachable).await, None);
+    }
+
+    #[tokio::test]
+    async fn claude_code_sync_retries_failed_lookups() {
+        let known = vec![candidate("work@x.com", "org-w", "old-token")];
+        let cache = Mutex::new(None);
+        let failing = |_: String| async { Err(anyhow::anyhow!("HTTP 500")) };
+        assert_eq!(claude_code_sync_target(&known, "cc-token", &cache, failing).await, None);
+        assert!(cache.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn claude_code_sync_picks_the_same_email_account_in_the_tokens_org() {
+        let known = vec![
+            candidate("alice@x.com", "org-personal", "personal-token"),
+            candidate("alice@x.com (org-team)", "org-team", "team-token"),
+        ];
+        let cache = Mutex::new(None);
+        let team = |_: String| async { Ok(profile("alice@x.com", "org-team")) };
+
+        assert_eq!(
+            claude_code_sync_target(&known, "new-team-token", &cache, team).await.as_deref(),
+            Some("alice@x.com (org-team)")
+        );
+    }
+
+    #[tokio::test]
+    async fn rejected_oauth_token_is_retried_once_with_claude_codes_credential() {
+        let ok = r#"{"five_hour":{"utilization":12}}"#;
+        let base = serve_each(&[("401 Unauthorized", "{}"), ("200 OK", ok)]).await;
+        let newer = || async { Some("fresh-token".to_string()) };
+
+        let (result, adopted) =
+            fetch_retrying_rejected("org", &AuthMethod::OAuth, Some("stale-token"), &options_for(base), newer).await;
+        assert_eq!(result.unwrap().utilization, 12);
+        assert_eq!(adopted.as_deref(), Some("fresh-token"));
+    }
+
+    #[tokio::test]
+    async fn rejected_token_without_a_newer_one_keeps_the_error() {
+        let base = serve_each(&[("401 Unauthorized", "{}")]).await;
+        let none = || async { None };
+
+        let (result, adopted) =
+            fetch_retrying_rejected("org", &AuthMethod::OAuth, Some("stale-token"), &options_for(base), none).await;
+        assert_eq!(result.unwrap_err(), EXPIRED_ERROR);
+        assert_eq!(adopted, None);
+    }
+
+    #[tokio::test]
+    async fn rejected_session_key_is_not_retried() {
+        let base = serve_each(&[("401 Unauthorized", "{}")]).await;
+        let unexpected = || async { panic!("session keys don't come from Claude Code") };
+
+        let (result, adopted) =
+            fetch_retrying_rejected("org", &AuthMethod::SessionKey, Some("sk"), &options_for(base), unexpected).await;
+        assert_eq!(result.unwrap_err(), EXPIRED_ERROR);
+        assert_eq!(adopted, None);
+    }
+
+    #[test]
+    fn same_email_in_two_orgs_only_matches_its_own_org() {
+        let profile = |org_id: &str| oauth::OAuthProfile {
+            email: "Alice@x.com".to_string(),
+            org_id: org_id.to_string(),
+            plan: None,
+        };
+        // As imported: the second org's account got the org suffix
+        assert!(owns_profile("alice@x.com", "org-personal", &profile("org-personal")));
+        assert!(owns_profile("alice@x.com (org-team)", "org-team", &profile("org-team")));
+        assert!(!owns_profile("alice@x.com", "org-personal", &profile("org-team")));
+        assert!(!owns_profile("alice@x.com (org-team)", "org-team", &profile("org-personal")));
+        assert!(!owns_profile("alice@x.com.au", "org-personal", &profile("org-personal")));
+    }
+
+    #[test]
+    fn json_body_parses() {
+        let body = r#"{"five_hour":{"utilization":42}}"#;
+        let value = parse_json_body(Some("application/json"), body).unwrap();
+        assert_eq!(value["five_hour"]["utilization"], 42);
+    }
 }
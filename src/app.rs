@@ -1,4 +1,6 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -6,14 +8,93 @@ use tokio::sync::mpsc;
 
 use crate::config::{self, AccountConfig, AuthMethod, Config};
 use crate::event::{Event, OAuthImportData};
-use crate::keyring_store::KeyringBackend;
+use crate::keyring_store::{AuditingKeyring, KeyringBackend};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct UsageData {
     pub utilization: u32,
     pub resets_at: Option<DateTime<Utc>>,
     pub weekly_utilization: Option<u32>,
     pub weekly_resets_at: Option<DateTime<Utc>>,
+    /// Absolute counts for the 5h window, when the response includes them.
+    pub tokens: Option<TokenCounts>,
+    /// Absolute counts for the 7d window, when the response includes them.
+    pub weekly_tokens: Option<TokenCounts>,
+    /// How the 7d window resets, when the response says; None means assume rolling.
+    pub weekly_cadence: Option<WeeklyCadence>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeeklyCadence {
+    /// A rolling 7 days from first use.
+    Rolling,
+    /// A fixed calendar boundary (e.g. every Monday).
+    Calendar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenCounts {
+    pub used: u64,
+    pub limit: u64,
+}
+
+/// A window's utilization as every view shows it; see [`UsageData::five_hour_reading`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowReading {
+    Pct(u32),
+    /// Reset since the last fetch with `recalculate_after_reset` on: unknown until
+    /// the next fetch.
+    Recalculating,
+}
+
+impl UsageData {
+    /// 5h utilization as of `now` for data fetched at `fetched`. A window that reset
+    /// after the fetch reads 0% (or `Recalculating` with `recalculate`); data fetched
+    /// after the reset is current even while the API still reports the old `resets_at`.
+    pub fn five_hour_reading(
+        &self,
+        fetched: Option<DateTime<Utc>>,
+        recalculate: bool,
+        now: DateTime<Utc>,
+    ) -> WindowReading {
+        window_reading(self.utilization, self.resets_at, fetched, recalculate, now)
+    }
+
+    /// 7d utilization, with the same reset rule as the 5h window.
+    pub fn weekly_reading(
+        &self,
+        fetched: Option<DateTime<Utc>>,
+        recalculate: bool,
+        now: DateTime<Utc>,
+    ) -> Option<WindowReading> {
+        let weekly = self.weekly_utilization?;
+        Some(window_reading(weekly, self.weekly_resets_at, fetched, recalculate, now))
+    }
+}
+
+fn window_reading(
+    pct: u32,
+    resets_at: Option<DateTime<Utc>>,
+    fetched: Option<DateTime<Utc>>,
+    recalculate: bool,
+    now: DateTime<Utc>,
+) -> WindowReading {
+    let reset_since_fetch = resets_at.is_some_and(|r| now > r && fetched.is_none_or(|f| f <= r));
+    match (reset_since_fetch, recalculate) {
+        (false, _) => WindowReading::Pct(pct),
+        (true, false) => WindowReading::Pct(0),
+        (true, true) => WindowReading::Recalculating,
+    }
+}
+
+impl WindowReading {
+    /// The percentage, counting a pending recalculation as the 0% the reset implies.
+    pub fn pct(self) -> u32 {
+        match self {
+            WindowReading::Pct(pct) => pct,
+            WindowReading::Recalculating => 0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -31,6 +112,124 @@ pub struct AccountState {
     pub last_fetched: Option<DateTime<Utc>>,
     /// Cached token loaded from keyring at startup/import — avoids keychain prompts on every poll.
     pub cached_token: Option<String>,
+    pub stats: FetchStats,
+}
+
+/// Per-session fetch counters for the details overlay.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FetchStats {
+    pub attempts: u32,
+    pub successes: u32,
+    /// Summed over every timed attempt; divide by `timed` for the mean.
+    pub total_latency: Duration,
+    pub timed: u32,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+impl FetchStats {
+    /// Percentage of attempts that succeeded, None before the first one.
+    pub fn success_rate(&self) -> Option<u32> {
+        (self.attempts > 0).then(|| self.successes * 100 / self.attempts)
+    }
+
+    pub fn average_latency(&self) -> Option<Duration> {
+        (self.timed > 0).then(|| self.total_latency / self.timed)
+    }
+}
+
+/// What the terminal can display, detected once at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorSupport {
+    TrueColor,
+    /// The 256-color palette the color ramp is designed for.
+    #[default]
+    Ansi256,
+    /// Only the 16 basic colors; indexed colors render as near-identical shades.
+    Basic,
+}
+
+impl ColorSupport {
+    pub fn detect() -> Self {
+        Self::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorSupport::TrueColor;
+        }
+        match term {
+            Some(t) if t.contains("256color") || t.contains("direct") => ColorSupport::Ansi256,
+            // Terminals that don't set TERM (e.g. Windows Terminal) handle 256 colors
+            None => ColorSupport::Ansi256,
+            Some(_) => ColorSupport::Basic,
+        }
+    }
+}
+
+pub const SMOOTH_RENDER_INTERVAL: Duration = Duration::from_millis(33);
+pub const LOW_POWER_RENDER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 5h utilization at or above which an Ok account sorts with the attention group.
+pub const HIGH_UTILIZATION_PCT: u32 = 80;
+
+/// Status for an account whose keyring entry has disappeared (e.g. the keychain was
+/// cleared) while it's still in the config.
+pub const NO_CREDENTIAL_ERROR: &str = "No credential — re-add";
+
+impl AccountState {
+    /// A configured account that hasn't been fetched yet.
+    fn new(config: AccountConfig, cached_token: Option<String>) -> Self {
+        Self {
+            config,
+            usage: None,
+            status: AccountStatus::Idle,
+            last_fetched: None,
+            cached_token,
+            stats: FetchStats::default(),
+        }
+    }
+
+    /// Flagged by the startup drift check and not re-added since. Fetching would only
+    /// replace the status with a vaguer error.
+    /// [`UsageData::five_hour_reading`] for the last fetched usage.
+    pub fn five_hour_reading(&self, recalculate: bool, now: DateTime<Utc>) -> Option<WindowReading> {
+        let usage = self.usage.as_ref()?;
+        Some(usage.five_hour_reading(self.last_fetched, recalculate, now))
+    }
+
+    /// [`UsageData::weekly_reading`] for the last fetched usage.
+    pub fn weekly_reading(&self, recalculate: bool, now: DateTime<Utc>) -> Option<WindowReading> {
+        self.usage.as_ref()?.weekly_reading(self.last_fetched, recalculate, now)
+    }
+
+    pub fn credential_missing(&self) -> bool {
+        self.cached_token.is_none()
+            && matches!(&self.status, AccountStatus::Error(e) if e == NO_CREDENTIAL_ERROR)
+    }
+
+    /// Triage rank for status-first sorting; lower sorts first. `sort_pct` is the
+    /// utilization the row was last sorted at, kept while the live value stays within
+    /// `threshold` of it.
+    fn severity(&self, now: DateTime<Utc>, sort_pct: Option<u32>, threshold: u32) -> u8 {
+        match &self.status {
+            AccountStatus::Error(_) => 0,
+            AccountStatus::Ok => match self.five_hour_reading(false, now) {
+                Some(reading) => {
+                    let live = reading.pct();
+                    let pct = match sort_pct {
+                        Some(prev) if live.abs_diff(prev) <= threshold => prev,
+                        _ => live,
+                    };
+                    if pct >= HIGH_UTILIZATION_PCT { 1 } else { 2 }
+                }
+                None => 2,
+            },
+            AccountStatus::Idle => 3,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -40,7 +239,37 @@ pub enum AppMode {
     EditAccount(usize),
     ConfirmDelete,
     ConfirmSwap,
+    /// Second confirm when the swap target's last fetch failed.
+    ConfirmSwapErrored,
+    /// Full-screen gauges for one account (`z`), by name so a delete or reload can't
+    /// swap in another account; Esc returns to the table.
+    Focus(String),
+    /// Release highlights after an update (`AppState::whats_new`); any key dismisses.
+    WhatsNew,
+    /// A reload (Ctrl+R) would drop accounts the last failed save never wrote.
+    ConfirmReload,
     Help,
+    /// Read-only overlay with everything known about the selected account.
+    Details,
+    /// Scrollable pretty-printed API response (see `AppState::raw_json`).
+    RawJson,
+    /// Paste a Claude Code credential JSON blob to create an OAuth account.
+    PasteCredential,
+    /// Paste a fresh credential JSON over the named OAuth account's keyring entry.
+    RepairCredential(String),
+    /// One-off raw fetch for the selected account with a different org_id; the
+    /// account's saved org_id is left alone.
+    OrgOverride,
+    /// Recent keyring calls recorded by `keyring_audit` (see `AppState::keyring_audit`).
+    KeyringLog,
+}
+
+/// Raw usage response fetched for debugging, shown in the raw JSON overlay.
+#[derive(Debug, Clone)]
+pub struct RawJsonView {
+    pub account_name: String,
+    pub body: String,
+    pub scroll: u16,
 }
 
 #[derive(Debug, Default)]
@@ -49,38 +278,134 @@ pub struct InputFields {
     pub session_key: String,
     pub org_id: String,
     pub focused_field: usize,
+    /// Pre-save checks of the dialog's values (swapped fields, `validate_on_add`);
+    /// any edit resets it.
+    pub validation: AddValidation,
+    /// Swapped-field warning shown by the last Enter; the next Enter goes on to
+    /// `validation` and saving. Any edit resets it.
+    pub swap_warning: Option<&'static str>,
+    /// Org lookup from the session key (Ctrl+O); any edit cancels it.
+    pub org_lookup: OrgLookup,
+}
+
+/// One org a session key belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrgChoice {
+    pub uuid: String,
+    /// Display name; may be empty.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OrgLookup {
+    #[default]
+    Idle,
+    Pending,
+    /// Picker over the session key's orgs; Enter fills the Org ID field.
+    Choosing { orgs: Vec<OrgChoice>, selected: usize },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum AddValidation {
+    #[default]
+    Unchecked,
+    Pending,
+    /// The check failed; the next Enter saves anyway.
+    Failed(String),
 }
 
 impl InputFields {
+    /// Number of editable fields. Everything that walks the fields is sized by this,
+    /// so adding one is a compile error until `fields`/`fields_mut` and the dialog
+    /// labels are updated too.
+    pub const FIELD_COUNT: usize = 3;
+
     pub fn clear(&mut self) {
-        self.name.clear();
-        self.session_key.clear();
-        self.org_id.clear();
+        for field in self.fields_mut() {
+            field.clear();
+        }
         self.focused_field = 0;
+        self.validation = AddValidation::Unchecked;
+        self.swap_warning = None;
+        self.org_lookup = OrgLookup::Idle;
+    }
+
+    /// Fields in dialog order.
+    pub fn fields(&self) -> [&String; Self::FIELD_COUNT] {
+        [&self.name, &self.session_key, &self.org_id]
+    }
+
+    fn fields_mut(&mut self) -> [&mut String; Self::FIELD_COUNT] {
+        [&mut self.name, &mut self.session_key, &mut self.org_id]
     }
 
     pub fn current_field_mut(&mut self) -> &mut String {
-        match self.focused_field {
-            0 => &mut self.name,
-            1 => &mut self.session_key,
-            2 => &mut self.org_id,
-            _ => unreachable!("focused_field must be 0..2, got {}", self.focused_field),
+        self.validation = AddValidation::Unchecked;
+        self.swap_warning = None;
+        self.org_lookup = OrgLookup::Idle;
+        let index = self.focused_field % Self::FIELD_COUNT;
+        let [name, session_key, org_id] = self.fields_mut();
+        match index {
+            0 => name,
+            1 => session_key,
+            _ => org_id,
         }
     }
 
     pub fn next_field(&mut self) {
-        self.focused_field = (self.focused_field + 1) % 3;
+        self.focused_field = (self.focused_field + 1) % Self::FIELD_COUNT;
     }
 
     pub fn prev_field(&mut self) {
-        self.focused_field = if self.focused_field == 0 {
-            2
-        } else {
-            self.focused_field - 1
-        };
+        self.focused_field = (self.focused_field + Self::FIELD_COUNT - 1) % Self::FIELD_COUNT;
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusLevel {
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    pub set_at: DateTime<Utc>,
+    pub level: StatusLevel,
+}
+
+/// A 5h window seen resetting while the tracker was running.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResetFlash {
+    /// The reset this flash is for, so each reset is announced once.
+    pub resets_at: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+/// A temporary fast-poll boost for one account.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FastPoll {
+    pub until: DateTime<Utc>,
+    pub next_at: DateTime<Utc>,
+    /// A fetch has started and its result hasn't come back yet.
+    pub in_flight: bool,
+}
+
+/// Matches the normal poll floor, so a boost can't poll faster than the config allows.
+const FAST_POLL_INTERVAL_SECS: i64 = config::MIN_POLL_INTERVAL_SECS as i64;
+
+/// How far the wall clock must pull ahead of the monotonic one between ticks to count
+/// as a sleep rather than timer jitter.
+const RESUME_CLOCK_GAP: Duration = Duration::from_secs(30);
+
+/// How long a row flashes after its 5h window resets.
+const RESET_FLASH_SECS: i64 = 6;
+
+/// Startup keyring reads in flight at once. The macOS keychain can prompt per entry,
+/// so it's kept to two: slow reads of already-allowed entries still overlap, but no
+/// more than two prompts queue up at once. Other backends don't prompt per entry.
+const STARTUP_KEYRING_READS: usize = if cfg!(target_os = "macos") { 2 } else { 4 };
+
 pub struct AppState {
     pub accounts: Vec<AccountState>,
     pub selected_index: usize,
@@ -88,32 +413,114 @@ pub struct AppState {
     pub mode: AppMode,
     pub should_quit: bool,
     pub last_poll: Option<DateTime<Utc>>,
-    pub status_message: Option<(String, DateTime<Utc>)>,
+    pub status_message: Option<StatusMessage>,
     pub input_fields: InputFields,
-    pub poll_interval_secs: u64,
+    pub settings: config::Settings,
     pub keyring: Arc<dyn KeyringBackend>,
     /// Which account name matches the token currently in Claude Code's keychain.
     pub logged_in_account: Option<String>,
+    /// When the next automatic poll fires — drives the "retry in" hint on errored rows.
+    pub next_poll_at: Option<DateTime<Utc>>,
+    /// Wall clock and monotonic clock at the last tick, for `detect_resume`.
+    pub last_tick: Option<(DateTime<Utc>, Instant)>,
+    pub raw_json: Option<RawJsonView>,
+    /// Text collected by the paste-credential dialog.
+    pub paste_buffer: String,
+    /// Show only errored accounts (toggled with `E`; not persisted).
+    pub errors_only: bool,
+    /// Accounts polled every `FAST_POLL_INTERVAL_SECS` on top of the normal cycle
+    /// (`B`), by name.
+    pub fast_poll: HashMap<String, FastPoll>,
+    /// Show a shortened org_id column in the table (toggled with `o`; not persisted).
+    pub show_org_column: bool,
+    /// org_id typed into the one-off override prompt (`O`).
+    pub org_override_input: String,
+    /// Keyring call log, present when the `keyring_audit` setting wraps the backend.
+    pub keyring_audit: Option<Arc<AuditingKeyring>>,
+    /// Automatic polling and Claude Code sync are suspended (toggled with Space).
+    /// Manual refreshes still run.
+    pub paused: bool,
+    /// Redraw once a second instead of ~30 times (toggled with `m`). Data only
+    /// changes on poll, so this mostly costs smooth countdowns.
+    pub low_power_render: bool,
+    pub color_support: ColorSupport,
+    /// Rows whose 5h window just reset, by account name.
+    pub reset_flashes: HashMap<String, ResetFlash>,
+    /// Contents of `notes_file` while the notes panel is open.
+    pub notes: Option<String>,
+    /// Lines for the what's-new overlay.
+    pub whats_new: Vec<String>,
+    /// 5h utilization each account was last sorted at, by name (see `resort_threshold_pct`).
+    pub sort_utilization: HashMap<String, u32>,
+    /// 5h utilization of each successful fetch, by name, for the details trend line.
+    pub history: HashMap<String, crate::history::HistoryBuffer>,
+    /// The last config save failed, so memory holds changes the file doesn't.
+    pub unsaved_changes: bool,
+    /// Account names this session last read from or wrote to the config file. An
+    /// account in the file but not here was added by another instance since, and a
+    /// save keeps it rather than overwriting it.
+    pub saved_names: HashSet<String>,
+    /// Settings as this session last read from or wrote to the config file. A setting
+    /// still equal to this wasn't changed here, so a save keeps the file's value for it.
+    pub saved_settings: config::Settings,
+    /// Config read by Ctrl+R, waiting on `ConfirmReload`.
+    pub pending_reload: Option<Config>,
+    /// Why startup keyring reads failed, for entries that exist but couldn't be read
+    /// (e.g. a locked or denied keychain). `check_keyring_drift` reports the first.
+    pub keyring_load_errors: Vec<String>,
+    /// Accounts whose startup read found no keyring entry at all, for
+    /// `check_keyring_drift`; asking again would cost a second keychain prompt.
+    pub keyring_missing: HashSet<String>,
+    /// Accounts whose cached token is newer than their keyring entry, because the write
+    /// of a refreshed token failed. `store_credential` always writes these.
+    pub unsynced_tokens: HashSet<String>,
 }
 
 impl AppState {
     pub fn from_config(config: Config, keyring: Arc<dyn KeyringBackend>) -> Self {
+        // Read a few at a time: one slow entry shouldn't hold up the rest.
+        // Failures leave the token empty; `check_keyring_drift` reports them.
+        let mut tokens: Vec<Result<String, crate::error::TrackerError>> = Vec::new();
+        for batch in config.accounts.chunks(STARTUP_KEYRING_READS) {
+            std::thread::scope(|scope| {
+                let reads: Vec<_> = batch
+                    .iter()
+                    .map(|ac| scope.spawn(|| keyring.get_session_key(&ac.name)))
+                    .collect();
+                tokens.extend(reads.into_iter().map(|read| {
+                    read.join()
+                        .unwrap_or_else(|_| Err(crate::error::TrackerError::Keyring("Keyring read panicked".into())))
+                }));
+            });
+        }
+        let keyring_load_errors = tokens
+            .iter()
+            .filter_map(|token| match token {
+                Err(crate::error::TrackerError::KeyNotFound(_)) | Ok(_) => None,
+                Err(e) => Some(e.to_string()),
+            })
+            .collect();
+        let keyring_missing = config
+            .accounts
+            .iter()
+            .zip(&tokens)
+            .filter(|(_, token)| matches!(token, Err(crate::error::TrackerError::KeyNotFound(_))))
+            .map(|(ac, _)| ac.name.clone())
+            .collect();
         let accounts: Vec<AccountState> = config
             .accounts
             .iter()
-            .map(|ac| {
-                let cached_token = keyring.get_session_key(&ac.name).ok();
-                AccountState {
-                    config: ac.clone(),
-                    usage: None,
-                    status: AccountStatus::Idle,
-                    last_fetched: None,
-                    cached_token,
-                }
-            })
+            .zip(tokens)
+            .map(|(ac, token)| AccountState::new(ac.clone(), token.ok()))
             .collect();
 
-        let active = config.settings.active_account.min(accounts.len().saturating_sub(1));
+        // A name that no longer matches any account falls back to the first one
+        let active = config
+            .settings
+            .active_account
+            .as_deref()
+            .and_then(|name| accounts.iter().position(|a| a.config.name == name))
+            .unwrap_or(0);
 
         Self {
             accounts,
@@ -125,7 +532,31 @@ impl AppState {
             status_message: None,
             input_fields: InputFields::default(),
             logged_in_account: None,
-            poll_interval_secs: config.settings.poll_interval_secs,
+            next_poll_at: None,
+            last_tick: None,
+            raw_json: None,
+            paste_buffer: String::new(),
+            errors_only: false,
+            fast_poll: HashMap::new(),
+            show_org_column: false,
+            org_override_input: String::new(),
+            keyring_audit: None,
+            paused: false,
+            low_power_render: config.settings.low_power_render,
+            color_support: ColorSupport::default(),
+            reset_flashes: HashMap::new(),
+            notes: None,
+            whats_new: Vec::new(),
+            sort_utilization: HashMap::new(),
+            history: HashMap::new(),
+            unsaved_changes: false,
+            pending_reload: None,
+            keyring_load_errors,
+            keyring_missing,
+            unsynced_tokens: HashSet::new(),
+            saved_names: config.accounts.iter().map(|ac| ac.name.clone()).collect(),
+            saved_settings: config.settings.clone(),
+            settings: config.settings,
             keyring,
         }
     }
@@ -136,436 +567,1716 @@ impl AppState {
         account_name: &str,
         result: Result<UsageData, String>,
     ) {
+        if let Some(boost) = self.fast_poll.get_mut(account_name) {
+            boost.in_flight = false;
+        }
         if let Some(account) = self
             .accounts
             .iter_mut()
             .find(|a| a.config.name == account_name)
         {
+            account.stats.attempts += 1;
             match result {
                 Ok(data) => {
+                    let threshold = self.settings.resort_threshold_pct;
+                    match self.sort_utilization.get(account_name) {
+                        Some(&prev) if data.utilization.abs_diff(prev) <= threshold => {}
+                        _ => {
+                            self.sort_utilization.insert(account_name.to_string(), data.utilization);
+                        }
+                    }
+                    self.history
+                        .entry(account_name.to_string())
+                        .or_default()
+                        .push(Utc::now(), data.utilization);
                     account.usage = Some(data);
                     account.status = AccountStatus::Ok;
                     account.last_fetched = Some(Utc::now());
+                    account.stats.successes += 1;
                 }
                 Err(msg) => {
                     account.status = AccountStatus::Error(msg);
+                    account.stats.last_error_at = Some(Utc::now());
                 }
             }
             self.last_poll = Some(Utc::now());
+            if self.errors_only {
+                self.keep_selection_visible();
+            }
         }
         // If account was deleted while fetch was in flight, result and last_poll are
         // both silently discarded — no misleading "Last refresh" in the status bar.
     }
 
-    pub fn clear_stale_messages(&mut self) {
-        if let Some((_, time)) = &self.status_message {
-            if Utc::now().signed_duration_since(*time).num_seconds() > 5 {
-                self.status_message = None;
-            }
+    /// Result of the Add dialog's pre-save check. A pass saves the account and returns
+    /// its name for the first fetch; a failure stays in the dialog with the reason shown.
+    /// Ignored if the dialog was closed or edited while the check ran.
+    pub fn apply_add_validation(&mut self, result: Result<(), String>) -> Option<String> {
+        if self.mode != AppMode::AddAccount || self.input_fields.validation != AddValidation::Pending {
+            return None;
+        }
+        if let Err(msg) = result {
+            self.input_fields.validation = AddValidation::Failed(msg);
+            return None;
         }
+        let name = self.input_fields.name.trim().to_string();
+        let session_key = self.input_fields.session_key.trim().to_string();
+        let org_id = self.input_fields.org_id.trim().to_string();
+        let added = self.add_account(name.clone(), session_key, org_id);
+        self.mode = AppMode::Normal;
+        added.map(|_| name)
     }
 
-    pub fn set_status(&mut self, msg: String) {
-        self.status_message = Some((msg, Utc::now()));
+    /// Result of an org lookup (Ctrl+O). A single org fills the Org ID field directly;
+    /// several open the picker. Ignored if the dialog was closed or edited meanwhile.
+    pub fn apply_org_lookup(&mut self, result: Result<Vec<OrgChoice>, String>) {
+        if !self.in_input_dialog() || self.input_fields.org_lookup != OrgLookup::Pending {
+            return;
+        }
+        self.input_fields.org_lookup = OrgLookup::Idle;
+        match result {
+            Ok(orgs) if orgs.len() == 1 => self.choose_org(&orgs[0]),
+            Ok(orgs) => self.input_fields.org_lookup = OrgLookup::Choosing { orgs, selected: 0 },
+            Err(msg) => self.set_error(msg),
+        }
     }
 
-    fn save_config(&mut self) {
-        let cfg = Config {
-            settings: config::Settings {
-                poll_interval_secs: self.poll_interval_secs,
-                active_account: self.active_account_index,
-            },
-            accounts: self.accounts.iter().map(|a| a.config.clone()).collect(),
-        };
-        if let Err(e) = config::save(&cfg) {
-            self.set_status(format!("Failed to save config: {e}"));
-        }
+    fn choose_org(&mut self, org: &OrgChoice) {
+        self.input_fields.org_id = org.uuid.clone();
+        self.input_fields.validation = AddValidation::Unchecked;
+        self.input_fields.swap_warning = None;
+        self.input_fields.focused_field = InputFields::FIELD_COUNT - 1;
     }
 
-    /// Returns Some(index) on success, None on failure.
-    fn add_account(&mut self, name: String, session_key: String, org_id: String) -> Option<usize> {
-        if self.accounts.iter().any(|a| a.config.name == name) {
-            self.set_status(format!("Account '{}' already exists", name));
-            return None;
+    /// Add one fetch's wall time (including any token refresh) to the account's stats.
+    pub fn record_fetch_latency(&mut self, account_name: &str, elapsed: Duration) {
+        if let Some(account) = self.accounts.iter_mut().find(|a| a.config.name == account_name) {
+            account.stats.total_latency += elapsed;
+            account.stats.timed += 1;
         }
+    }
 
-        if let Err(e) = self.keyring.set_session_key(&name, &session_key) {
-            self.set_status(format!("Keyring error: {e}"));
-            return None;
+    /// Indices into `accounts` in the order rows are displayed.
+    pub fn display_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.accounts.len())
+            .filter(|&i| {
+                !self.errors_only || matches!(self.accounts[i].status, AccountStatus::Error(_))
+            })
+            .collect();
+        if self.settings.sort_status_first {
+            let now = Utc::now();
+            let threshold = self.settings.resort_threshold_pct;
+            order.sort_by_key(|&i| {
+                let account = &self.accounts[i];
+                let sort_pct = self.sort_utilization.get(&account.config.name).copied();
+                account.severity(now, sort_pct, threshold)
+            });
         }
+        order
+    }
 
-        let ac = AccountConfig {
-            name,
-            org_id,
-            auth_method: AuthMethod::SessionKey,
+    /// Move the selection `delta` rows through the displayed order, wrapping at the ends.
+    fn move_selection(&mut self, delta: isize) {
+        let order = self.display_order();
+        if order.is_empty() {
+            return;
+        }
+        let pos = order.iter().position(|&i| i == self.selected_index).unwrap_or(0);
+        // Paged: wrap within the current page
+        let (start, len) = match self.page_size() {
+            Some(size) => {
+                let start = pos / size * size;
+                (start, size.min(order.len() - start))
+            }
+            None => (0, order.len()),
         };
-        self.accounts.push(AccountState {
-            config: ac,
-            usage: None,
-            status: AccountStatus::Idle,
-            last_fetched: None,
-            cached_token: Some(session_key),
-        });
-        self.save_config();
-        self.set_status("Account added".to_string());
-        Some(self.accounts.len() - 1)
+        let next = start + ((pos - start) as isize + delta).rem_euclid(len as isize) as usize;
+        self.selected_index = order[next];
     }
 
-    /// Write new key first. Only delete old key after new key write succeeds.
-    fn update_account(&mut self, index: usize, name: String, session_key: String, org_id: String) {
-        // Immutable borrow to read old name — released before keyring ops
-        let Some(old_name) = self.accounts.get(index).map(|a| a.config.name.clone()) else {
+    fn page_size(&self) -> Option<usize> {
+        self.settings.page_size.filter(|&size| size > 0)
+    }
+
+    /// Zero-based page holding the selection and the page count, when `page_size`
+    /// is set.
+    pub fn page(&self) -> Option<(usize, usize)> {
+        let size = self.page_size()?;
+        let order = self.display_order();
+        let pos = order.iter().position(|&i| i == self.selected_index).unwrap_or(0);
+        Some((pos / size, order.len().div_ceil(size).max(1)))
+    }
+
+    /// Select the first row of the page `delta` pages away, stopping at either end.
+    fn turn_page(&mut self, delta: isize) {
+        let (Some(size), Some((page, pages))) = (self.page_size(), self.page()) else {
             return;
         };
-        let name_changed = old_name != name;
+        let target = (page as isize + delta).clamp(0, pages as isize - 1) as usize;
+        if let Some(&index) = self.display_order().get(target * size) {
+            self.selected_index = index;
+        }
+    }
 
-        // Write new key FIRST -- if this fails, old key is preserved
-        if let Err(e) = self.keyring.set_session_key(&name, &session_key) {
-            self.set_status(format!("Keyring error: {e}"));
+    fn toggle_favorite(&mut self) {
+        let Some(account) = self.accounts.get_mut(self.selected_index) else {
             return;
-        }
+        };
+        account.config.favorite = !account.config.favorite;
+        let msg = if account.config.favorite {
+            format!("'{}' added to favorites", account.config.name)
+        } else {
+            format!("'{}' removed from favorites", account.config.name)
+        };
+        self.save_config();
+        self.set_status(msg);
+    }
 
-        // Only delete old key AFTER new key is safely stored
-        if name_changed {
-            if let Err(e) = self.keyring.delete_session_key(&old_name) {
-                self.set_status(format!("Warning: old key not deleted: {e}"));
-            }
+    /// Select the next favorite after the current row, in display order, wrapping.
+    fn next_favorite(&mut self) {
+        let order = self.display_order();
+        let pos = order.iter().position(|&i| i == self.selected_index).unwrap_or(0);
+        let next = (1..=order.len())
+            .map(|step| order[(pos + step) % order.len()])
+            .find(|&i| self.accounts[i].config.favorite);
+        match next {
+            Some(i) => self.selected_index = i,
+            None => self.set_status("No favorites — mark one with f".to_string()),
         }
+    }
 
-        // Now mutate the account
-        if let Some(account) = self.accounts.get_mut(index) {
-            account.config.name = name;
-            account.config.org_id = org_id;
-            account.cached_token = Some(session_key);
-            account.usage = None;
-            account.status = AccountStatus::Idle;
+    /// Start or stop fast polling for the selected account (`B`).
+    fn toggle_fast_poll(&mut self, now: DateTime<Utc>) {
+        let Some(account) = self.accounts.get(self.selected_index) else {
+            return;
+        };
+        let name = account.config.name.clone();
+        if self.fast_poll.remove(&name).is_some() {
+            self.set_status(format!("Fast polling stopped for '{name}'"));
+            return;
         }
-        self.save_config();
-        self.set_status("Account updated".to_string());
+        let window = self.settings.fast_poll_window_secs;
+        self.fast_poll.insert(
+            name.clone(),
+            FastPoll {
+                until: now + chrono::Duration::seconds(window as i64),
+                next_at: now,
+                in_flight: false,
+            },
+        );
+        self.set_status(format!("Polling '{name}' every {FAST_POLL_INTERVAL_SECS}s for {}m", window.div_ceil(60)));
     }
 
-    fn delete_selected(&mut self) {
-        if self.selected_index < self.accounts.len() {
-            let name = self.accounts[self.selected_index].config.name.clone();
-            if let Err(e) = self.keyring.delete_session_key(&name) {
-                self.set_status(format!("Warning: key not deleted from keyring: {e}"));
+    /// Fast-polled accounts due a fetch, rescheduled for their next one. Boosts that
+    /// have run their window are dropped. An account whose last fetch is still out is
+    /// skipped, and one in error backs off to the regular poll instead of being retried
+    /// every fast interval.
+    pub fn due_fast_polls(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        self.expire_fast_polls(now);
+        let mut due = Vec::new();
+        for (name, boost) in &mut self.fast_poll {
+            if now < boost.next_at || boost.in_flight {
+                continue;
             }
-            self.accounts.remove(self.selected_index);
-
-            if self.accounts.is_empty() {
-                self.selected_index = 0;
-                self.active_account_index = 0;
-            } else {
-                if self.selected_index >= self.accounts.len() {
-                    self.selected_index = self.accounts.len() - 1;
-                }
-                if self.active_account_index >= self.accounts.len() {
-                    self.active_account_index = self.accounts.len() - 1;
-                }
+            boost.next_at = now + chrono::Duration::seconds(FAST_POLL_INTERVAL_SECS);
+            let erroring = self
+                .accounts
+                .iter()
+                .any(|a| a.config.name == *name && matches!(a.status, AccountStatus::Error(_)));
+            if !erroring {
+                boost.in_flight = true;
+                due.push(name.clone());
             }
-            self.save_config();
-            self.set_status("Account deleted".to_string());
         }
+        due
     }
 
-    /// Import an OAuth account from Claude Code. If an account with the same name
-    /// already exists, update its credentials. Otherwise, add a new account.
-    /// Returns the account index on success.
-    pub fn import_oauth_account(&mut self, data: OAuthImportData) -> Option<usize> {
-        // Store the full credential JSON (includes refresh token) in our keyring
-        if let Err(e) = self.keyring.set_session_key(&data.name, &data.raw_credential) {
-            self.set_status(format!("Keyring error: {e}"));
-            return None;
+    /// A full poll just fetched every account, so boosted ones count it as their fast
+    /// fetch rather than being fetched again on the same tick.
+    pub fn defer_fast_polls(&mut self, now: DateTime<Utc>) {
+        for (name, boost) in &mut self.fast_poll {
+            boost.next_at = now + chrono::Duration::seconds(FAST_POLL_INTERVAL_SECS);
+            // `spawn_fetch_all` skips accounts with no credential; no result would clear it
+            boost.in_flight |= self
+                .accounts
+                .iter()
+                .any(|a| a.config.name == *name && !a.credential_missing());
         }
+    }
 
-        // Check if account already exists (by name)
-        if let Some(pos) = self.accounts.iter().position(|a| a.config.name == data.name) {
-            self.accounts[pos].config.org_id = data.org_id;
-            self.accounts[pos].config.auth_method = AuthMethod::OAuth;
-            self.accounts[pos].cached_token = Some(data.raw_credential);
-            self.accounts[pos].usage = None;
-            self.accounts[pos].status = AccountStatus::Idle;
-            self.save_config();
-            self.set_status(format!("Updated OAuth account '{}'", data.name));
-            return Some(pos);
+    /// Drop boosts that have run their window. Runs every tick, so `[fast]` goes away
+    /// on time even while paused or in a dialog, when nothing is fetched.
+    pub fn expire_fast_polls(&mut self, now: DateTime<Utc>) {
+        let expired: Vec<String> = self
+            .fast_poll
+            .iter()
+            .filter(|(_, boost)| now >= boost.until)
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in &expired {
+            self.fast_poll.remove(name);
+            self.set_status(format!("Fast polling ended for '{name}'"));
         }
+    }
 
-        // Add new account
-        let ac = AccountConfig {
-            name: data.name.clone(),
-            org_id: data.org_id,
-            auth_method: AuthMethod::OAuth,
-        };
-        self.accounts.push(AccountState {
-            config: ac,
-            usage: None,
-            status: AccountStatus::Idle,
-            last_fetched: None,
-            cached_token: Some(data.raw_credential),
-        });
-        self.save_config();
-        self.set_status(format!("Imported OAuth account '{}'", data.name));
-        Some(self.accounts.len() - 1)
+    fn toggle_errors_only(&mut self) {
+        if self.errors_only {
+            self.errors_only = false;
+            self.set_status("Showing all accounts".to_string());
+            return;
+        }
+        self.errors_only = true;
+        let visible = self.display_order().len();
+        if visible == 0 {
+            // An empty table would leave d/e/s acting on a row nobody can see
+            self.errors_only = false;
+            self.set_status("No accounts in error".to_string());
+            return;
+        }
+        self.keep_selection_visible();
+        self.set_status(format!("Showing {visible} errored account(s)"));
     }
 
-    /// Update cached credential after a successful token refresh.
-    pub fn apply_token_refresh(&mut self, account_name: &str, raw_credential: String) {
-        if let Some(account) = self
-            .accounts
-            .iter_mut()
-            .find(|a| a.config.name == account_name)
-        {
-            // Persist to keyring
-            if let Err(e) = self.keyring.set_session_key(account_name, &raw_credential) {
-                eprintln!("[refresh] Failed to persist refreshed token: {e}");
+    /// Move the selection onto the first displayed row if the errors-only filter now
+    /// hides it. If the filter hides everything (the last error recovered), turn it off.
+    fn keep_selection_visible(&mut self) {
+        let order = self.display_order();
+        match order.first() {
+            Some(&first) if !order.contains(&self.selected_index) => self.selected_index = first,
+            Some(_) => {}
+            None if self.errors_only => {
+                self.errors_only = false;
+                self.set_status("No accounts left in error — showing all".to_string());
             }
-            account.cached_token = Some(raw_credential);
+            None => {}
         }
     }
 
-    fn swap_to_selected(&mut self) {
-        if self.selected_index < self.accounts.len() {
-            let name = self.accounts[self.selected_index].config.name.clone();
-            self.active_account_index = self.selected_index;
-            self.save_config();
-            self.set_status(format!("Active: '{name}'"));
+    /// The visible account with the highest effective 5h utilization; ties go to the
+    /// soonest reset. Accounts without usage data are skipped.
+    fn nearest_limit_index(&self, now: DateTime<Utc>) -> Option<usize> {
+        self.display_order()
+            .into_iter()
+            .filter_map(|i| {
+                let account = &self.accounts[i];
+                let pct = account.five_hour_reading(false, now)?.pct();
+                // Unknown reset sorts after any known one
+                let resets_at = account.usage.as_ref()?.resets_at.unwrap_or(DateTime::<Utc>::MAX_UTC);
+                Some((i, pct, resets_at))
+            })
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.2.cmp(&a.2)))
+            .map(|(i, _, _)| i)
+    }
+
+    fn jump_to_nearest_limit(&mut self) {
+        let now = Utc::now();
+        match self.nearest_limit_index(now) {
+            Some(i) => {
+                self.selected_index = i;
+                let usage = self.accounts[i].five_hour_reading(false, now).map(WindowReading::pct);
+                self.set_status(format!(
+                    "Nearest limit: '{}' at {}%",
+                    self.accounts[i].config.name,
+                    usage.unwrap_or(0)
+                ));
+            }
+            None => self.set_status("No usage data yet".to_string()),
         }
     }
-}
 
-pub fn handle_key(app: &mut AppState, key: KeyEvent, tx: &mpsc::UnboundedSender<Event>) {
-    match &app.mode {
-        AppMode::Normal => handle_normal_key(app, key, tx),
-        AppMode::AddAccount => handle_input_key(app, key, tx),
-        AppMode::EditAccount(_) => handle_input_key(app, key, tx),
-        AppMode::ConfirmDelete => handle_confirm_delete(app, key),
-        AppMode::ConfirmSwap => handle_confirm_swap(app, key),
-        AppMode::Help => {
-            app.mode = AppMode::Normal;
+    /// True while the user is typing into the Add/Edit dialog. Automatic polls wait
+    /// until it closes so results don't churn the screen mid-entry.
+    pub fn in_input_dialog(&self) -> bool {
+        matches!(self.mode, AppMode::AddAccount | AppMode::EditAccount(_))
+    }
+
+    /// Note a tick, returning true if the machine slept since the last one: the wall
+    /// clock runs on through a sleep while `Instant` (on macOS and Linux) doesn't. The
+    /// data and the poll schedule are both stale then, so the next poll is due now.
+    pub fn detect_resume(&mut self, wall: DateTime<Utc>, mono: Instant) -> bool {
+        let resumed = self.last_tick.is_some_and(|(last_wall, last_mono)| {
+            let wall_elapsed = wall.signed_duration_since(last_wall).to_std().unwrap_or_default();
+            wall_elapsed.saturating_sub(mono.duration_since(last_mono)) > RESUME_CLOCK_GAP
+        });
+        self.last_tick = Some((wall, mono));
+        if resumed && !self.paused {
+            self.next_poll_at = Some(wall);
         }
+        resumed
     }
-}
 
-fn handle_normal_key(
-    app: &mut AppState,
-    key: KeyEvent,
-    tx: &mpsc::UnboundedSender<Event>,
-) {
-    match key.code {
-        KeyCode::Char('q') => {
-            app.should_quit = true;
+    /// Record that an automatic poll just ran, so the next one is one interval away.
+    pub fn schedule_next_poll(&mut self) {
+        self.next_poll_at =
+            Some(Utc::now() + chrono::Duration::seconds(self.settings.poll_interval_secs as i64));
+    }
+
+    /// Start a flash for each account whose 5h countdown reached zero since we last
+    /// looked. Only resets that were still ahead at the last fetch count, so stale data
+    /// loaded after the fact doesn't flash. Returns the accounts that just reset.
+    pub fn detect_completed_resets(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut reset = Vec::new();
+        for account in &self.accounts {
+            let (Some(resets_at), Some(fetched)) = (
+                account.usage.as_ref().and_then(|u| u.resets_at),
+                account.last_fetched,
+            ) else {
+                continue;
+            };
+            let name = &account.config.name;
+            let announced = self.reset_flashes.get(name).map(|f| f.resets_at);
+            if resets_at <= now && fetched < resets_at && announced != Some(resets_at) {
+                self.reset_flashes.insert(
+                    name.clone(),
+                    ResetFlash {
+                        resets_at,
+                        until: now + chrono::Duration::seconds(RESET_FLASH_SECS),
+                    },
+                );
+                reset.push(name.clone());
+            }
         }
-        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app.should_quit = true;
+        if let [name] = reset.as_slice() {
+            self.set_status(format!("5h window reset for '{name}'"));
+        } else if !reset.is_empty() {
+            self.set_status(format!("5h window reset for {} accounts", reset.len()));
         }
-        KeyCode::Char('j') | KeyCode::Down => {
-            if !app.accounts.is_empty() {
-                app.selected_index = (app.selected_index + 1) % app.accounts.len();
+        reset
+    }
+
+    pub fn is_flashing(&self, account_name: &str, now: DateTime<Utc>) -> bool {
+        self.reset_flashes
+            .get(account_name)
+            .is_some_and(|f| now < f.until)
+    }
+
+    /// Flag accounts whose keyring entry is gone, going by the startup reads rather
+    /// than asking the keyring again. A read that failed any other way (locked,
+    /// denied) flags nothing. Any account that came up without a token is counted in
+    /// a startup summary. Returns how many were flagged.
+    pub fn check_keyring_drift(&mut self) -> usize {
+        let mut missing = 0;
+        let mut unloaded = 0;
+        for account in self.accounts.iter_mut().filter(|a| a.cached_token.is_none()) {
+            unloaded += 1;
+            if self.keyring_missing.contains(&account.config.name) {
+                account.status = AccountStatus::Error(NO_CREDENTIAL_ERROR.to_string());
+                missing += 1;
             }
         }
-        KeyCode::Char('k') | KeyCode::Up => {
-            if !app.accounts.is_empty() {
-                app.selected_index = if app.selected_index == 0 {
-                    app.accounts.len() - 1
-                } else {
-                    app.selected_index - 1
-                };
+        if unloaded > 0 {
+            let total = self.accounts.len();
+            let mut detail = if missing > 0 {
+                format!(" ({missing} not in the keyring)")
+            } else {
+                String::new()
+            };
+            // Tells a locked keychain from a denied one
+            if let Some(reason) = self.keyring_load_errors.first() {
+                detail.push_str(&format!(": {reason}"));
             }
+            self.set_error(format!("{unloaded} of {total} credentials could not be loaded{detail}"));
         }
-        KeyCode::Char('r') => {
-            crate::api::spawn_fetch_all(app, tx);
-            crate::api::spawn_detect_logged_in(app, tx);
-            app.set_status("Refreshing...".to_string());
+        missing
+    }
+
+    pub fn render_interval(&self) -> Duration {
+        if self.low_power_render {
+            LOW_POWER_RENDER_INTERVAL
+        } else {
+            SMOOTH_RENDER_INTERVAL
         }
-        KeyCode::Char('R') => {
-            crate::api::spawn_fetch_one(app, app.selected_index, tx);
-            app.set_status("Refreshing selected...".to_string());
+    }
+
+    fn toggle_render_rate(&mut self) {
+        self.low_power_render = !self.low_power_render;
+        let label = if self.low_power_render {
+            "Low-power redraw (1s)"
+        } else {
+            "Smooth redraw (~30fps)"
+        };
+        self.set_status(label.to_string());
+    }
+
+    /// Record which account Claude Code is logged into.
+    pub fn set_logged_in(&mut self, account_name: Option<String>) {
+        self.logged_in_account = account_name;
+    }
+
+    /// With `selection_follows_active`, move the cursor onto the active (`*`) account
+    /// after a swap made elsewhere, so the two don't diverge. A row hidden by the
+    /// errors-only filter isn't selected.
+    fn follow_active(&mut self) {
+        if self.settings.selection_follows_active && self.display_order().contains(&self.active_account_index) {
+            self.selected_index = self.active_account_index;
         }
-        KeyCode::Char('a') => {
-            app.input_fields.clear();
-            app.mode = AppMode::AddAccount;
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if self.paused {
+            // No automatic poll is coming, so don't promise one in the retry hints
+            self.next_poll_at = None;
+            self.set_status("Polling paused — r/R still refresh".to_string());
+        } else {
+            self.schedule_next_poll();
+            self.set_status("Polling resumed".to_string());
         }
-        KeyCode::Char('e') => {
-            if let Some(account) = app.accounts.get(app.selected_index) {
-                app.input_fields.name = account.config.name.clone();
-                app.input_fields.org_id = account.config.org_id.clone();
-                app.input_fields.session_key = account.cached_token.clone().unwrap_or_default();
-                app.input_fields.focused_field = 0;
-                app.mode = AppMode::EditAccount(app.selected_index);
+    }
+
+    /// Drop the status message once it has been shown for its level's lifetime
+    /// (errors linger longer than routine confirmations).
+    pub fn clear_stale_messages(&mut self) {
+        if let Some(status) = &self.status_message {
+            let lifetime = match status.level {
+                StatusLevel::Info => self.settings.status_message_secs,
+                StatusLevel::Error => self.settings.error_message_secs,
+            };
+            if Utc::now().signed_duration_since(status.set_at).num_seconds() > lifetime as i64 {
+                self.status_message = None;
             }
         }
-        KeyCode::Char('d') | KeyCode::Char('x') => {
-            if !app.accounts.is_empty() {
-                app.mode = AppMode::ConfirmDelete;
-            }
+    }
+
+    pub fn set_status(&mut self, msg: String) {
+        self.set_status_with_level(msg, StatusLevel::Info);
+    }
+
+    pub fn set_error(&mut self, msg: String) {
+        self.set_status_with_level(msg, StatusLevel::Error);
+    }
+
+    fn set_status_with_level(&mut self, text: String, level: StatusLevel) {
+        self.status_message = Some(StatusMessage {
+            text,
+            set_at: Utc::now(),
+            level,
+        });
+    }
+
+    /// The config as it would be saved now.
+    pub fn current_config(&self) -> Config {
+        Config {
+            version: config::CONFIG_VERSION,
+            settings: config::Settings {
+                active_account: self
+                    .accounts
+                    .get(self.active_account_index)
+                    .map(|a| a.config.name.clone()),
+                ..self.settings.clone()
+            },
+            accounts: self.accounts.iter().map(|a| a.config.clone()).collect(),
         }
-        KeyCode::Char('s') | KeyCode::Enter => {
-            if !app.accounts.is_empty() {
-                app.mode = AppMode::ConfirmSwap;
+    }
+
+    /// Returns true if the config was written to disk.
+    fn save_config(&mut self) -> bool {
+        // Clamp the live settings too, so the TUI runs with what the file says
+        self.settings = std::mem::take(&mut self.settings).normalized();
+        let cfg = self.current_config();
+        let written: HashSet<String> = cfg.accounts.iter().map(|ac| ac.name.clone()).collect();
+        let (known, base) = (&self.saved_names, &self.saved_settings);
+        let saved = config::update(|disk| {
+            *disk = merge_for_save(cfg, disk, known, base);
+            disk.settings.clone()
+        });
+        let settings = match saved {
+            Ok(settings) => settings,
+            Err(e) => {
+                self.set_error(format!("Failed to save config: {e}"));
+                self.unsaved_changes = true;
+                return false;
             }
-        }
-        KeyCode::Char('i') => {
-            crate::api::spawn_oauth_import(tx);
-            app.set_status("Importing from Claude Code...".to_string());
-        }
-        KeyCode::Char('L') => {
-            crate::api::spawn_oauth_login(tx);
-            app.set_status("Opening browser — log in to add account...".to_string());
-        }
-        KeyCode::Char('?') => {
-            app.mode = AppMode::Help;
-        }
-        _ => {}
+        };
+        self.saved_names = written;
+        self.adopt_saved_settings(settings);
+        self.unsaved_changes = false;
+        true
     }
-}
 
-fn handle_input_key(
-    app: &mut AppState,
-    key: KeyEvent,
-    tx: &mpsc::UnboundedSender<Event>,
-) {
-    match key.code {
-        KeyCode::Esc => {
-            app.input_fields.clear();
-            app.mode = AppMode::Normal;
-        }
-        KeyCode::Tab | KeyCode::Down => {
-            app.input_fields.next_field();
-        }
-        KeyCode::BackTab | KeyCode::Up => {
-            app.input_fields.prev_field();
+    /// Run with the settings just written, including any another instance changed,
+    /// such as the active account `claude-tracker swap` picked.
+    fn adopt_saved_settings(&mut self, settings: config::Settings) {
+        let swapped = settings
+            .active_account
+            .as_deref()
+            .and_then(|name| self.accounts.iter().position(|a| a.config.name == name))
+            .filter(|&index| index != self.active_account_index);
+        self.saved_settings = settings.clone();
+        self.settings = settings;
+        if let Some(index) = swapped {
+            self.active_account_index = index;
+            self.follow_active();
         }
-        KeyCode::Enter => {
-            let name = app.input_fields.name.trim().to_string();
-            let session_key = app.input_fields.session_key.trim().to_string();
-            let org_id = app.input_fields.org_id.trim().to_string();
+    }
 
-            if name.is_empty() || session_key.is_empty() || org_id.is_empty() {
-                app.set_status("All fields are required".to_string());
-                return;
+    /// Re-read the config file (Ctrl+R). Returns the names of newly added accounts,
+    /// which need a first fetch. Asks first if unsaved accounts would be dropped.
+    fn reload_config(&mut self) -> Vec<String> {
+        let cfg = match config::load_or_init() {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                self.set_error(format!("Reload failed: {e}"));
+                return Vec::new();
             }
+        };
+        if self.unsaved_changes && !self.dropped_by_reload(&cfg).is_empty() {
+            self.pending_reload = Some(cfg);
+            self.mode = AppMode::ConfirmReload;
+            return Vec::new();
+        }
+        self.apply_reloaded_config(cfg)
+    }
 
-            match &app.mode {
-                AppMode::AddAccount => {
-                    if let Some(idx) = app.add_account(name, session_key, org_id) {
-                        crate::api::spawn_fetch_one(app, idx, tx);
-                    }
+    /// Names of in-memory accounts that `cfg` doesn't have.
+    pub fn dropped_by_reload(&self, cfg: &Config) -> Vec<String> {
+        self.accounts
+            .iter()
+            .filter(|a| !cfg.accounts.iter().any(|ac| ac.name == a.config.name))
+            .map(|a| a.config.name.clone())
+            .collect()
+    }
+
+    /// Adopt a freshly read config. Accounts that still exist keep their usage, status
+    /// and stats; new ones load their credential. Runtime toggles (pause, filters) stay.
+    fn apply_reloaded_config(&mut self, cfg: Config) -> Vec<String> {
+        let selected = self.accounts.get(self.selected_index).map(|a| a.config.name.clone());
+        let active = self.accounts.get(self.active_account_index).map(|a| a.config.name.clone());
+        let mut previous: HashMap<String, AccountState> = self
+            .accounts
+            .drain(..)
+            .map(|a| (a.config.name.clone(), a))
+            .collect();
+        let mut added = Vec::new();
+        self.saved_names = cfg.accounts.iter().map(|ac| ac.name.clone()).collect();
+        self.saved_settings = cfg.settings.clone();
+        for ac in cfg.accounts {
+            let state = match previous.remove(&ac.name) {
+                Some(mut state) => {
+                    state.config = ac;
+                    state
                 }
-                AppMode::EditAccount(index) => {
-                    let idx = *index;
-                    app.update_account(idx, name, session_key, org_id);
-                    crate::api::spawn_fetch_one(app, idx, tx);
+                None => {
+                    added.push(ac.name.clone());
+                    let token = self.keyring.get_session_key(&ac.name).ok();
+                    AccountState::new(ac, token)
                 }
-                _ => {}
-            }
-            app.mode = AppMode::Normal;
-        }
-        KeyCode::Backspace => {
-            app.input_fields.current_field_mut().pop();
-        }
-        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app.input_fields.current_field_mut().clear();
+            };
+            self.accounts.push(state);
         }
-        KeyCode::Char(c) => {
-            app.input_fields.current_field_mut().push(c);
+        let position = |name: Option<&str>| {
+            name.and_then(|name| self.accounts.iter().position(|a| a.config.name == name))
+                .unwrap_or(0)
+        };
+        self.active_account_index = position(cfg.settings.active_account.as_deref());
+        self.selected_index = position(selected.as_deref());
+        self.low_power_render = cfg.settings.low_power_render;
+        let swapped = cfg.settings.active_account.is_some() && cfg.settings.active_account != active;
+        self.settings = cfg.settings;
+        if swapped {
+            self.follow_active();
         }
-        _ => {}
+        self.unsaved_changes = false;
+        self.set_status(format!(
+            "Reloaded config: {} accounts, {} new, {} removed",
+            self.accounts.len(),
+            added.len(),
+            previous.len()
+        ));
+        added
     }
-}
 
-fn handle_confirm_delete(app: &mut AppState, key: KeyEvent) {
-    match key.code {
-        KeyCode::Char('y') | KeyCode::Enter => {
-            app.delete_selected();
-            app.mode = AppMode::Normal;
-        }
-        KeyCode::Char('n') | KeyCode::Esc => {
-            app.mode = AppMode::Normal;
+    /// Write the config even though nothing changed — e.g. to retry after a failed save.
+    fn force_save(&mut self) {
+        if self.save_config() {
+            self.set_status("Config saved".to_string());
         }
-        _ => {}
     }
-}
 
-fn handle_confirm_swap(app: &mut AppState, key: KeyEvent) {
-    match key.code {
-        KeyCode::Char('y') | KeyCode::Enter => {
-            app.swap_to_selected();
-            app.mode = AppMode::Normal;
-        }
-        KeyCode::Char('n') | KeyCode::Esc => {
-            app.mode = AppMode::Normal;
-        }
-        _ => {}
+    /// The account other than `except` whose keyring entry `name` would share.
+    fn keyring_name_conflict(&self, name: &str, except: Option<usize>) -> Option<&str> {
+        self.accounts
+            .iter()
+            .enumerate()
+            .find(|(i, a)| Some(*i) != except && same_keyring_name(&a.config.name, name))
+            .map(|(_, a)| a.config.name.as_str())
     }
-}
 
-// =============================================================================
-// Tests
-// =============================================================================
+    /// Returns Some(index) on success, None on failure.
+    fn add_account(&mut self, name: String, session_key: String, org_id: String) -> Option<usize> {
+        if let Some(existing) = self.keyring_name_conflict(&name, None) {
+            self.set_status(format!("Account '{existing}' already exists"));
+            return None;
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::sync::Mutex;
+        if let Err(e) = self.keyring.set_session_key(&name, &session_key) {
+            self.set_error(format!("Keyring error: {e}"));
+            return None;
+        }
 
-    // -------------------------------------------------------------------------
-    // Mock keyring: records all calls, configurable to fail on set
-    // -------------------------------------------------------------------------
-    struct MockKeyring {
-        store: Mutex<std::collections::HashMap<String, String>>,
-        calls: Mutex<Vec<(String, String)>>, // (operation, account_name)
-        fail_on_set: Mutex<bool>,
+        let ac = AccountConfig {
+            name,
+            org_id,
+            auth_method: AuthMethod::SessionKey,
+            ..Default::default()
+        };
+        self.accounts.push(AccountState::new(ac, Some(session_key)));
+        self.save_config();
+        self.set_status("Account added".to_string());
+        Some(self.accounts.len() - 1)
     }
 
-    impl MockKeyring {
-        fn new() -> Self {
-            Self {
-                store: Mutex::new(std::collections::HashMap::new()),
-                calls: Mutex::new(vec![]),
-                fail_on_set: Mutex::new(false),
-            }
-        }
-
-        fn with_fail_on_set() -> Self {
-            let m = Self::new();
-            *m.fail_on_set.lock().unwrap() = true;
-            m
+    /// Write new key first. Only delete old key after new key write succeeds.
+    /// Returns true if the account was updated.
+    fn update_account(&mut self, index: usize, name: String, session_key: String, org_id: String) -> bool {
+        // Immutable borrow to read old name — released before keyring ops
+        let Some(old_name) = self.accounts.get(index).map(|a| a.config.name.clone()) else {
+            return false;
+        };
+        if let Some(existing) = self.keyring_name_conflict(&name, Some(index)) {
+            self.set_status(format!("Account '{existing}' already exists"));
+            return false;
         }
+        let name_changed = old_name != name;
+        // A case-insensitive keychain already finds the old entry under the new name, so
+        // the write below updates it and deleting the old name would remove the new key.
+        // A case-sensitive one doesn't, and its old entry must go. Unsure: keep it.
+        let shares_entry = name_changed
+            && same_keyring_name(&old_name, &name)
+            && !matches!(self.keyring.has_key(&name), Ok(false));
 
-        fn preload(&self, name: &str, key: &str) {
-            self.store
-                .lock()
-                .unwrap()
-                .insert(name.to_string(), key.to_string());
+        // Write new key FIRST -- if this fails, old key is preserved. Skipped when the
+        // name and key are both unchanged (e.g. only the org_id was edited).
+        if let Err(e) = self.store_credential(&name, &session_key) {
+            self.set_error(format!("Keyring error: {e}"));
+            return false;
         }
 
-        fn get_calls(&self) -> Vec<(String, String)> {
-            self.calls.lock().unwrap().clone()
+        // Only delete old key AFTER new key is safely stored
+        if name_changed && !shares_entry {
+            if let Err(e) = self.keyring.delete_session_key(&old_name) {
+                self.set_error(format!("Warning: old key not deleted: {e}"));
+            }
         }
 
-        fn has_key(&self, name: &str) -> bool {
-            self.store.lock().unwrap().contains_key(name)
+        // Now mutate the account
+        if let Some(account) = self.accounts.get_mut(index) {
+            account.config.name = name;
+            account.config.org_id = org_id;
+            account.cached_token = Some(session_key);
+            account.usage = None;
+            account.status = AccountStatus::Idle;
         }
+        self.save_config();
+        self.set_status("Account updated".to_string());
+        true
     }
 
-    impl KeyringBackend for MockKeyring {
-        fn get_session_key(&self, account_name: &str) -> Result<String, crate::error::TrackerError> {
-            self.calls
-                .lock()
-                .unwrap()
-                .push(("get".into(), account_name.into()));
-            self.store
-                .lock()
-                .unwrap()
-                .get(account_name)
-                .cloned()
-                .ok_or_else(|| {
-                    crate::error::TrackerError::Keyring(format!("No key for '{account_name}'"))
-                })
+    fn delete_selected(&mut self) {
+        if self.selected_index < self.accounts.len() {
+            let name = self.accounts[self.selected_index].config.name.clone();
+            if let Err(e) = self.keyring.delete_session_key(&name) {
+                self.set_error(format!("Warning: key not deleted from keyring: {e}"));
+            }
+            self.accounts.remove(self.selected_index);
+            // Keep the same account active when one above it is removed
+            if self.selected_index < self.active_account_index {
+                self.active_account_index -= 1;
+            }
+
+            if self.accounts.is_empty() {
+                self.selected_index = 0;
+                self.active_account_index = 0;
+            } else {
+                if self.selected_index >= self.accounts.len() {
+                    self.selected_index = self.accounts.len() - 1;
+                }
+                if self.active_account_index >= self.accounts.len() {
+                    self.active_account_index = self.accounts.len() - 1;
+                }
+            }
+            self.keep_selection_visible();
+            self.save_config();
+            self.set_status("Account deleted".to_string());
         }
+    }
 
-        fn set_session_key(
-            &self,
+    /// Import an OAuth account from Claude Code. If an account with the same name
+    /// already exists, update its credentials. Otherwise, add a new account.
+    /// Returns the account index on success.
+    pub fn import_oauth_account(&mut self, data: OAuthImportData) -> Option<usize> {
+        let (index, updated) = self.import_without_saving(data)?;
+        self.save_config();
+        let name = &self.accounts[index].config.name;
+        let verb = if updated { "Updated" } else { "Imported" };
+        self.set_status(format!("{verb} OAuth account '{name}'"));
+        Some(index)
+    }
+
+    /// Import several accounts with one config save at the end. Returns the stored
+    /// names of those imported, which need a first fetch.
+    pub fn import_oauth_accounts(&mut self, accounts: Vec<OAuthImportData>) -> Vec<String> {
+        let total = accounts.len();
+        let imported: Vec<String> = accounts
+            .into_iter()
+            .filter_map(|data| {
+                let (index, _) = self.import_without_saving(data)?;
+                Some(self.accounts[index].config.name.clone())
+            })
+            .collect();
+        if imported.is_empty() {
+            return imported;
+        }
+        self.save_config();
+        if imported.len() == total {
+            self.set_status(format!("Imported {} OAuth account(s)", imported.len()));
+        } else {
+            self.set_error(format!("Imported {} of {total} OAuth accounts", imported.len()));
+        }
+        imported
+    }
+
+    /// Store the credential and add or update the account in memory. Returns its index
+    /// and whether it already existed; the caller saves the config.
+    fn import_without_saving(&mut self, mut data: OAuthImportData) -> Option<(usize, bool)> {
+        data.name = self.import_name(&data.name, &data.org_id);
+        // Keep the existing spelling, so the credential lands on that account's entry
+        if let Some(existing) = self.accounts.iter().find(|a| same_keyring_name(&a.config.name, &data.name)) {
+            data.name = existing.config.name.clone();
+        }
+
+        // Store the full credential JSON (includes refresh token) in our keyring
+        if let Err(e) = self.store_credential(&data.name, &data.raw_credential) {
+            self.set_error(format!("Keyring error: {e}"));
+            return None;
+        }
+
+        // Check if account already exists (by name)
+        if let Some(pos) = self.accounts.iter().position(|a| a.config.name == data.name) {
+            self.accounts[pos].config.org_id = data.org_id;
+            self.accounts[pos].config.auth_method = AuthMethod::OAuth;
+            if data.plan.is_some() {
+                self.accounts[pos].config.plan = data.plan;
+            }
+            self.accounts[pos].cached_token = Some(data.raw_credential);
+            self.accounts[pos].usage = None;
+            self.accounts[pos].status = AccountStatus::Idle;
+            return Some((pos, true));
+        }
+
+        // Add new account
+        let ac = AccountConfig {
+            name: data.name.clone(),
+            org_id: data.org_id,
+            auth_method: AuthMethod::OAuth,
+            plan: data.plan,
+            ..Default::default()
+        };
+        self.accounts.push(AccountState::new(ac, Some(data.raw_credential)));
+        Some((self.accounts.len() - 1, false))
+    }
+
+    /// Write an account's credential unless it already holds exactly this value, so
+    /// re-imports and repeated refreshes don't each cost a keychain prompt. The cached
+    /// token is what was last read from or written to the keyring, except for accounts
+    /// in `unsynced_tokens`.
+    fn store_credential(&mut self, account_name: &str, raw_credential: &str) -> Result<(), crate::error::TrackerError> {
+        let unchanged = !self.unsynced_tokens.contains(account_name)
+            && self
+                .accounts
+                .iter()
+                .any(|a| a.config.name == account_name && a.cached_token.as_deref() == Some(raw_credential));
+        if unchanged {
+            return Ok(());
+        }
+        self.keyring.set_session_key(account_name, raw_credential)?;
+        self.unsynced_tokens.remove(account_name);
+        Ok(())
+    }
+
+    /// Name to import under. The same email in a different org (aliases, team vs.
+    /// personal) is a distinct account, so it gets the org appended instead of
+    /// overwriting the existing one.
+    fn import_name(&self, name: &str, org_id: &str) -> String {
+        let clashes = self.accounts.iter().any(|a| {
+            same_keyring_name(&a.config.name, name)
+                && !a.config.org_id.is_empty()
+                && !org_id.is_empty()
+                && a.config.org_id != org_id
+        });
+        if clashes {
+            let short_org: String = org_id.chars().take(8).collect();
+            format!("{name} ({short_org})")
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Update cached credential after a successful token refresh.
+    pub fn apply_token_refresh(&mut self, account_name: &str, raw_credential: String) {
+        // Deleted while the refresh was in flight: don't recreate its keyring entry
+        let Some(index) = self.accounts.iter().position(|a| a.config.name == account_name) else {
+            return;
+        };
+        // Persist to keyring. The token is used either way; a failed write is retried
+        // by the next store of this account's credential.
+        if let Err(e) = self.store_credential(account_name, &raw_credential) {
+            eprintln!("[refresh] Failed to persist refreshed token: {e}");
+            self.unsynced_tokens.insert(account_name.to_string());
+        }
+        self.accounts[index].cached_token = Some(raw_credential);
+    }
+
+    /// Store a repair credential once it's been identified, but only if it belongs to
+    /// the account being repaired; pasting another account's credential would silently
+    /// repoint the row. Returns true if it was stored.
+    pub fn apply_replacement_identity(&mut self, account_name: &str, result: Result<OAuthImportData, String>) -> bool {
+        let data = match result {
+            Ok(data) => data,
+            Err(e) => {
+                self.set_error(format!("'{account_name}' not repaired: {e}"));
+                return false;
+            }
+        };
+        let org_id = self
+            .accounts
+            .iter()
+            .find(|a| a.config.name == account_name)
+            .map(|a| a.config.org_id.clone())
+            .unwrap_or_default();
+        let login = crate::oauth::OAuthProfile {
+            email: data.name,
+            org_id: data.org_id,
+            plan: None,
+        };
+        if !crate::api::owns_profile(account_name, &org_id, &login) {
+            let short_org: String = login.org_id.chars().take(8).collect();
+            self.set_error(format!(
+                "That credential is {} (org {short_org}…), not '{account_name}' — not saved",
+                login.email
+            ));
+            return false;
+        }
+        self.replace_credential(account_name, data.raw_credential)
+    }
+
+    /// Overwrite an OAuth account's stored credential, keeping its name and config.
+    /// Returns true if the keyring accepted it.
+    fn replace_credential(&mut self, account_name: &str, raw_credential: String) -> bool {
+        let Some(index) = self.accounts.iter().position(|a| a.config.name == account_name) else {
+            self.set_error(format!("'{account_name}' no longer exists"));
+            return false;
+        };
+        if let Err(e) = self.keyring.set_session_key(account_name, &raw_credential) {
+            self.set_error(format!("Keyring error: {e}"));
+            return false;
+        }
+        self.unsynced_tokens.remove(account_name);
+        let account = &mut self.accounts[index];
+        account.cached_token = Some(raw_credential);
+        account.status = AccountStatus::Idle;
+        self.set_status(format!("Credential replaced for '{account_name}'"));
+        true
+    }
+
+    /// Open the raw JSON overlay with a fetched body, unless the user has moved on
+    /// to another dialog in the meantime.
+    pub fn show_raw_json(&mut self, account_name: String, result: Result<String, String>) {
+        match result {
+            Ok(body) if self.mode == AppMode::Normal => {
+                self.raw_json = Some(RawJsonView {
+                    account_name,
+                    body,
+                    scroll: 0,
+                });
+                self.mode = AppMode::RawJson;
+                self.status_message = None;
+            }
+            // Another dialog opened while the fetch was in flight; don't cover it
+            Ok(_) => self.set_status(format!("Raw response for '{account_name}' dropped (dialog open); press J again")),
+            Err(msg) => self.set_error(format!("Raw fetch failed for '{account_name}': {msg}")),
+        }
+    }
+
+    /// Copy the selected account's usage as one line of text.
+    fn yank_selected(&mut self) {
+        let Some(account) = self.accounts.get(self.selected_index) else {
+            return;
+        };
+        let text = yank_line(account, Utc::now());
+        let name = account.config.name.clone();
+        match crate::clipboard::copy(&text) {
+            Ok(crate::clipboard::CopyOutcome::Clipboard) => self.set_status(format!("Copied '{name}' usage")),
+            Ok(crate::clipboard::CopyOutcome::Terminal) => {
+                self.set_status(format!("Sent '{name}' usage via OSC 52 (terminal may ignore it)"))
+            }
+            Ok(crate::clipboard::CopyOutcome::File(path)) => {
+                self.set_status(format!("No clipboard — wrote to {}", path.display()))
+            }
+            Err(e) => self.set_error(e),
+        }
+    }
+
+    /// Open the what's-new overlay if this binary is newer than the last one dismissed.
+    /// A first run with no accounts has nothing to catch up on and just records the version.
+    /// An older binary (a downgrade) shows nothing and keeps the newer version on record.
+    pub fn show_whats_new(&mut self) {
+        let last_seen = self.settings.last_seen_version.as_deref();
+        if !crate::whats_new::is_newer(last_seen) {
+            return;
+        }
+        if last_seen.is_none() && self.accounts.is_empty() {
+            self.mark_whats_new_seen();
+            return;
+        }
+        self.whats_new = crate::whats_new::notes_since(last_seen);
+        if self.whats_new.is_empty() {
+            self.mark_whats_new_seen();
+        } else {
+            self.mode = AppMode::WhatsNew;
+        }
+    }
+
+    fn mark_whats_new_seen(&mut self) {
+        self.settings.last_seen_version = Some(crate::whats_new::CURRENT_VERSION.to_string());
+        self.whats_new.clear();
+        self.save_config();
+    }
+
+    /// Open the notes panel with a fresh read of `notes_file`, or close it.
+    fn toggle_notes(&mut self) {
+        if self.notes.take().is_some() {
+            return;
+        }
+        let Some(path) = self.settings.notes_file.clone() else {
+            self.set_error("No notes_file set in config".to_string());
+            return;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => self.notes = Some(text),
+            Err(e) => self.set_error(format!("Can't read {}: {e}", path.display())),
+        }
+    }
+
+    /// Append one CSV row per account to the configured export file.
+    fn export_csv(&mut self) {
+        let path = match self.settings.csv_export_path.clone() {
+            Some(path) => path,
+            None => match config::default_csv_path() {
+                Ok(path) => path,
+                Err(e) => {
+                    self.set_error(format!("CSV export failed: {e}"));
+                    return;
+                }
+            },
+        };
+        let snapshot = crate::usage_snapshot::UsageSnapshot::from_app(self, Utc::now());
+        let rows = crate::export::csv_rows(&snapshot);
+        match crate::export::append_csv(&path, &rows) {
+            Ok(()) => self.set_status(format!("Appended {} rows to {}", rows.len(), path.display())),
+            Err(e) => self.set_error(format!("CSV export failed: {e}")),
+        }
+    }
+
+    /// Open claude.ai's usage page for the selected account (`b`), or show the URL
+    /// when no browser can be opened.
+    fn open_usage_page(&mut self) {
+        let Some(account) = self.accounts.get(self.selected_index) else {
+            return;
+        };
+        let url = crate::api::USAGE_PAGE_URL;
+        let org: String = account.config.org_id.chars().take(8).collect();
+        let org_hint = if org.is_empty() { String::new() } else { format!(" (org {org}…)") };
+        match crate::api::open_in_browser(url) {
+            Ok(()) => self.set_status(format!("Opened usage page for '{}'{org_hint}", account.config.name)),
+            Err(e) => self.set_status(format!("Couldn't open a browser ({e}); usage page{org_hint}: {url}")),
+        }
+    }
+
+    /// Write a redacted debug bundle for attaching to a bug report.
+    fn write_debug_bundle(&mut self) {
+        let path = match config::debug_bundle_path() {
+            Ok(path) => path,
+            Err(e) => {
+                self.set_error(format!("Debug bundle failed: {e}"));
+                return;
+            }
+        };
+        match crate::debug_bundle::DebugBundle::from_app(self, Utc::now()).write(&path) {
+            Ok(()) => self.set_status(format!("Debug bundle written to {}", path.display())),
+            Err(e) => self.set_error(format!("Debug bundle failed: {e}")),
+        }
+    }
+
+    /// Returns true if the swap was saved.
+    fn swap_to_selected(&mut self) -> bool {
+        if self.selected_index >= self.accounts.len() {
+            return false;
+        }
+        let name = self.accounts[self.selected_index].config.name.clone();
+        self.active_account_index = self.selected_index;
+        if !self.save_config() {
+            return false;
+        }
+        self.set_status(format!("Active: '{name}'"));
+        true
+    }
+}
+
+/// "name: 5h 45% (resets 17:05 UTC), 7d 18%" — what `y` copies.
+fn yank_line(account: &AccountState, now: DateTime<Utc>) -> String {
+    let Some(usage) = &account.usage else {
+        return format!("{}: no usage yet", account.config.name);
+    };
+    let five_hour = account.five_hour_reading(false, now).map_or(0, WindowReading::pct);
+    let mut text = format!("{}: 5h {five_hour}%", account.config.name);
+    if let Some(r) = usage.resets_at.filter(|r| *r > now) {
+        text.push_str(&format!(" (resets {})", r.format("%H:%M UTC")));
+    }
+    if let Some(weekly) = account.weekly_reading(false, now).map(WindowReading::pct) {
+        text.push_str(&format!(", 7d {weekly}%"));
+    }
+    text
+}
+
+/// `ours` plus any account in the file that this session has never read or written
+/// (`known`): another instance added it since, so it's kept rather than deleted.
+/// Settings this session left as they were in `base` take the file's value, so a
+/// change made elsewhere (e.g. `swap`) isn't undone.
+fn merge_for_save(mut ours: Config, on_disk: &Config, known: &HashSet<String>, base: &config::Settings) -> Config {
+    let added_elsewhere: Vec<AccountConfig> = on_disk
+        .accounts
+        .iter()
+        .filter(|ac| !known.contains(&ac.name) && !ours.accounts.iter().any(|a| a.name == ac.name))
+        .cloned()
+        .collect();
+    ours.accounts.extend(added_elsewhere);
+    ours.settings = merge_settings(&ours.settings, base, &on_disk.settings);
+    ours
+}
+
+/// Three-way merge, one top-level setting at a time: ours where it differs from
+/// `base`, the file's otherwise.
+fn merge_settings(ours: &config::Settings, base: &config::Settings, on_disk: &config::Settings) -> config::Settings {
+    let table = |settings: &config::Settings| toml::Table::try_from(settings).unwrap_or_default();
+    let (ours_table, base_table) = (table(ours), table(base));
+    let mut merged = table(on_disk);
+    for key in ours_table.keys().chain(base_table.keys()) {
+        if ours_table.get(key) == base_table.get(key) {
+            continue;
+        }
+        match ours_table.get(key) {
+            Some(value) => merged.insert(key.clone(), value.clone()),
+            None => merged.remove(key),
+        };
+    }
+    merged.try_into().unwrap_or_else(|_| ours.clone())
+}
+
+pub fn handle_key(app: &mut AppState, key: KeyEvent, tx: &mpsc::UnboundedSender<Event>) {
+    match &app.mode {
+        AppMode::Normal => handle_normal_key(app, key, tx),
+        AppMode::AddAccount => handle_input_key(app, key, tx),
+        AppMode::EditAccount(_) => handle_input_key(app, key, tx),
+        AppMode::ConfirmDelete => handle_confirm_delete(app, key),
+        AppMode::ConfirmSwap | AppMode::ConfirmSwapErrored => handle_confirm_swap(app, key, tx),
+        AppMode::Help | AppMode::Details | AppMode::KeyringLog => {
+            app.mode = AppMode::Normal;
+        }
+        AppMode::RawJson => handle_raw_json_key(app, key),
+        AppMode::ConfirmReload => match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                app.mode = AppMode::Normal;
+                if let Some(cfg) = app.pending_reload.take() {
+                    for name in app.apply_reloaded_config(cfg) {
+                        crate::api::spawn_fetch_one(app, &name, tx);
+                    }
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                app.pending_reload = None;
+                app.mode = AppMode::Normal;
+            }
+            _ => {}
+        },
+        AppMode::WhatsNew => {
+            app.mark_whats_new_seen();
+            app.mode = AppMode::Normal;
+        }
+        AppMode::Focus(_) => match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.should_quit = true;
+            }
+            KeyCode::Esc | KeyCode::Char('z') => app.mode = AppMode::Normal,
+            _ => {}
+        },
+        AppMode::PasteCredential | AppMode::RepairCredential(_) => {
+            handle_paste_credential_key(app, key, tx)
+        }
+        AppMode::OrgOverride => handle_org_override_key(app, key, tx),
+    }
+}
+
+fn handle_normal_key(
+    app: &mut AppState,
+    key: KeyEvent,
+    tx: &mpsc::UnboundedSender<Event>,
+) {
+    match key.code {
+        KeyCode::Char('q') => {
+            app.should_quit = true;
+        }
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.force_save();
+        }
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            for name in app.reload_config() {
+                crate::api::spawn_fetch_one(app, &name, tx);
+            }
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            app.move_selection(1);
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            app.move_selection(-1);
+        }
+        KeyCode::Char('n') | KeyCode::PageDown => {
+            app.turn_page(1);
+        }
+        KeyCode::Char('p') | KeyCode::PageUp => {
+            app.turn_page(-1);
+        }
+        KeyCode::Char('r') => {
+            crate::api::spawn_fetch_all(app, tx);
+            crate::api::spawn_detect_logged_in(app, tx);
+            app.set_status("Refreshing...".to_string());
+        }
+        KeyCode::Char(' ') => {
+            app.toggle_pause();
+        }
+        KeyCode::Char('m') => {
+            app.toggle_render_rate();
+        }
+        KeyCode::Char('l') => {
+            crate::api::spawn_detect_logged_in(app, tx);
+            app.set_status("Detecting active login...".to_string());
+        }
+        KeyCode::Char('T') => {
+            let errored: Vec<String> = app
+                .accounts
+                .iter()
+                .filter(|a| matches!(a.status, AccountStatus::Error(_)))
+                .map(|a| a.config.name.clone())
+                .collect();
+            for name in &errored {
+                crate::api::spawn_fetch_one(app, name, tx);
+            }
+            if errored.is_empty() {
+                app.set_status("No accounts in error".to_string());
+            } else {
+                app.set_status(format!("Retrying {} errored account(s)...", errored.len()));
+            }
+        }
+        KeyCode::Char('R') => {
+            if let Some(account) = app.accounts.get(app.selected_index) {
+                crate::api::spawn_fetch_one(app, &account.config.name, tx);
+            }
+            app.set_status("Refreshing selected...".to_string());
+        }
+        KeyCode::Char('a') => {
+            app.input_fields.clear();
+            app.mode = AppMode::AddAccount;
+        }
+        KeyCode::Char('c') => {
+            // Duplicate as template: same org, fresh name and key. The Add flow always
+            // creates a session-key account, whatever the source account uses.
+            if let Some(account) = app.accounts.get(app.selected_index) {
+                let org_id = account.config.org_id.clone();
+                app.input_fields.clear();
+                app.input_fields.org_id = org_id;
+                app.mode = AppMode::AddAccount;
+            }
+        }
+        KeyCode::Char('e') => {
+            if let Some(account) = app.accounts.get(app.selected_index) {
+                app.input_fields.name = account.config.name.clone();
+                app.input_fields.org_id = account.config.org_id.clone();
+                app.input_fields.session_key = account.cached_token.clone().unwrap_or_default();
+                app.input_fields.focused_field = 0;
+                app.mode = AppMode::EditAccount(app.selected_index);
+            }
+        }
+        KeyCode::Char('d') | KeyCode::Char('x') if !app.accounts.is_empty() => {
+            app.mode = AppMode::ConfirmDelete;
+        }
+        KeyCode::Char('s') | KeyCode::Enter if !app.accounts.is_empty() => {
+            app.mode = AppMode::ConfirmSwap;
+        }
+        KeyCode::Char('i') => {
+            crate::api::spawn_oauth_import(app, tx);
+            app.set_status("Importing from Claude Code...".to_string());
+        }
+        KeyCode::Char('P') => {
+            app.paste_buffer.clear();
+            app.mode = AppMode::PasteCredential;
+        }
+        KeyCode::Char('L') => {
+            crate::api::spawn_oauth_login(tx);
+            app.set_status("Opening browser — log in to add account...".to_string());
+        }
+        KeyCode::Char('E') => {
+            app.toggle_errors_only();
+        }
+        KeyCode::Char('g') => {
+            app.jump_to_nearest_limit();
+        }
+        KeyCode::Char('f') => {
+            app.toggle_favorite();
+        }
+        KeyCode::Char('K') => {
+            if let Some(account) = app.accounts.get(app.selected_index) {
+                if account.config.auth_method == AuthMethod::OAuth {
+                    app.paste_buffer.clear();
+                    app.mode = AppMode::RepairCredential(account.config.name.clone());
+                } else {
+                    app.set_error("Only OAuth accounts store a credential JSON — use e to edit".to_string());
+                }
+            }
+        }
+        KeyCode::Char('F') => {
+            app.next_favorite();
+        }
+        KeyCode::Char('N') => {
+            app.toggle_notes();
+        }
+        KeyCode::Char('y') => {
+            app.yank_selected();
+        }
+        KeyCode::Char('o') => {
+            app.show_org_column = !app.show_org_column;
+        }
+        KeyCode::Char('W') => {
+            app.write_debug_bundle();
+        }
+        KeyCode::Char('B') => {
+            app.toggle_fast_poll(Utc::now());
+        }
+        KeyCode::Char('b') => {
+            app.open_usage_page();
+        }
+        KeyCode::Char('z') => {
+            if let Some(account) = app.accounts.get(app.selected_index) {
+                app.mode = AppMode::Focus(account.config.name.clone());
+            }
+        }
+        KeyCode::Char('A') => {
+            if app.keyring_audit.is_some() {
+                app.mode = AppMode::KeyringLog;
+            } else {
+                app.set_status("Keyring audit is off — set keyring_audit = true in config".to_string());
+            }
+        }
+        KeyCode::Char('H') => {
+            app.settings.show_help_bar = !app.settings.show_help_bar;
+            app.save_config();
+        }
+        KeyCode::Char('w') => {
+            app.export_csv();
+        }
+        KeyCode::Char('J') if !app.accounts.is_empty() => {
+            crate::api::spawn_fetch_raw(app, app.selected_index, None, tx);
+            app.set_status("Fetching raw response...".to_string());
+        }
+        KeyCode::Char('O') => {
+            if let Some(account) = app.accounts.get(app.selected_index) {
+                if account.config.auth_method == AuthMethod::SessionKey {
+                    app.org_override_input.clear();
+                    app.mode = AppMode::OrgOverride;
+                } else {
+                    app.set_status("Org override only applies to session-key accounts".to_string());
+                }
+            }
+        }
+        KeyCode::Char('v') if !app.accounts.is_empty() => {
+            app.mode = AppMode::Details;
+        }
+        KeyCode::Char('?') => {
+            app.mode = AppMode::Help;
+        }
+        _ => {}
+    }
+}
+
+fn handle_input_key(
+    app: &mut AppState,
+    key: KeyEvent,
+    tx: &mpsc::UnboundedSender<Event>,
+) {
+    if let OrgLookup::Choosing { orgs, selected } = &mut app.input_fields.org_lookup {
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1).min(orgs.len() - 1),
+            KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+            KeyCode::Enter => {
+                let org = orgs[*selected].clone();
+                app.input_fields.org_lookup = OrgLookup::Idle;
+                app.choose_org(&org);
+            }
+            KeyCode::Esc => app.input_fields.org_lookup = OrgLookup::Idle,
+            _ => {}
+        }
+        return;
+    }
+    match key.code {
+        KeyCode::Esc => {
+            app.input_fields.clear();
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Tab | KeyCode::Down => {
+            app.input_fields.next_field();
+        }
+        KeyCode::BackTab | KeyCode::Up => {
+            app.input_fields.prev_field();
+        }
+        KeyCode::Enter => {
+            let name = app.input_fields.name.trim().to_string();
+            let session_key = app.input_fields.session_key.trim().to_string();
+            let org_id = app.input_fields.org_id.trim().to_string();
+
+            if name.is_empty() || session_key.is_empty() || org_id.is_empty() {
+                app.set_error("All fields are required".to_string());
+                return;
+            }
+            // Shown once; the next Enter still runs `validate_on_add` if it's on
+            let unwarned = app.input_fields.swap_warning.is_none();
+            if unwarned && app.input_fields.validation == AddValidation::Unchecked {
+                if let Some(warning) = swapped_field_warning(&session_key, &org_id) {
+                    app.input_fields.swap_warning = Some(warning);
+                    return;
+                }
+            }
+
+            match &app.mode {
+                AppMode::AddAccount => {
+                    if app.settings.validate_on_add {
+                        match app.input_fields.validation {
+                            AddValidation::Unchecked => {
+                                app.input_fields.validation = AddValidation::Pending;
+                                crate::api::spawn_validate_account(&app.settings, session_key, org_id, tx);
+                                return;
+                            }
+                            AddValidation::Pending => return,
+                            AddValidation::Failed(_) => {}
+                        }
+                    }
+                    let added = app.add_account(name.clone(), session_key, org_id);
+                    if added.is_some() {
+                        crate::api::spawn_fetch_one(app, &name, tx);
+                    }
+                }
+                AppMode::EditAccount(index) => {
+                    let idx = *index;
+                    if app.update_account(idx, name.clone(), session_key, org_id) {
+                        crate::api::spawn_fetch_one(app, &name, tx);
+                    }
+                }
+                _ => {}
+            }
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Backspace => {
+            app.input_fields.current_field_mut().pop();
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.input_fields.current_field_mut().clear();
+        }
+        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            let session_key = app.input_fields.session_key.trim().to_string();
+            if session_key.is_empty() {
+                app.set_error("Enter the session key first to look up its orgs".to_string());
+                return;
+            }
+            app.input_fields.org_lookup = OrgLookup::Pending;
+            crate::api::spawn_lookup_orgs(&app.settings, session_key, tx);
+        }
+        KeyCode::Char(c) => {
+            app.input_fields.current_field_mut().push(c);
+        }
+        _ => {}
+    }
+}
+
+/// Claude.ai session keys carry this prefix; org IDs never do.
+const SESSION_KEY_PREFIX: &str = "sk-ant-";
+
+/// Org IDs are UUIDs: 8-4-4-4-12 hex digits.
+fn looks_like_org_id(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// A warning when a value looks like it went in the other field, the usual result of
+/// pasting before tabbing.
+fn swapped_field_warning(session_key: &str, org_id: &str) -> Option<&'static str> {
+    let key_in_org = org_id.starts_with(SESSION_KEY_PREFIX);
+    let org_in_key = looks_like_org_id(session_key);
+    match (key_in_org, org_in_key) {
+        (true, true) => Some("Session Key and Org ID look swapped"),
+        (true, false) => Some("Org ID looks like a session key — wrong field?"),
+        (false, true) => Some("Session Key looks like an org ID — wrong field?"),
+        (false, false) => None,
+    }
+}
+
+/// Insert pasted text into the focused input field. Secrets and IDs never contain
+/// whitespace, so all of it is stripped there; the name field only loses line breaks.
+pub fn handle_paste(app: &mut AppState, text: &str) {
+    if matches!(app.mode, AppMode::PasteCredential | AppMode::RepairCredential(_)) {
+        app.paste_buffer.push_str(text.trim());
+        return;
+    }
+    if app.mode == AppMode::OrgOverride {
+        app.org_override_input
+            .extend(text.chars().filter(|c| !c.is_whitespace()));
+        return;
+    }
+    if !app.in_input_dialog() {
+        return;
+    }
+    let cleaned: String = if app.input_fields.focused_field == 0 {
+        text.lines().map(str::trim).collect::<Vec<_>>().join(" ")
+    } else {
+        text.chars().filter(|c| !c.is_whitespace()).collect()
+    };
+    app.input_fields.current_field_mut().push_str(&cleaned);
+}
+
+/// One-off fetch of the selected account against a typed org_id; the result opens in
+/// the raw JSON overlay and the stored org_id is left alone.
+fn handle_org_override_key(
+    app: &mut AppState,
+    key: KeyEvent,
+    tx: &mpsc::UnboundedSender<Event>,
+) {
+    match key.code {
+        KeyCode::Esc => {
+            app.org_override_input.clear();
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Enter => {
+            let org_id = app.org_override_input.trim().to_string();
+            if org_id.is_empty() {
+                app.set_error("Enter an org ID".to_string());
+                return;
+            }
+            app.org_override_input.clear();
+            app.mode = AppMode::Normal;
+            app.set_status(format!("Fetching with org {org_id}..."));
+            crate::api::spawn_fetch_raw(app, app.selected_index, Some(org_id), tx);
+        }
+        KeyCode::Backspace => {
+            app.org_override_input.pop();
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.org_override_input.clear();
+        }
+        KeyCode::Char(c) => {
+            app.org_override_input.push(c);
+        }
+        _ => {}
+    }
+}
+
+fn handle_paste_credential_key(
+    app: &mut AppState,
+    key: KeyEvent,
+    tx: &mpsc::UnboundedSender<Event>,
+) {
+    match key.code {
+        KeyCode::Esc => {
+            app.paste_buffer.clear();
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Enter => match crate::oauth::parse_credential_json(&app.paste_buffer) {
+            Ok(credential) => {
+                app.paste_buffer.clear();
+                let mode = std::mem::replace(&mut app.mode, AppMode::Normal);
+                if let AppMode::RepairCredential(name) = mode {
+                    app.set_status(format!("Checking the credential belongs to '{name}'..."));
+                    crate::api::spawn_identify_replacement(name, credential, tx);
+                } else {
+                    crate::api::spawn_credential_import(credential, tx);
+                    app.set_status("Identifying pasted credential...".to_string());
+                }
+            }
+            Err(e) => {
+                app.set_error(format!("Invalid credential JSON: {e}"));
+            }
+        },
+        KeyCode::Backspace => {
+            app.paste_buffer.pop();
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.paste_buffer.clear();
+        }
+        KeyCode::Char(c) => {
+            app.paste_buffer.push(c);
+        }
+        _ => {}
+    }
+}
+
+fn handle_raw_json_key(app: &mut AppState, key: KeyEvent) {
+    let Some(view) = app.raw_json.as_mut() else {
+        app.mode = AppMode::Normal;
+        return;
+    };
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => view.scroll = view.scroll.saturating_add(1),
+        KeyCode::Char('k') | KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+        KeyCode::PageDown => view.scroll = view.scroll.saturating_add(10),
+        KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(10),
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.raw_json = None;
+            app.mode = AppMode::Normal;
+        }
+        _ => {}
+    }
+}
+
+fn handle_confirm_delete(app: &mut AppState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => {
+            app.delete_selected();
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Char('n') | KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+        }
+        _ => {}
+    }
+}
+
+fn handle_confirm_swap(app: &mut AppState, key: KeyEvent, tx: &mpsc::UnboundedSender<Event>) {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => {
+            // A failed account is likely expired; swapping to it would log Claude Code out
+            let errored = app
+                .accounts
+                .get(app.selected_index)
+                .is_some_and(|a| matches!(a.status, AccountStatus::Error(_)));
+            if errored && app.mode == AppMode::ConfirmSwap {
+                app.mode = AppMode::ConfirmSwapErrored;
+                return;
+            }
+            if app.swap_to_selected() {
+                if let Some(command) = app.settings.on_swap_command.clone() {
+                    let account = &app.accounts[app.active_account_index].config;
+                    crate::api::spawn_swap_hook(command, account, tx);
+                }
+            }
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Char('n') | KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+        }
+        _ => {}
+    }
+}
+
+/// Keychains may match account names case-insensitively, so "Work" and "work" can
+/// land on the same entry; treat them as the same name.
+fn same_keyring_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// An idle account `name` in org `org-{name}` with no usage or token. Tests set
+    /// what they care about with struct update syntax.
+    pub(crate) fn test_account(name: &str) -> AccountState {
+        AccountState {
+            config: AccountConfig {
+                name: name.to_string(),
+                org_id: format!("org-{name}"),
+                ..Default::default()
+            },
+            usage: None,
+            status: AccountStatus::Idle,
+            last_fetched: None,
+            cached_token: None,
+            stats: Default::default(),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Mock keyring: records all calls, configurable to fail on set
+    // -------------------------------------------------------------------------
+    struct MockKeyring {
+        store: Mutex<std::collections::HashMap<String, String>>,
+        calls: Mutex<Vec<(String, String)>>, // (operation, account_name)
+        fail_on_set: Mutex<bool>,
+    }
+
+    impl MockKeyring {
+        fn new() -> Self {
+            Self {
+                store: Mutex::new(std::collections::HashMap::new()),
+                calls: Mutex::new(vec![]),
+                fail_on_set: Mutex::new(false),
+            }
+        }
+
+        fn with_fail_on_set() -> Self {
+            let m = Self::new();
+            *m.fail_on_set.lock().unwrap() = true;
+            m
+        }
+
+        fn preload(&self, name: &str, key: &str) {
+            self.store
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), key.to_string());
+        }
+
+        fn get_calls(&self) -> Vec<(String, String)> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        fn has_key(&self, name: &str) -> bool {
+            self.store.lock().unwrap().contains_key(name)
+        }
+    }
+
+    impl KeyringBackend for MockKeyring {
+        fn get_session_key(&self, account_name: &str) -> Result<String, crate::error::TrackerError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(("get".into(), account_name.into()));
+            self.store
+                .lock()
+                .unwrap()
+                .get(account_name)
+                .cloned()
+                .ok_or_else(|| crate::error::TrackerError::KeyNotFound(account_name.to_string()))
+        }
+
+        fn set_session_key(
+            &self,
             account_name: &str,
             session_key: &str,
         ) -> Result<(), crate::error::TrackerError> {
@@ -578,194 +2289,1483 @@ mod tests {
                     "Simulated keyring write failure".into(),
                 ));
             }
-            self.store
-                .lock()
-                .unwrap()
-                .insert(account_name.into(), session_key.into());
-            Ok(())
+            self.store
+                .lock()
+                .unwrap()
+                .insert(account_name.into(), session_key.into());
+            Ok(())
+        }
+
+        fn has_key(&self, account_name: &str) -> Result<bool, crate::error::TrackerError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(("has_key".into(), account_name.into()));
+            Ok(self.store.lock().unwrap().contains_key(account_name))
+        }
+
+        fn delete_session_key(
+            &self,
+            account_name: &str,
+        ) -> Result<(), crate::error::TrackerError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(("delete".into(), account_name.into()));
+            self.store.lock().unwrap().remove(account_name);
+            Ok(())
+        }
+    }
+
+    /// Folds names to lowercase before reaching the mock, like a case-insensitive keychain.
+    struct CaseInsensitiveKeyring(Arc<MockKeyring>);
+
+    impl KeyringBackend for CaseInsensitiveKeyring {
+        fn get_session_key(&self, account_name: &str) -> Result<String, crate::error::TrackerError> {
+            self.0.get_session_key(&account_name.to_lowercase())
+        }
+
+        fn set_session_key(&self, account_name: &str, session_key: &str) -> Result<(), crate::error::TrackerError> {
+            self.0.set_session_key(&account_name.to_lowercase(), session_key)
+        }
+
+        fn delete_session_key(&self, account_name: &str) -> Result<(), crate::error::TrackerError> {
+            self.0.delete_session_key(&account_name.to_lowercase())
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Helper: build a test AppState without touching disk or real keyring
+    // -------------------------------------------------------------------------
+    fn test_app(names: &[&str], keyring: Arc<dyn KeyringBackend>) -> AppState {
+        let accounts: Vec<AccountConfig> = names
+            .iter()
+            .map(|n| test_account(n).config)
+            .collect();
+        let config = Config {
+            version: crate::config::CONFIG_VERSION,
+            settings: crate::config::Settings::default(),
+            accounts,
+        };
+        AppState::from_config(config, keyring)
+    }
+
+    // =========================================================================
+    // FIX VERIFIED: add_account failure does not panic.
+    //
+    // Scenario: 0 accounts, keyring write fails, add_account returns None.
+    // The if-let guard in handle_input_key prevents any index arithmetic
+    // on the empty list.
+    // =========================================================================
+    #[test]
+    fn add_account_failure_on_empty_list_does_not_panic() {
+        let mock = Arc::new(MockKeyring::with_fail_on_set());
+        let mut app = test_app(&[], mock);
+
+        app.mode = AppMode::AddAccount;
+        app.input_fields.name = "Test".to_string();
+        app.input_fields.session_key = "sk-test".to_string();
+        app.input_fields.org_id = "org-test".to_string();
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        handle_key(&mut app, key, &tx);
+
+        assert_eq!(app.accounts.len(), 0, "No account should have been added");
+        assert!(
+            app.status_message.is_some(),
+            "Should have an error status message"
+        );
+    }
+
+    // =========================================================================
+    // BUG 2: stale index after deletion misapplies usage result
+    //
+    // Scenario: accounts = [Alice, Bob, Charlie]. Fetch starts for all 3.
+    // Bob (index 1) is deleted. accounts = [Alice, Charlie].
+    // In-flight result for old index 1 (Bob) arrives.
+    // BUG: result is applied to Charlie (now at index 1) instead of being
+    // discarded.
+    //
+    // Expected: Charlie should NOT receive Bob's usage data.
+    // =========================================================================
+    #[test]
+    fn bug2_usage_result_not_misapplied_after_deletion() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Alice", "Bob", "Charlie"], mock);
+
+        // Simulate: fetch was started when Bob was at index 1
+        // Then Bob is deleted
+        app.accounts.remove(1); // accounts = [Alice, Charlie]
+
+        // Stale in-flight result arrives for "Bob" (who no longer exists)
+        let bobs_usage = UsageData {
+            utilization: 99,
+            resets_at: None,
+            weekly_utilization: None,
+            weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
+        };
+        app.apply_usage_result("Bob", Ok(bobs_usage));
+
+        // Charlie (now at index 1) must NOT have Bob's data
+        let charlie = &app.accounts[1];
+        assert_ne!(
+            charlie.usage.as_ref().map(|u| u.utilization),
+            Some(99),
+            "Charlie must NOT receive Bob's stale usage result (utilization=99)"
+        );
+    }
+
+    #[tokio::test]
+    async fn org_lookup_picker_fills_org_id() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        app.mode = AppMode::AddAccount;
+        app.input_fields.session_key = "sk-ant-key".to_string();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL), &tx);
+        assert_eq!(app.input_fields.org_lookup, OrgLookup::Pending);
+
+        let org = |uuid: &str| OrgChoice { uuid: uuid.to_string(), name: String::new() };
+        app.apply_org_lookup(Ok(vec![org("org-a"), org("org-b")]));
+        assert!(matches!(app.input_fields.org_lookup, OrgLookup::Choosing { .. }));
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Down, KeyModifiers::NONE), &tx);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &tx);
+        assert_eq!(app.input_fields.org_id, "org-b");
+        assert_eq!(app.input_fields.org_lookup, OrgLookup::Idle);
+        assert_eq!(app.mode, AppMode::AddAccount, "Picking an org doesn't submit the dialog");
+    }
+
+    #[test]
+    fn org_lookup_ignored_after_edit() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        app.mode = AppMode::AddAccount;
+        app.input_fields.org_lookup = OrgLookup::Pending;
+        app.input_fields.focused_field = 1;
+
+        // Typing into the session key makes the pending lookup stale
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE), &tx);
+        app.apply_org_lookup(Ok(vec![OrgChoice { uuid: "org-a".to_string(), name: String::new() }]));
+
+        assert_eq!(app.input_fields.org_id, "");
+    }
+
+    #[test]
+    fn fast_poll_fires_every_thirty_seconds_until_the_window_ends() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a", "b"], mock);
+        app.settings.fast_poll_window_secs = 90;
+        app.selected_index = 1;
+        let start = Utc::now();
+        let at = |secs: i64| start + chrono::Duration::seconds(secs);
+
+        app.toggle_fast_poll(start);
+        let fetched = |app: &mut AppState| app.apply_usage_result("b", Ok(UsageData::default()));
+        assert_eq!(app.due_fast_polls(at(0)), vec!["b".to_string()], "First fetch is immediate");
+        fetched(&mut app);
+        assert!(app.due_fast_polls(at(10)).is_empty());
+        assert_eq!(app.due_fast_polls(at(30)), vec!["b".to_string()]);
+        fetched(&mut app);
+        assert_eq!(app.due_fast_polls(at(60)), vec!["b".to_string()]);
+
+        assert!(app.due_fast_polls(at(90)).is_empty(), "Window over");
+        assert!(app.fast_poll.is_empty());
+        assert!(app.status_message.as_ref().unwrap().text.contains("ended for 'b'"));
+    }
+
+    #[test]
+    fn fast_poll_skips_in_flight_erroring_and_just_polled_accounts() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a"], mock);
+        app.accounts[0].cached_token = Some("sk".to_string());
+        let start = Utc::now();
+        let at = |secs: i64| start + chrono::Duration::seconds(secs);
+        app.toggle_fast_poll(start);
+
+        assert_eq!(app.due_fast_polls(at(0)).len(), 1);
+        assert!(app.due_fast_polls(at(30)).is_empty(), "Previous fetch still in flight");
+
+        app.apply_usage_result("a", Err("HTTP 429 Too Many Requests".to_string()));
+        assert!(app.due_fast_polls(at(60)).is_empty(), "Errors wait for the regular poll");
+
+        app.apply_usage_result("a", Ok(UsageData::default()));
+        app.defer_fast_polls(at(65));
+        assert!(app.due_fast_polls(at(65)).is_empty(), "The full poll already fetched it");
+        app.apply_usage_result("a", Ok(UsageData::default()));
+        assert!(app.due_fast_polls(at(80)).is_empty());
+        assert_eq!(app.due_fast_polls(at(95)).len(), 1);
+    }
+
+    #[test]
+    fn resume_from_sleep_makes_the_next_poll_due() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a"], mock);
+        let (wall, mono) = (Utc::now(), Instant::now());
+        app.schedule_next_poll();
+
+        assert!(!app.detect_resume(wall, mono));
+        let tick = (wall + chrono::Duration::seconds(1), mono + Duration::from_secs(1));
+        assert!(!app.detect_resume(tick.0, tick.1), "An ordinary tick");
+        assert!(app.next_poll_at.unwrap() > tick.0);
+
+        let woke = tick.0 + chrono::Duration::hours(2);
+        assert!(app.detect_resume(woke, tick.1 + Duration::from_secs(1)));
+        assert_eq!(app.next_poll_at, Some(woke));
+    }
+
+    #[test]
+    fn fast_poll_expires_while_paused() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a"], mock);
+        app.settings.fast_poll_window_secs = 60;
+        let start = Utc::now();
+        app.toggle_fast_poll(start);
+
+        app.expire_fast_polls(start + chrono::Duration::seconds(30));
+        assert!(app.fast_poll.contains_key("a"));
+        app.expire_fast_polls(start + chrono::Duration::seconds(60));
+        assert!(app.fast_poll.is_empty());
+    }
+
+    #[test]
+    fn paging_turns_pages_and_keeps_jk_within_the_page() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a", "b", "c", "d", "e"], mock);
+        app.settings.page_size = Some(2);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let press = |app: &mut AppState, code: KeyCode| handle_key(app, KeyEvent::new(code, KeyModifiers::NONE), &tx);
+
+        assert_eq!(app.page(), Some((0, 3)));
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.selected_index, 1, "k wraps to the bottom of page 1, not the list");
+
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!((app.selected_index, app.page()), (2, Some((1, 3))));
+        press(&mut app, KeyCode::PageDown);
+        assert_eq!(app.selected_index, 4);
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.selected_index, 4, "Last page has one row");
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.selected_index, 4, "Stops at the last page");
+
+        press(&mut app, KeyCode::Char('p'));
+        press(&mut app, KeyCode::PageUp);
+        press(&mut app, KeyCode::PageUp);
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn raw_json_replaces_the_fetching_message() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a"], mock);
+        app.set_status("Fetching raw response...".to_string());
+
+        app.show_raw_json("a".to_string(), Ok("{}".to_string()));
+        assert_eq!(app.mode, AppMode::RawJson);
+        assert!(app.status_message.is_none());
+
+        app.mode = AppMode::Help;
+        app.show_raw_json("a".to_string(), Ok("{}".to_string()));
+        assert_eq!(app.mode, AppMode::Help, "An open dialog isn't covered");
+        assert!(app.status_message.as_ref().unwrap().text.contains("press J again"));
+    }
+
+    #[test]
+    fn save_keeps_accounts_added_by_another_instance() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["kept", "deleted"], mock);
+        app.accounts.remove(1);
+        app.accounts.push(test_account("added-here"));
+
+        let on_disk = Config {
+            accounts: ["kept", "deleted", "added-elsewhere"]
+                .into_iter()
+                .map(|name| test_account(name).config)
+                .collect(),
+            ..app.current_config()
+        };
+        let merged = merge_for_save(app.current_config(), &on_disk, &app.saved_names, &app.saved_settings);
+        let names: Vec<&str> = merged.accounts.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["kept", "added-here", "added-elsewhere"]);
+    }
+
+    #[test]
+    fn tui_save_after_a_cli_swap_keeps_the_swapped_account() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a", "b"], mock);
+        app.settings.active_account = Some("a".to_string());
+        app.saved_settings.active_account = Some("a".to_string());
+
+        // `swap b` ran while the TUI was open; then the TUI saves a favorite
+        let mut on_disk = app.current_config();
+        on_disk.settings.active_account = Some("b".to_string());
+        app.accounts[0].config.favorite = true;
+        let merged = merge_for_save(app.current_config(), &on_disk, &app.saved_names, &app.saved_settings);
+        assert_eq!(merged.settings.active_account.as_deref(), Some("b"));
+        assert!(merged.accounts[0].favorite);
+
+        app.adopt_saved_settings(merged.settings);
+        assert_eq!(app.active_account_index, 1, "The TUI shows the swapped account as active");
+    }
+
+    #[test]
+    fn save_keeps_settings_changed_by_another_instance() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a"], mock);
+        app.settings.bar_width = 30;
+
+        let mut on_disk = app.current_config();
+        on_disk.settings.bar_width = app.saved_settings.bar_width;
+        on_disk.settings.poll_interval_secs = 900;
+        let merged = merge_for_save(app.current_config(), &on_disk, &app.saved_names, &app.saved_settings);
+        assert_eq!(merged.settings.bar_width, 30, "Changed here");
+        assert_eq!(merged.settings.poll_interval_secs, 900, "Changed elsewhere, untouched here");
+    }
+
+    #[test]
+    fn failed_update_reports_false_and_keeps_the_account() {
+        let mock = Arc::new(MockKeyring::with_fail_on_set());
+        mock.preload("Alice", "old-key");
+        let mut app = test_app(&["Alice"], mock);
+
+        assert!(!app.update_account(0, "Alicia".into(), "new-key".into(), "org-Alice".into()));
+        assert_eq!(app.accounts[0].config.name, "Alice");
+    }
+
+    #[test]
+    fn paging_keys_do_nothing_without_page_size() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a", "b", "c"], mock);
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.selected_index, 0);
+        assert_eq!(app.page(), None);
+    }
+
+    #[test]
+    fn fast_poll_toggles_off() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["a"], mock);
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('B'), KeyModifiers::NONE), &tx);
+        assert!(app.fast_poll.contains_key("a"));
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('B'), KeyModifiers::NONE), &tx);
+        assert!(app.fast_poll.is_empty());
+    }
+
+    #[test]
+    fn add_account_rejects_name_differing_only_in_case() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Work"], mock.clone());
+
+        let added = app.add_account("work".to_string(), "sk-other".to_string(), "org-2".to_string());
+
+        assert!(added.is_none(), "'work' would share 'Work''s keyring entry");
+        assert_eq!(app.accounts.len(), 1);
+        assert!(
+            !mock.get_calls().iter().any(|(op, _)| op == "set"),
+            "Nothing written to the keyring: {:?}",
+            mock.get_calls()
+        );
+        assert!(app.status_message.as_ref().unwrap().text.contains("'Work' already exists"));
+    }
+
+    #[test]
+    fn rename_into_another_account_differing_in_case_is_rejected() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Work", "Home"], mock.clone());
+
+        app.update_account(1, "WORK".to_string(), "sk-home".to_string(), "org-Home".to_string());
+
+        assert_eq!(app.accounts[1].config.name, "Home");
+        assert!(
+            mock.get_calls().iter().all(|(op, _)| op == "get"),
+            "Keyring untouched: {:?}",
+            mock.get_calls()
+        );
+    }
+
+    #[test]
+    fn editing_only_org_id_does_not_rewrite_the_key() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("Work", "sk-work");
+        let mut app = test_app(&["Work"], mock.clone());
+
+        app.update_account(0, "Work".to_string(), "sk-work".to_string(), "org-new".to_string());
+
+        assert_eq!(app.accounts[0].config.org_id, "org-new");
+        assert!(
+            !mock.get_calls().iter().any(|(op, _)| op == "set" || op == "delete"),
+            "Unchanged key must not touch the keychain: {:?}",
+            mock.get_calls()
+        );
+    }
+
+    #[test]
+    fn rename_with_unchanged_key_still_moves_the_entry() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("Old", "sk-same");
+        let mut app = test_app(&["Old"], mock.clone());
+
+        app.update_account(0, "New".to_string(), "sk-same".to_string(), "org-Old".to_string());
+
+        assert!(mock.has_key("New"));
+        assert!(!mock.has_key("Old"));
+    }
+
+    #[test]
+    fn case_only_rename_keeps_the_shared_keyring_entry() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("work", "sk-old");
+        let mut app = test_app(&["work"], Arc::new(CaseInsensitiveKeyring(mock.clone())));
+
+        app.update_account(0, "Work".to_string(), "sk-new".to_string(), "org-work".to_string());
+
+        assert_eq!(app.accounts[0].config.name, "Work");
+        assert!(
+            !mock.get_calls().iter().any(|(op, _)| op == "delete"),
+            "On a case-insensitive keychain the delete would remove the key just written: {:?}",
+            mock.get_calls()
+        );
+        assert_eq!(mock.get_session_key("work").unwrap(), "sk-new");
+    }
+
+    #[test]
+    fn case_only_rename_on_a_case_sensitive_keyring_removes_the_old_entry() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("work", "sk-old");
+        let mut app = test_app(&["work"], mock.clone());
+
+        app.update_account(0, "Work".to_string(), "sk-new".to_string(), "org-work".to_string());
+
+        assert!(mock.has_key("Work"));
+        assert!(!mock.has_key("work"), "Old entry left orphaned");
+    }
+
+    #[test]
+    fn oauth_import_matches_names_case_insensitively() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["user@x.com"], mock.clone());
+        let data = OAuthImportData {
+            name: "User@x.com".to_string(),
+            org_id: "org-user@x.com".to_string(),
+            plan: None,
+            raw_credential: "{}".to_string(),
+        };
+
+        let imported = app.import_without_saving(data);
+
+        assert_eq!(imported, Some((0, true)), "Updated the existing account");
+        assert_eq!(app.accounts.len(), 1);
+        assert!(mock.has_key("user@x.com"));
+        assert!(!mock.has_key("User@x.com"));
+    }
+
+    // =========================================================================
+    // BUG 3: rename deletes old keyring entry before writing new one
+    //
+    // Scenario: account "OldName" has a stored key. User renames to "NewName".
+    // update_account deletes "OldName" FIRST, then tries to set "NewName".
+    // If set fails, the old credential is gone forever.
+    //
+    // Expected: old key should still exist when set_session_key fails.
+    // =========================================================================
+    #[test]
+    fn bug3_rename_preserves_old_key_when_new_write_fails() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("OldName", "old-secret-key");
+
+        // Verify precondition: old key exists
+        assert!(mock.has_key("OldName"), "precondition: OldName key exists");
+
+        let mut app = test_app(&["OldName"], mock.clone());
+
+        // Now make set fail (simulating keychain write failure)
+        *mock.fail_on_set.lock().unwrap() = true;
+
+        // Try to rename OldName -> NewName
+        app.update_account(
+            0,
+            "NewName".to_string(),
+            "new-secret-key".to_string(),
+            "org-new".to_string(),
+        );
+
+        // BUG: old key was deleted before the set attempt, so it's gone
+        assert!(
+            mock.has_key("OldName"),
+            "Old key must still exist when new key write fails. \
+             Calls: {:?}",
+            mock.get_calls()
+        );
+    }
+
+    // =========================================================================
+    // FIX VERIFIED: Input fields cleared after edit cancel.
+    //
+    // When user presses Esc to cancel an edit, input_fields.clear() is called
+    // so credentials don't linger in heap memory.
+    // =========================================================================
+    #[test]
+    fn input_fields_cleared_on_edit_cancel() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("Alice", "alice-secret-key");
+        let mut app = test_app(&["Alice"], mock);
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        // User presses 'e' to edit
+        handle_key(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE),
+            &tx,
+        );
+        assert_eq!(app.mode, AppMode::EditAccount(0));
+        assert_eq!(
+            app.input_fields.session_key, "alice-secret-key",
+            "Session key loaded into input fields for editing"
+        );
+
+        // User presses Esc to cancel
+        handle_key(
+            &mut app,
+            KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+            &tx,
+        );
+        assert_eq!(app.mode, AppMode::Normal);
+
+        // FIX: session key is cleared after cancel
+        assert!(
+            app.input_fields.session_key.is_empty(),
+            "Session key must be cleared after edit cancel"
+        );
+        assert!(
+            app.input_fields.name.is_empty(),
+            "Name must be cleared after edit cancel"
+        );
+    }
+
+    #[test]
+    fn input_field_navigation_stays_in_range() {
+        let mut fields = InputFields::default();
+        for _ in 0..(InputFields::FIELD_COUNT * 2 + 1) {
+            fields.next_field();
+            assert!(fields.focused_field < InputFields::FIELD_COUNT);
+            fields.current_field_mut().push('x');
+        }
+        for _ in 0..(InputFields::FIELD_COUNT * 2 + 1) {
+            fields.prev_field();
+            assert!(fields.focused_field < InputFields::FIELD_COUNT);
+        }
+
+        // A stale out-of-range index no longer panics
+        fields.focused_field = 7;
+        fields.current_field_mut().push('y');
+        fields.clear();
+        assert!(fields.fields().iter().all(|f| f.is_empty()));
+    }
+
+    #[test]
+    fn duplicate_seeds_org_id_only() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("Alice", "alice-secret-key");
+        let mut app = test_app(&["Alice"], mock);
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        handle_key(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE),
+            &tx,
+        );
+        assert_eq!(app.mode, AppMode::AddAccount);
+        assert_eq!(app.input_fields.org_id, "org-Alice");
+        assert!(app.input_fields.name.is_empty());
+        assert!(
+            app.input_fields.session_key.is_empty(),
+            "Template must not copy the source account's secret"
+        );
+        assert_eq!(app.input_fields.focused_field, 0);
+    }
+
+    #[test]
+    fn status_first_order_groups_by_severity() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Idle", "Calm", "Hot", "Broken"], mock);
+        let usage = |pct| UsageData {
+            utilization: pct,
+            resets_at: None,
+            weekly_utilization: None,
+            weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
+        };
+        app.accounts[1].status = AccountStatus::Ok;
+        app.accounts[1].usage = Some(usage(20));
+        app.accounts[2].status = AccountStatus::Ok;
+        app.accounts[2].usage = Some(usage(95));
+        app.accounts[3].status = AccountStatus::Error("boom".to_string());
+
+        assert_eq!(app.display_order(), vec![0, 1, 2, 3], "Config order by default");
+
+        app.settings.sort_status_first = true;
+        assert_eq!(app.display_order(), vec![3, 2, 1, 0]);
+
+        // Navigation walks the displayed order, not the config order.
+        let (tx, _rx) = mpsc::unbounded_channel();
+        app.selected_index = 3;
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.selected_index, 2);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE), &tx);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.selected_index, 0, "Wraps from the top to the last displayed row");
+    }
+
+    #[tokio::test]
+    async fn failed_add_validation_warns_then_saves_on_second_enter() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        app.settings.validate_on_add = true;
+        app.mode = AppMode::AddAccount;
+        app.input_fields.name = "Test".to_string();
+        app.input_fields.session_key = "sk-test".to_string();
+        app.input_fields.org_id = "org-typo".to_string();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        handle_key(&mut app, enter, &tx);
+        assert_eq!(app.input_fields.validation, AddValidation::Pending);
+        assert!(app.accounts.is_empty(), "Nothing saved while checking");
+
+        let bad_org = "Session key works, but it has no org 'org-typo'".to_string();
+        assert_eq!(app.apply_add_validation(Err(bad_org.clone())), None);
+        assert_eq!(app.mode, AppMode::AddAccount);
+        assert_eq!(app.input_fields.validation, AddValidation::Failed(bad_org));
+
+        handle_key(&mut app, enter, &tx);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.accounts.len(), 1, "Second Enter saves anyway");
+    }
+
+    #[test]
+    fn passed_add_validation_saves_and_late_results_are_ignored() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        app.mode = AppMode::AddAccount;
+        app.input_fields.name = "Test".to_string();
+        app.input_fields.session_key = "sk-test".to_string();
+        app.input_fields.org_id = "org-test".to_string();
+        app.input_fields.validation = AddValidation::Pending;
+
+        app.input_fields.current_field_mut().push('x');
+        assert_eq!(app.apply_add_validation(Ok(())), None, "Edited since the check started");
+
+        app.input_fields.validation = AddValidation::Pending;
+        assert_eq!(app.apply_add_validation(Ok(())), Some("Testx".to_string()));
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.accounts[0].config.org_id, "org-test");
+    }
+
+    #[test]
+    fn resort_threshold_ignores_small_changes() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B"], mock);
+        app.settings.sort_status_first = true;
+        app.settings.resort_threshold_pct = 5;
+        let usage = |utilization| UsageData {
+            utilization,
+            resets_at: None,
+            weekly_utilization: None,
+            weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
+        };
+        app.apply_usage_result("A", Ok(usage(10)));
+        app.apply_usage_result("B", Ok(usage(78)));
+        assert_eq!(app.display_order(), vec![0, 1]);
+
+        app.apply_usage_result("B", Ok(usage(81)));
+        assert_eq!(app.display_order(), vec![0, 1], "3 points across 80% doesn't reorder");
+
+        app.apply_usage_result("B", Ok(usage(84)));
+        assert_eq!(app.display_order(), vec![1, 0], "6 points past the sorted value does");
+    }
+
+    #[test]
+    fn auditing_keyring_records_calls_and_outcomes() {
+        let mock = Arc::new(MockKeyring::with_fail_on_set());
+        mock.preload("Alice", "alice-secret-key");
+        let audit = Arc::new(AuditingKeyring::new(mock.clone()));
+        let mut app = test_app(&["Alice"], audit.clone());
+        app.keyring_audit = Some(audit.clone());
+
+        assert!(app.add_account("Bob".into(), "bob-key".into(), "org-b".into()).is_none());
+
+        let entries = audit.entries();
+        let summary: Vec<(&str, &str, bool)> = entries
+            .iter()
+            .map(|e| (e.operation, e.account_name.as_str(), e.outcome.is_ok()))
+            .collect();
+        assert_eq!(summary, vec![("get", "Alice", true), ("set", "Bob", false)]);
+        assert_eq!(mock.get_calls().len(), 2, "Every call still reaches the backend");
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('A'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::KeyringLog);
+    }
+
+    #[tokio::test]
+    async fn retry_errored_reports_count() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B", "C"], mock);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('T'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.status_message.as_ref().unwrap().text, "No accounts in error");
+
+        app.accounts[0].status = AccountStatus::Error("timeout".to_string());
+        app.accounts[2].status = AccountStatus::Error("timeout".to_string());
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('T'), KeyModifiers::NONE), &tx);
+        assert_eq!(
+            app.status_message.as_ref().unwrap().text,
+            "Retrying 2 errored account(s)..."
+        );
+
+        // Only the errored accounts were fetched (no token, so each fails at once)
+        let mut fetched = Vec::new();
+        while fetched.len() < 2 {
+            let event = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+            if let Event::UsageResult { account_name, .. } = event {
+                fetched.push(account_name);
+            }
         }
+        fetched.sort();
+        assert_eq!(fetched, vec!["A".to_string(), "C".to_string()]);
+        assert!(rx.try_recv().is_err(), "B is healthy and wasn't fetched");
+    }
 
-        fn delete_session_key(
-            &self,
-            account_name: &str,
-        ) -> Result<(), crate::error::TrackerError> {
-            self.calls
-                .lock()
-                .unwrap()
-                .push(("delete".into(), account_name.into()));
-            self.store.lock().unwrap().remove(account_name);
-            Ok(())
+    #[test]
+    fn color_support_from_env() {
+        let detect = ColorSupport::from_env;
+        assert_eq!(detect(Some("truecolor"), Some("xterm")), ColorSupport::TrueColor);
+        assert_eq!(detect(None, Some("xterm-256color")), ColorSupport::Ansi256);
+        assert_eq!(detect(None, Some("screen-256color")), ColorSupport::Ansi256);
+        assert_eq!(detect(None, Some("xterm")), ColorSupport::Basic);
+        assert_eq!(detect(None, Some("linux")), ColorSupport::Basic);
+    }
+
+    #[test]
+    fn selection_follows_a_swap_made_elsewhere_when_enabled() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B", "C"], mock);
+        let swapped_to = |name: &str| config::Settings {
+            active_account: Some(name.to_string()),
+            ..config::Settings::default()
+        };
+
+        app.adopt_saved_settings(swapped_to("C"));
+        assert_eq!(app.active_account_index, 2);
+        assert_eq!(app.selected_index, 0, "Off by default");
+
+        app.adopt_saved_settings(config::Settings {
+            selection_follows_active: true,
+            ..swapped_to("B")
+        });
+        assert_eq!(app.selected_index, 1);
+
+        // Saving again with the same active account doesn't yank the cursor back
+        app.selected_index = 2;
+        app.adopt_saved_settings(app.settings.clone());
+        assert_eq!(app.selected_index, 2);
+    }
+
+    #[test]
+    fn selection_does_not_follow_onto_a_filtered_row() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B"], mock);
+        app.accounts[0].status = AccountStatus::Error("boom".to_string());
+        app.errors_only = true;
+
+        app.adopt_saved_settings(config::Settings {
+            active_account: Some("B".to_string()),
+            selection_follows_active: true,
+            ..config::Settings::default()
+        });
+        assert_eq!(app.active_account_index, 1);
+        assert_eq!(app.selected_index, 0, "B is hidden by the errors-only filter");
+    }
+
+    #[test]
+    fn active_account_resolves_by_name() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B", "C"], mock.clone());
+        let mut config = Config {
+            version: crate::config::CONFIG_VERSION,
+            settings: app.settings.clone(),
+            accounts: app.accounts.iter().map(|a| a.config.clone()).collect(),
+        };
+        config.settings.active_account = Some("C".to_string());
+        assert_eq!(AppState::from_config(config.clone(), mock.clone()).active_account_index, 2);
+
+        config.settings.active_account = Some("gone".to_string());
+        assert_eq!(AppState::from_config(config, mock).active_account_index, 0);
+
+        // Deleting an account above the active one keeps the same account active
+        app.active_account_index = 2;
+        app.selected_index = 0;
+        app.delete_selected();
+        assert_eq!(app.accounts[app.active_account_index].config.name, "C");
+    }
+
+    #[test]
+    fn completed_reset_flashes_once() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B"], mock);
+        let now = Utc::now();
+        let resets_at = now - chrono::Duration::seconds(1);
+        app.apply_usage_result(
+            "A",
+            Ok(UsageData {
+                utilization: 95,
+                resets_at: Some(resets_at),
+                weekly_utilization: None,
+                weekly_resets_at: None,
+                tokens: None,
+                weekly_tokens: None,
+                weekly_cadence: None,
+            }),
+        );
+        app.accounts[0].last_fetched = Some(now - chrono::Duration::minutes(3));
+
+        assert_eq!(app.detect_completed_resets(now), vec!["A".to_string()]);
+        assert!(app.is_flashing("A", now));
+        assert!(!app.is_flashing("B", now));
+        assert!(!app.is_flashing("A", now + chrono::Duration::seconds(RESET_FLASH_SECS)));
+
+        assert!(app.detect_completed_resets(now).is_empty(), "Same reset isn't announced twice");
+
+        // Fetched after the reset already passed: nothing we watched happen
+        app.reset_flashes.clear();
+        app.accounts[0].last_fetched = Some(now);
+        assert!(app.detect_completed_resets(now).is_empty());
+    }
+
+    #[test]
+    fn g_jumps_to_highest_utilization_with_soonest_reset_on_tie() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B", "C", "D"], mock);
+        let now = Utc::now();
+        let usage = |utilization, resets_in_mins: i64| UsageData {
+            utilization,
+            resets_at: Some(now + chrono::Duration::minutes(resets_in_mins)),
+            weekly_utilization: None,
+            weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
+        };
+        app.accounts[0].usage = Some(usage(70, 30));
+        app.accounts[1].usage = Some(usage(85, 90));
+        app.accounts[2].usage = Some(usage(85, 20));
+        // Reset already passed: effectively 0%
+        app.accounts[3].usage = Some(usage(99, -5));
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.selected_index, 2);
+    }
+
+    #[test]
+    fn startup_reads_match_tokens_to_accounts_and_summarize_failures() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("A", "sk-a");
+        mock.preload("C", "sk-c");
+        let mut app = test_app(&["A", "B", "C", "D"], mock);
+
+        let tokens: Vec<Option<&str>> = app.accounts.iter().map(|a| a.cached_token.as_deref()).collect();
+        assert_eq!(tokens, vec![Some("sk-a"), None, Some("sk-c"), None]);
+
+        app.check_keyring_drift();
+        let msg = app.status_message.as_ref().unwrap();
+        assert_eq!(msg.text, "2 of 4 credentials could not be loaded (2 not in the keyring)");
+        assert_eq!(msg.level, StatusLevel::Error);
+    }
+
+    /// Every read fails the way a locked keychain does.
+    struct LockedKeyring;
+
+    impl KeyringBackend for LockedKeyring {
+        fn get_session_key(&self, _: &str) -> Result<String, crate::error::TrackerError> {
+            Err(crate::error::TrackerError::Keyring("Keychain is locked".into()))
+        }
+
+        fn set_session_key(&self, _: &str, _: &str) -> Result<(), crate::error::TrackerError> {
+            Err(crate::error::TrackerError::Keyring("Keychain is locked".into()))
+        }
+
+        fn delete_session_key(&self, _: &str) -> Result<(), crate::error::TrackerError> {
+            Err(crate::error::TrackerError::Keyring("Keychain is locked".into()))
         }
     }
 
-    // -------------------------------------------------------------------------
-    // Helper: build a test AppState without touching disk or real keyring
-    // -------------------------------------------------------------------------
-    fn test_app(names: &[&str], keyring: Arc<dyn KeyringBackend>) -> AppState {
-        let accounts: Vec<AccountConfig> = names
-            .iter()
-            .map(|n| AccountConfig {
-                name: n.to_string(),
-                org_id: format!("org-{n}"),
-                auth_method: AuthMethod::default(),
-            })
-            .collect();
-        let config = Config {
-            settings: crate::config::Settings::default(),
-            accounts,
-        };
-        AppState::from_config(config, keyring)
+    #[test]
+    fn startup_summary_says_why_reads_failed() {
+        let mut app = test_app(&["A", "B"], Arc::new(LockedKeyring));
+
+        assert_eq!(app.check_keyring_drift(), 0, "A locked keychain flags nothing as missing");
+        let msg = app.status_message.as_ref().unwrap();
+        assert!(msg.text.starts_with("2 of 2 credentials could not be loaded: "), "{}", msg.text);
+        assert!(msg.text.contains("Keychain is locked"), "{}", msg.text);
+    }
+
+    #[test]
+    fn keyring_drift_flags_accounts_without_an_entry() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("A", "sk-a");
+        let mut app = test_app(&["A", "B"], mock.clone());
+
+        assert_eq!(app.check_keyring_drift(), 1);
+        assert_eq!(mock.get_calls().len(), 2, "One read per account, none again: {:?}", mock.get_calls());
+        assert_eq!(app.accounts[0].status, AccountStatus::Idle);
+        assert_eq!(app.accounts[1].status, AccountStatus::Error(NO_CREDENTIAL_ERROR.to_string()));
+        assert!(app.accounts[1].credential_missing());
+        assert!(!app.accounts[0].credential_missing());
+    }
+
+    #[test]
+    fn shift_f_cycles_through_favorites_only() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B", "C", "D"], mock);
+        app.accounts[1].config.favorite = true;
+        app.accounts[3].config.favorite = true;
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let press = |app: &mut AppState| {
+            handle_key(app, KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT), &tx);
+            app.selected_index
+        };
+
+        assert_eq!(press(&mut app), 1);
+        assert_eq!(press(&mut app), 3);
+        assert_eq!(press(&mut app), 1, "Wraps around");
+    }
+
+    #[test]
+    fn reload_keeps_usage_for_surviving_accounts() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("C", "sk-c");
+        let mut app = test_app(&["A", "B"], mock);
+        app.accounts[0].status = AccountStatus::Ok;
+        app.accounts[0].stats.attempts = 3;
+        app.selected_index = 0;
+
+        // The file as edited elsewhere: reordered, one account added, settings changed
+        let edited = test_app(&["B", "A", "C"], Arc::new(MockKeyring::new()));
+        let mut cfg = Config {
+            version: crate::config::CONFIG_VERSION,
+            settings: edited.settings.clone(),
+            accounts: edited.accounts.iter().map(|a| a.config.clone()).collect(),
+        };
+        cfg.settings.poll_interval_secs = 600;
+        cfg.settings.active_account = Some("C".to_string());
+        cfg.settings.low_power_render = true;
+
+        let added = app.apply_reloaded_config(cfg);
+        assert_eq!(added, vec!["C".to_string()]);
+        let names: Vec<&str> = app.accounts.iter().map(|a| a.config.name.as_str()).collect();
+        assert_eq!(names, ["B", "A", "C"]);
+        assert_eq!(app.accounts[1].status, AccountStatus::Ok, "A keeps its runtime state");
+        assert_eq!(app.accounts[1].stats.attempts, 3);
+        assert_eq!(app.accounts[2].cached_token.as_deref(), Some("sk-c"));
+        assert_eq!(app.selected_index, 1, "Selection follows A");
+        assert_eq!(app.active_account_index, 2);
+        assert_eq!(app.settings.poll_interval_secs, 600);
+        assert!(app.low_power_render, "Render rate follows the reloaded setting");
+    }
+
+    #[test]
+    fn reload_dropping_unsaved_accounts_asks_first() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B"], mock);
+        let cfg = Config {
+            version: crate::config::CONFIG_VERSION,
+            settings: app.settings.clone(),
+            accounts: vec![app.accounts[0].config.clone()],
+        };
+        assert_eq!(app.dropped_by_reload(&cfg), vec!["B".to_string()]);
+
+        app.pending_reload = Some(cfg);
+        app.mode = AppMode::ConfirmReload;
+        let (tx, _rx) = mpsc::unbounded_channel();
+        handle_key(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), &tx);
+        assert_eq!(app.accounts.len(), 2, "Cancel keeps B");
+        assert!(app.pending_reload.is_none());
+    }
+
+    #[test]
+    fn whats_new_shows_once_after_update() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A"], mock);
+        app.settings.last_seen_version = Some("0.0.1".to_string());
+
+        app.show_whats_new();
+        assert_eq!(app.mode, AppMode::WhatsNew);
+        assert!(!app.whats_new.is_empty());
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        handle_key(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.settings.last_seen_version.as_deref(), Some(crate::whats_new::CURRENT_VERSION));
+
+        app.show_whats_new();
+        assert_eq!(app.mode, AppMode::Normal, "Not again for the same version");
+    }
+
+    #[test]
+    fn save_clamps_the_live_settings() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A"], mock);
+        app.settings.poll_interval_secs = 1;
+
+        app.save_config();
+        assert!(app.settings.poll_interval_secs > 1, "Floor applies in memory, not just on disk");
+    }
+
+    #[test]
+    fn older_binary_keeps_the_newer_seen_version() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A"], mock);
+        app.settings.last_seen_version = Some("99.0.0".to_string());
+
+        app.show_whats_new();
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.settings.last_seen_version.as_deref(), Some("99.0.0"));
+    }
+
+    #[test]
+    fn swapped_fields_are_flagged() {
+        let org = "65f10de7-1c2d-4e5f-8a9b-0123456789ab";
+        let key = "sk-ant-sid01-abcdef";
+        assert_eq!(swapped_field_warning(key, org), None);
+        assert_eq!(swapped_field_warning(org, key), Some("Session Key and Org ID look swapped"));
+        assert_eq!(swapped_field_warning(key, key), Some("Org ID looks like a session key — wrong field?"));
+        assert_eq!(swapped_field_warning(org, org), Some("Session Key looks like an org ID — wrong field?"));
+    }
+
+    #[tokio::test]
+    async fn swapped_field_warning_needs_second_enter() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        app.mode = AppMode::AddAccount;
+        app.input_fields.name = "Test".to_string();
+        app.input_fields.session_key = "65f10de7-1c2d-4e5f-8a9b-0123456789ab".to_string();
+        app.input_fields.org_id = "sk-ant-sid01-abcdef".to_string();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        handle_key(&mut app, enter, &tx);
+        assert_eq!(app.mode, AppMode::AddAccount);
+        assert!(app.input_fields.swap_warning.is_some());
+        assert!(app.accounts.is_empty());
+
+        handle_key(&mut app, enter, &tx);
+        assert_eq!(app.accounts.len(), 1, "Saved anyway after the warning");
+    }
+
+    #[tokio::test]
+    async fn dismissed_swap_warning_still_runs_validate_on_add() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        app.settings.validate_on_add = true;
+        app.mode = AppMode::AddAccount;
+        app.input_fields.name = "Test".to_string();
+        app.input_fields.session_key = "65f10de7-1c2d-4e5f-8a9b-0123456789ab".to_string();
+        app.input_fields.org_id = "sk-ant-sid01-abcdef".to_string();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        handle_key(&mut app, enter, &tx);
+        handle_key(&mut app, enter, &tx);
+        assert_eq!(app.input_fields.validation, AddValidation::Pending);
+        assert!(app.accounts.is_empty(), "Not saved until the check answers");
+    }
+
+    #[test]
+    fn yank_line_summarizes_usage() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A"], mock);
+        let now = Utc::now();
+        assert_eq!(yank_line(&app.accounts[0], now), "A: no usage yet");
+
+        let resets_at = now + chrono::Duration::hours(1);
+        app.accounts[0].usage = Some(UsageData {
+            utilization: 45,
+            resets_at: Some(resets_at),
+            weekly_utilization: Some(18),
+            weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
+        });
+        assert_eq!(
+            yank_line(&app.accounts[0], now),
+            format!("A: 5h 45% (resets {}), 7d 18%", resets_at.format("%H:%M UTC"))
+        );
+    }
+
+    #[test]
+    fn z_focuses_selected_account_and_esc_returns() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B"], mock);
+        app.selected_index = 1;
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::Focus("B".to_string()));
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE), &tx);
+        assert!(!app.should_quit, "Only Esc/z leave focus mode");
+        handle_key(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::Normal);
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE), &tx);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL), &tx);
+        assert!(app.should_quit, "Ctrl+C quits from focus mode too");
+    }
+
+    #[test]
+    fn notes_panel_rereads_file_on_each_open() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A"], mock);
+        let path = std::env::temp_dir().join(format!("tracker-notes-{}.md", std::process::id()));
+        std::fs::write(&path, "A: work\n").unwrap();
+        app.settings.notes_file = Some(path.clone());
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let n = KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT);
+
+        handle_key(&mut app, n, &tx);
+        assert_eq!(app.notes.as_deref(), Some("A: work\n"));
+
+        std::fs::write(&path, "A: personal\n").unwrap();
+        handle_key(&mut app, n, &tx);
+        assert!(app.notes.is_none(), "Second press closes");
+        handle_key(&mut app, n, &tx);
+        assert_eq!(app.notes.as_deref(), Some("A: personal\n"));
+
+        std::fs::remove_file(&path).unwrap();
     }
 
-    // =========================================================================
-    // FIX VERIFIED: add_account failure does not panic.
-    //
-    // Scenario: 0 accounts, keyring write fails, add_account returns None.
-    // The if-let guard in handle_input_key prevents any index arithmetic
-    // on the empty list.
-    // =========================================================================
     #[test]
-    fn add_account_failure_on_empty_list_does_not_panic() {
-        let mock = Arc::new(MockKeyring::with_fail_on_set());
-        let mut app = test_app(&[], mock);
+    fn notes_panel_without_file_reports_error() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A"], mock);
+        let (tx, _rx) = mpsc::unbounded_channel();
 
-        app.mode = AppMode::AddAccount;
-        app.input_fields.name = "Test".to_string();
-        app.input_fields.session_key = "sk-test".to_string();
-        app.input_fields.org_id = "org-test".to_string();
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT), &tx);
+        assert!(app.notes.is_none());
+        assert_eq!(app.status_message.as_ref().unwrap().level, StatusLevel::Error);
+    }
 
+    #[test]
+    fn swap_to_errored_account_needs_second_confirm() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B"], mock);
+        app.accounts[1].status = AccountStatus::Error("Expired".to_string());
+        app.selected_index = 1;
         let (tx, _rx) = mpsc::unbounded_channel();
-        let key = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        let y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
 
-        handle_key(&mut app, key, &tx);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE), &tx);
+        handle_key(&mut app, y, &tx);
+        assert_eq!(app.mode, AppMode::ConfirmSwapErrored);
+        assert_eq!(app.active_account_index, 0, "Nothing written yet");
 
-        assert_eq!(app.accounts.len(), 0, "No account should have been added");
-        assert!(
-            app.status_message.is_some(),
-            "Should have an error status message"
-        );
+        handle_key(&mut app, y, &tx);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.active_account_index, 1);
     }
 
-    // =========================================================================
-    // BUG 2: stale index after deletion misapplies usage result
-    //
-    // Scenario: accounts = [Alice, Bob, Charlie]. Fetch starts for all 3.
-    // Bob (index 1) is deleted. accounts = [Alice, Charlie].
-    // In-flight result for old index 1 (Bob) arrives.
-    // BUG: result is applied to Charlie (now at index 1) instead of being
-    // discarded.
-    //
-    // Expected: Charlie should NOT receive Bob's usage data.
-    // =========================================================================
     #[test]
-    fn bug2_usage_result_not_misapplied_after_deletion() {
+    fn cancelling_errored_swap_warning_keeps_active() {
         let mock = Arc::new(MockKeyring::new());
-        let mut app = test_app(&["Alice", "Bob", "Charlie"], mock);
+        let mut app = test_app(&["A", "B"], mock);
+        app.accounts[1].status = AccountStatus::Error("Expired".to_string());
+        app.selected_index = 1;
+        let (tx, _rx) = mpsc::unbounded_channel();
 
-        // Simulate: fetch was started when Bob was at index 1
-        // Then Bob is deleted
-        app.accounts.remove(1); // accounts = [Alice, Charlie]
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE), &tx);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE), &tx);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(app.active_account_index, 0);
+    }
 
-        // Stale in-flight result arrives for "Bob" (who no longer exists)
-        let bobs_usage = UsageData {
-            utilization: 99,
+    #[test]
+    fn fetch_stats_count_attempts_and_latency() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Alice"], mock);
+        assert_eq!(app.accounts[0].stats.success_rate(), None);
+
+        let usage = UsageData {
+            utilization: 10,
             resets_at: None,
             weekly_utilization: None,
             weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
-        app.apply_usage_result("Bob", Ok(bobs_usage));
+        app.record_fetch_latency("Alice", Duration::from_millis(100));
+        app.apply_usage_result("Alice", Ok(usage));
+        app.record_fetch_latency("Alice", Duration::from_millis(300));
+        app.apply_usage_result("Alice", Err("HTTP 500".to_string()));
 
-        // Charlie (now at index 1) must NOT have Bob's data
-        let charlie = &app.accounts[1];
-        assert_ne!(
-            charlie.usage.as_ref().map(|u| u.utilization),
-            Some(99),
-            "Charlie must NOT receive Bob's stale usage result (utilization=99)"
-        );
+        let stats = &app.accounts[0].stats;
+        assert_eq!(stats.attempts, 2);
+        assert_eq!(stats.successes, 1);
+        assert_eq!(stats.success_rate(), Some(50));
+        assert_eq!(stats.average_latency(), Some(Duration::from_millis(200)));
+        assert!(stats.last_error_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn repair_credential_overwrites_keyring_and_keeps_account() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("A", "{\"claudeAiOauth\":{}}");
+        let mut app = test_app(&["A"], mock.clone());
+        app.accounts[0].config.auth_method = AuthMethod::OAuth;
+        app.accounts[0].status = AccountStatus::Error("Invalid OAuth credential JSON".to_string());
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('K'), KeyModifiers::SHIFT), &tx);
+        assert_eq!(app.mode, AppMode::RepairCredential("A".to_string()));
+
+        let pasted = r#"{"claudeAiOauth":{"accessToken":"at-new","refreshToken":"rt"}}"#;
+        handle_paste(&mut app, pasted);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(mock.get_session_key("A").unwrap().contains("{}"), "Nothing stored before the check");
+
+        let identified = OAuthImportData {
+            name: "A".to_string(),
+            org_id: "org-A".to_string(),
+            plan: None,
+            raw_credential: pasted.to_string(),
+        };
+        assert!(app.apply_replacement_identity("A", Ok(identified)));
+        assert_eq!(app.accounts.len(), 1);
+        assert_eq!(app.accounts[0].config.name, "A");
+        assert_eq!(app.accounts[0].status, AccountStatus::Idle);
+        let stored = mock.get_session_key("A").unwrap();
+        assert_eq!(crate::oauth::normalize_stored_token(&stored), "at-new");
+        assert_eq!(app.accounts[0].cached_token.as_deref(), Some(stored.as_str()));
     }
 
-    // =========================================================================
-    // BUG 3: rename deletes old keyring entry before writing new one
-    //
-    // Scenario: account "OldName" has a stored key. User renames to "NewName".
-    // update_account deletes "OldName" FIRST, then tries to set "NewName".
-    // If set fails, the old credential is gone forever.
-    //
-    // Expected: old key should still exist when set_session_key fails.
-    // =========================================================================
     #[test]
-    fn bug3_rename_preserves_old_key_when_new_write_fails() {
+    fn repair_with_another_accounts_credential_is_rejected() {
         let mock = Arc::new(MockKeyring::new());
-        mock.preload("OldName", "old-secret-key");
+        mock.preload("A", "old");
+        let mut app = test_app(&["A"], mock.clone());
+        let someone_else = OAuthImportData {
+            name: "B".to_string(),
+            org_id: "org-B".to_string(),
+            plan: None,
+            raw_credential: "b-credential".to_string(),
+        };
 
-        // Verify precondition: old key exists
-        assert!(mock.has_key("OldName"), "precondition: OldName key exists");
+        assert!(!app.apply_replacement_identity("A", Ok(someone_else)));
+        assert_eq!(mock.get_session_key("A").unwrap(), "old");
+        assert!(app.status_message.as_ref().unwrap().text.contains("not 'A'"));
+    }
 
-        let mut app = test_app(&["OldName"], mock.clone());
+    #[test]
+    fn m_toggles_render_interval() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A"], mock);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        assert_eq!(app.render_interval(), SMOOTH_RENDER_INTERVAL);
 
-        // Now make set fail (simulating keychain write failure)
-        *mock.fail_on_set.lock().unwrap() = true;
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.render_interval(), LOW_POWER_RENDER_INTERVAL);
 
-        // Try to rename OldName -> NewName
-        app.update_account(
-            0,
-            "NewName".to_string(),
-            "new-secret-key".to_string(),
-            "org-new".to_string(),
-        );
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.render_interval(), SMOOTH_RENDER_INTERVAL);
+    }
 
-        // BUG: old key was deleted before the set attempt, so it's gone
-        assert!(
-            mock.has_key("OldName"),
-            "Old key must still exist when new key write fails. \
-             Calls: {:?}",
-            mock.get_calls()
-        );
+    #[test]
+    fn space_toggles_pause_and_clears_retry_schedule() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A"], mock);
+        app.schedule_next_poll();
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE), &tx);
+        assert!(app.paused);
+        assert!(app.next_poll_at.is_none());
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE), &tx);
+        assert!(!app.paused);
+        assert!(app.next_poll_at.is_some());
     }
 
-    // =========================================================================
-    // FIX VERIFIED: Input fields cleared after edit cancel.
-    //
-    // When user presses Esc to cancel an edit, input_fields.clear() is called
-    // so credentials don't linger in heap memory.
-    // =========================================================================
     #[test]
-    fn input_fields_cleared_on_edit_cancel() {
+    fn errors_only_filter_confines_navigation() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B", "C", "D"], mock);
+        app.accounts[1].status = AccountStatus::Error("timeout".to_string());
+        app.accounts[3].status = AccountStatus::Error("401".to_string());
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('E'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.display_order(), vec![1, 3]);
+        assert_eq!(app.selected_index, 1, "Selection moves onto a visible row");
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.selected_index, 3);
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.selected_index, 1, "Skips accounts hidden by the filter");
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('E'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.display_order(), vec![0, 1, 2, 3]);
+        assert_eq!(app.selected_index, 1, "Clearing the filter keeps the selection");
+    }
+
+    #[test]
+    fn errors_only_never_leaves_a_hidden_row_selected() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["A", "B", "C"], mock.clone());
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let press = |app: &mut AppState, c: char| handle_key(app, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), &tx);
+
+        press(&mut app, 'E');
+        assert!(!app.errors_only, "Nothing to show, so the filter stays off");
+
+        app.accounts[1].status = AccountStatus::Error("timeout".to_string());
+        app.accounts[2].status = AccountStatus::Error("timeout".to_string());
+        press(&mut app, 'E');
+        assert_eq!(app.selected_index, 1);
+
+        // B recovers while selected: the cursor moves to the row still shown
+        app.apply_usage_result("B", Ok(UsageData::default()));
+        assert_eq!(app.selected_index, 2);
+
+        press(&mut app, 'd');
+        press(&mut app, 'y');
+        let names: Vec<&str> = app.accounts.iter().map(|a| a.config.name.as_str()).collect();
+        assert_eq!(names, vec!["A", "B"], "Deletes the visible account");
+        assert!(!app.errors_only, "No errors left to show");
+    }
+
+    #[tokio::test]
+    async fn org_override_leaves_config_untouched() {
         let mock = Arc::new(MockKeyring::new());
         mock.preload("Alice", "alice-secret-key");
         let mut app = test_app(&["Alice"], mock);
+        let body = r#"{"five_hour":{"utilization":7}}"#;
+        app.settings.api_base_url = Some(crate::api::tests::serve_once("200 OK", "application/json", body).await);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('O'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::OrgOverride);
+
+        handle_paste(&mut app, " org-other\n");
+        assert_eq!(app.org_override_input, "org-other");
+
+        handle_key(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.org_override_input.is_empty());
+        assert_eq!(app.accounts[0].config.org_id, "org-Alice");
+
+        match rx.recv().await {
+            Some(Event::RawUsageResult { account_name, result }) => {
+                assert_eq!(account_name, "Alice @ org-other");
+                assert!(result.unwrap().contains("\"utilization\": 7"));
+            }
+            other => panic!("expected a raw result, got {other:?}"),
+        }
+    }
 
+    #[test]
+    fn org_override_not_offered_for_oauth() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Alice"], mock);
+        app.accounts[0].config.auth_method = AuthMethod::OAuth;
         let (tx, _rx) = mpsc::unbounded_channel();
 
-        // User presses 'e' to edit
-        handle_key(
-            &mut app,
-            KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE),
-            &tx,
-        );
-        assert_eq!(app.mode, AppMode::EditAccount(0));
-        assert_eq!(
-            app.input_fields.session_key, "alice-secret-key",
-            "Session key loaded into input fields for editing"
-        );
+        handle_key(&mut app, KeyEvent::new(KeyCode::Char('O'), KeyModifiers::NONE), &tx);
+        assert_eq!(app.mode, AppMode::Normal);
+    }
+
+    // =========================================================================
+    // Pasting a session key with a trailing newline must not submit the form.
+    // =========================================================================
+    #[test]
+    fn paste_with_newline_strips_whitespace_and_does_not_submit() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        app.mode = AppMode::AddAccount;
+        app.input_fields.focused_field = 1;
+
+        handle_paste(&mut app, "sk-ant-\n abc123\r\n");
+
+        assert_eq!(app.input_fields.session_key, "sk-ant-abc123");
+        assert_eq!(app.mode, AppMode::AddAccount, "Paste must not submit the dialog");
+        assert!(app.accounts.is_empty());
+    }
+
+    #[test]
+    fn malformed_pasted_credential_keeps_dialog_open() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        app.mode = AppMode::PasteCredential;
+        handle_paste(&mut app, "{\"claudeAiOauth\": {\"refreshToken\": \"rt\"}}\n");
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        handle_key(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &tx);
+
+        assert_eq!(app.mode, AppMode::PasteCredential);
+        let status = app.status_message.as_ref().unwrap();
+        assert!(status.text.starts_with("Invalid credential JSON"), "got: {}", status.text);
+        assert_eq!(status.level, StatusLevel::Error);
+    }
+
+    #[test]
+    fn paste_ignored_outside_input_dialogs() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Alice"], mock);
+
+        handle_paste(&mut app, "q");
 
-        // User presses Esc to cancel
-        handle_key(
-            &mut app,
-            KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
-            &tx,
-        );
         assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.input_fields.name.is_empty());
+    }
 
-        // FIX: session key is cleared after cancel
-        assert!(
-            app.input_fields.session_key.is_empty(),
-            "Session key must be cleared after edit cancel"
-        );
-        assert!(
-            app.input_fields.name.is_empty(),
-            "Name must be cleared after edit cancel"
-        );
+    #[test]
+    fn error_messages_outlive_info_messages() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&[], mock);
+        app.settings.status_message_secs = 5;
+        app.settings.error_message_secs = 15;
+        let ten_secs_ago = Utc::now() - chrono::Duration::seconds(10);
+
+        app.set_status("Account added".to_string());
+        app.status_message.as_mut().unwrap().set_at = ten_secs_ago;
+        app.clear_stale_messages();
+        assert!(app.status_message.is_none(), "Info expires after status_message_secs");
+
+        app.set_error("Keyring error".to_string());
+        app.status_message.as_mut().unwrap().set_at = ten_secs_ago;
+        app.clear_stale_messages();
+        assert!(app.status_message.is_some(), "Errors stay for error_message_secs");
     }
 
     // =========================================================================
@@ -791,6 +3791,9 @@ mod tests {
             resets_at: None,
             weekly_utilization: None,
             weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
         app.apply_usage_result("Alice", Ok(usage));
 
@@ -816,6 +3819,9 @@ mod tests {
             resets_at: None,
             weekly_utilization: None,
             weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
         app.apply_usage_result("Alice", Ok(usage));
 
@@ -843,6 +3849,9 @@ mod tests {
             resets_at: Some(Utc::now() + chrono::Duration::hours(3)),
             weekly_utilization: Some(40),
             weekly_resets_at: Some(Utc::now() + chrono::Duration::days(5)),
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
         app.apply_usage_result("Alice", Ok(usage));
 
@@ -895,6 +3904,9 @@ mod tests {
             resets_at: Some(Utc::now() + chrono::Duration::hours(1)),
             weekly_utilization: Some(88),
             weekly_resets_at: Some(Utc::now() + chrono::Duration::days(3)),
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
         app.apply_usage_result("Alice", Ok(usage));
 
@@ -935,6 +3947,9 @@ mod tests {
             resets_at: Some(Utc::now() + chrono::Duration::hours(1)),
             weekly_utilization: Some(70),
             weekly_resets_at: Some(Utc::now() + chrono::Duration::days(2)),
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
         app.apply_usage_result("Alice", Ok(old_usage));
 
@@ -948,6 +3963,9 @@ mod tests {
             resets_at: Some(Utc::now() + chrono::Duration::hours(5)),
             weekly_utilization: Some(20),
             weekly_resets_at: Some(Utc::now() + chrono::Duration::days(7)),
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
         app.apply_usage_result("Alice", Ok(new_usage));
 
@@ -1009,6 +4027,9 @@ mod tests {
             resets_at: None,
             weekly_utilization: None,
             weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
         app.apply_usage_result("Alice", Ok(usage));
 
@@ -1043,6 +4064,9 @@ mod tests {
             resets_at: Some(Utc::now() + chrono::Duration::hours(2)),
             weekly_utilization: Some(60),
             weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
         };
         app.apply_usage_result("Alice", Ok(usage));
         assert!(app.accounts[0].usage.is_some());
@@ -1051,6 +4075,7 @@ mod tests {
         let import_data = OAuthImportData {
             name: "Alice".to_string(),
             org_id: "org-Alice".to_string(),
+            plan: None,
             raw_credential: "fresh-token-xyz".to_string(),
         };
         app.import_oauth_account(import_data);
@@ -1071,4 +4096,89 @@ mod tests {
             "Cached token must be updated"
         );
     }
+
+    #[test]
+    fn batch_import_skips_unchanged_credentials() {
+        let mock = Arc::new(MockKeyring::new());
+        mock.preload("Alice", "alice-token");
+        let mut app = test_app(&["Alice"], mock.clone());
+        let import = |name: &str, token: &str| OAuthImportData {
+            name: name.to_string(),
+            org_id: format!("org-{name}"),
+            plan: None,
+            raw_credential: token.to_string(),
+        };
+
+        let names = app.import_oauth_accounts(vec![import("Alice", "alice-token"), import("Bob", "bob-token")]);
+
+        assert_eq!(names, vec!["Alice".to_string(), "Bob".to_string()]);
+        let sets: Vec<String> = mock
+            .get_calls()
+            .into_iter()
+            .filter(|(op, _)| op == "set")
+            .map(|(_, name)| name)
+            .collect();
+        assert_eq!(sets, vec!["Bob".to_string()], "Alice's stored token is already current");
+        assert_eq!(app.status_message.as_ref().unwrap().text, "Imported 2 OAuth account(s)");
+    }
+
+    #[test]
+    fn repeated_token_refresh_writes_once() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Alice"], mock.clone());
+
+        app.apply_token_refresh("Alice", "fresh".to_string());
+        app.apply_token_refresh("Alice", "fresh".to_string());
+        app.apply_token_refresh("Gone", "fresh".to_string());
+
+        let sets = mock.get_calls().iter().filter(|(op, _)| op == "set").count();
+        assert_eq!(sets, 1, "{:?}", mock.get_calls());
+        assert!(!mock.has_key("Gone"), "No entry recreated for a deleted account");
+    }
+
+    #[test]
+    fn failed_refresh_write_is_retried() {
+        let mock = Arc::new(MockKeyring::with_fail_on_set());
+        let mut app = test_app(&["Alice"], mock.clone());
+
+        app.apply_token_refresh("Alice", "fresh".to_string());
+        assert_eq!(app.accounts[0].cached_token.as_deref(), Some("fresh"), "Still used this session");
+        assert!(!mock.has_key("Alice"));
+
+        *mock.fail_on_set.lock().unwrap() = false;
+        app.apply_token_refresh("Alice", "fresh".to_string());
+        assert_eq!(mock.get_session_key("Alice").unwrap(), "fresh");
+        assert!(app.unsynced_tokens.is_empty());
+    }
+
+    #[test]
+    fn import_same_name_different_org_adds_distinct_account() {
+        let mock = Arc::new(MockKeyring::new());
+        let mut app = test_app(&["Alice"], mock.clone());
+        app.accounts[0].config.auth_method = AuthMethod::OAuth;
+        let import = |org_id: &str, token: &str| OAuthImportData {
+            name: "Alice".to_string(),
+            org_id: org_id.to_string(),
+            plan: None,
+            raw_credential: token.to_string(),
+        };
+
+        let idx = app.import_oauth_account(import("0f3c9a21-7777", "team-token")).unwrap();
+        assert_eq!(idx, 1, "Must not overwrite the existing Alice");
+        assert_eq!(app.accounts.len(), 2);
+        assert_eq!(app.accounts[0].config.org_id, "org-Alice");
+        assert_eq!(app.accounts[1].config.name, "Alice (0f3c9a21)");
+        assert_eq!(app.accounts[1].config.org_id, "0f3c9a21-7777");
+        assert!(mock.has_key("Alice (0f3c9a21)"), "Stored under the distinct name");
+
+        // Importing the same profile again updates it rather than adding a third
+        let idx = app.import_oauth_account(import("0f3c9a21-7777", "team-token-2")).unwrap();
+        assert_eq!(idx, 1);
+        assert_eq!(app.accounts.len(), 2);
+        assert_eq!(app.accounts[1].cached_token.as_deref(), Some("team-token-2"));
+
+        // Same org as the original still updates the original
+        let idx = app.import_oauth_account(import("org-Alice", "alice-token")).unwrap();
+        assert_eq!(idx, 0);
+    }
 }
@@ -0,0 +1,170 @@
+//! Copy text out of the TUI: the platform clipboard tool when there is one, the
+//! terminal's own clipboard (OSC 52) over SSH, and otherwise a file in the config
+//! directory whose path is reported, so copying still works on headless machines.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Backend {
+    /// A clipboard command that reads the text on stdin.
+    Command(&'static str, &'static [&'static str]),
+    /// OSC 52 escape: the terminal emulator on the user's machine sets its clipboard.
+    Osc52,
+    File,
+}
+
+/// Where copied text ended up, for the status message.
+#[derive(Debug, PartialEq)]
+pub enum CopyOutcome {
+    Clipboard,
+    /// Sent as OSC 52; terminals that don't support it drop it without a reply.
+    Terminal,
+    File(PathBuf),
+}
+
+/// Detected on first copy; downgraded to `File` if the chosen tool then fails.
+static BACKEND: Mutex<Option<Backend>> = Mutex::new(None);
+
+fn pick_backend(os: &str, has_var: impl Fn(&str) -> bool) -> Backend {
+    if has_var("SSH_CONNECTION") || has_var("SSH_TTY") {
+        // A local clipboard tool would copy on the remote machine, not the user's
+        Backend::Osc52
+    } else if os == "macos" {
+        Backend::Command("pbcopy", &[])
+    } else if os == "windows" {
+        Backend::Command("clip", &[])
+    } else if has_var("WAYLAND_DISPLAY") {
+        Backend::Command("wl-copy", &[])
+    } else if has_var("DISPLAY") {
+        Backend::Command("xclip", &["-selection", "clipboard"])
+    } else {
+        Backend::File
+    }
+}
+
+pub fn copy(text: &str) -> Result<CopyOutcome, String> {
+    let mut backend = BACKEND.lock().unwrap_or_else(|e| e.into_inner());
+    let chosen = *backend.get_or_insert_with(|| {
+        pick_backend(std::env::consts::OS, |name| {
+            std::env::var_os(name).is_some_and(|v| !v.is_empty())
+        })
+    });
+    match chosen {
+        Backend::Command(program, args) => {
+            if run_clipboard_command(program, args, text) {
+                return Ok(CopyOutcome::Clipboard);
+            }
+            *backend = Some(Backend::File);
+        }
+        Backend::Osc52 => {
+            let mut stdout = std::io::stdout();
+            if write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))
+                .and_then(|()| stdout.flush())
+                .is_ok()
+            {
+                return Ok(CopyOutcome::Terminal);
+            }
+        }
+        Backend::File => {}
+    }
+    write_fallback_file(text).map(CopyOutcome::File)
+}
+
+/// Longest a clipboard tool may hold up the UI thread; a tool that hangs (xclip with
+/// no reachable X server) is killed and later copies go to the file.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(1);
+
+fn run_clipboard_command(program: &str, args: &[&str], text: &str) -> bool {
+    let Ok(mut child) = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let written = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    let deadline = Instant::now() + COMMAND_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return written && status.success(),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
+
+/// In the user's own config directory, not the shared temp dir, where another local
+/// user could plant a symlink at the fixed name.
+fn write_fallback_file(text: &str) -> Result<PathBuf, String> {
+    let path = crate::config::copy_fallback_path().map_err(|e| format!("Copy failed: {e}"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Copy failed: {e}"))?;
+    }
+    std::fs::write(&path, text).map_err(|e| format!("Copy failed: {e}"))?;
+    Ok(path)
+}
+
+/// Standard padded base64, for the OSC 52 payload.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_reference_vectors() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn ssh_sessions_use_the_terminal_clipboard() {
+        let env = |vars: &'static [&'static str]| move |name: &str| vars.contains(&name);
+        assert_eq!(pick_backend("macos", env(&["SSH_TTY"])), Backend::Osc52);
+        assert_eq!(pick_backend("macos", env(&[])), Backend::Command("pbcopy", &[]));
+        assert_eq!(pick_backend("linux", env(&["WAYLAND_DISPLAY", "DISPLAY"])), Backend::Command("wl-copy", &[]));
+        assert_eq!(pick_backend("linux", env(&[])), Backend::File, "Headless: no clipboard at all");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hung_clipboard_tool_is_killed_after_the_timeout() {
+        let started = Instant::now();
+        assert!(!run_clipboard_command("sleep", &["10"], "text"));
+        assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use crate::error::ConfigError;
 
@@ -13,8 +15,73 @@ pub enum AuthMethod {
     OAuth,
 }
 
+/// Schema version written by this build. Bump it together with a new step in `migrate`.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Characters used to draw the usage bars.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BarGlyphs {
+    /// Full/light block characters.
+    #[default]
+    Blocks,
+    /// `#` and `-`, for fonts without block characters.
+    Ascii,
+    Braille,
+}
+
+/// Extra emphasis for accounts at or over 100% of a window.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitAlert {
+    /// Just the usual red.
+    #[default]
+    Off,
+    /// The whole row blinks.
+    Blink,
+    /// A "LIMIT" badge replaces the status text.
+    Badge,
+}
+
+/// How the table shows time left until a reset.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CountdownFormat {
+    /// Two units: "3h 05m", "2d 4h".
+    #[default]
+    Full,
+    /// Largest unit only: "3h", "2d", "45m".
+    Compact,
+    /// "3:05:12", "12:34", "2d 04:00".
+    Clock,
+}
+
+/// When the table header gives way to a column legend in the status bar.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactHeader {
+    /// Only on terminals too short to spare the header row.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// How per-account usage is rolled up into an org summary row.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrgAggregate {
+    /// Total across accounts — for a limit the org shares.
+    Sum,
+    /// Busiest account — for per-account limits.
+    Max,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Schema version; files written before versioning existed read as 0.
+    #[serde(default)]
+    pub version: u32,
     #[serde(default)]
     pub settings: Settings,
     #[serde(default)]
@@ -25,73 +92,909 @@ pub struct Config {
 pub struct Settings {
     #[serde(default = "default_poll_interval")]
     pub poll_interval_secs: u64,
+    /// Name of the active account. Stored by name so hand-editing or reordering the
+    /// accounts can't silently make a different account active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_account: Option<String>,
+    /// Delay between starting each account's fetch in a full refresh, to spread
+    /// requests out. 0 fires them all at once; at most 5 seconds.
+    #[serde(default = "default_fetch_stagger_ms")]
+    pub fetch_stagger_ms: u64,
+    /// Override the API host for usage fetches (e.g. a staging endpoint).
+    /// Unset means production.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_base_url: Option<String>,
+    /// Where `w` appends usage rows. Unset means `usage.csv` next to the config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv_export_path: Option<PathBuf>,
+    /// Show a usage response with no `five_hour` bucket as 0% rather than an error.
+    #[serde(default = "default_true")]
+    pub allow_missing_five_hour: bool,
+    /// Whether the keybinding bar at the bottom is shown (toggled with `H`).
+    #[serde(default = "default_true")]
+    pub show_help_bar: bool,
+    /// How long routine status messages stay visible.
+    #[serde(default = "default_status_message_secs")]
+    pub status_message_secs: u64,
+    /// How long error status messages stay visible.
+    #[serde(default = "default_error_message_secs")]
+    pub error_message_secs: u64,
+    /// Re-read Claude Code's keychain this often and adopt refreshed tokens for
+    /// matching accounts. 0 disables the sync.
+    #[serde(default)]
+    pub claude_code_sync_secs: u64,
+    /// Float accounts needing attention to the top: errors, then high utilization,
+    /// then the rest, with idle accounts last. Config order is kept within each group.
+    #[serde(default)]
+    pub sort_status_first: bool,
+    /// With `sort_status_first`, an account's 5h utilization must move more than this
+    /// many points before its new value can change its position. 0 re-sorts on every change.
+    #[serde(default)]
+    pub resort_threshold_pct: u32,
+    /// Record keyring calls in memory for the audit overlay (`A`). Debug aid.
+    #[serde(default)]
+    pub keyring_audit: bool,
+    /// Show "used / limit" token counts in the % columns when the API provides them.
+    #[serde(default)]
+    pub show_token_counts: bool,
+    /// Mark the active account with a row background instead of a " *" name suffix.
     #[serde(default)]
-    pub active_account: usize,
+    pub active_marker_color: bool,
+    /// Keychain service Claude Code's credential is read from (`i`, login detection,
+    /// sync). Alternate config-directory entries are found by this prefix. macOS only.
+    #[serde(default = "default_keychain_service")]
+    pub claude_code_keychain_service: String,
+    /// Shell command run after each swap (`s`), with the account name and org_id as
+    /// `$1`/`$2` and in `CLAUDE_TRACKER_ACCOUNT`/`CLAUDE_TRACKER_ORG_ID`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_swap_command: Option<String>,
+    /// Also send a desktop notification when a 5h window resets (the row always flashes).
+    #[serde(default)]
+    pub notify_on_reset: bool,
+    /// Once a window's reset time passes, show it as "recalc…" until the next fetch
+    /// confirms the new numbers, instead of assuming 0%.
+    #[serde(default)]
+    pub recalculate_after_reset: bool,
+    /// Start with a 1s redraw instead of ~30fps (toggle at runtime with `m`).
+    #[serde(default)]
+    pub low_power_render: bool,
+    /// Move the selection to the active (`*`) account when a swap made elsewhere (the
+    /// `swap` command, a config reload) changes it.
+    #[serde(default)]
+    pub selection_follows_active: bool,
+    /// Add a summary row per org with two or more displayed accounts, pinned below the
+    /// account rows. Unset means no summaries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_aggregate: Option<OrgAggregate>,
+    /// Show at most this many table rows, with a "… N more" line for the rest.
+    /// Unset means as many as fit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rows: Option<usize>,
+    /// Show accounts a page of this many at a time, turned with `n`/`p`. Unset means
+    /// one continuous list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<usize>,
+    /// Check a new session-key account's org_id and key with one fetch before saving
+    /// it from the Add dialog. A failed check can still be saved with a second Enter.
+    #[serde(default)]
+    pub validate_on_add: bool,
+    /// `--once` gives up on any account still fetching after this long and reports it
+    /// as a timeout, so a hung request can't stall a script. At least 1 second.
+    #[serde(default = "default_once_deadline_secs")]
+    pub once_deadline_secs: u64,
+    /// How long `B` polls the selected account every 30s before it drops back to
+    /// the normal interval. At most a day.
+    #[serde(default = "default_fast_poll_window_secs")]
+    pub fast_poll_window_secs: u64,
+    /// Version whose what's-new overlay was last dismissed; newer binaries show theirs once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_version: Option<String>,
+    /// Text file shown read-only in the panel toggled with `N`, re-read on each open.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_file: Option<PathBuf>,
+    /// Segments in each usage bar (clamped to 3..=40 on load).
+    #[serde(default = "default_bar_width")]
+    pub bar_width: u16,
+    #[serde(default)]
+    pub bar_glyphs: BarGlyphs,
+    #[serde(default)]
+    pub limit_alert: LimitAlert,
+    #[serde(default)]
+    pub countdown_format: CountdownFormat,
+    #[serde(default)]
+    pub compact_header: CompactHeader,
+    /// Weekly usage below this percentage shows as a dimmed "<N%" without bar or
+    /// countdown. 0 shows every value; at most 100.
+    #[serde(default)]
+    pub weekly_hide_below_pct: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AccountConfig {
     pub name: String,
     #[serde(default)]
     pub org_id: String,
     #[serde(default)]
     pub auth_method: AuthMethod,
+    /// Subscription plan (e.g. "Pro", "Max 5x") captured from the OAuth profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<String>,
+    /// Extra request headers for this account's usage fetches. A name that matches a
+    /// built-in header (e.g. `User-Agent`, `Referer`) replaces it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Marked with `f`; `F` cycles through favorites only.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    /// Short tag or emoji shown before the name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Color for this row's # and name instead of the utilization color: a name
+    /// (`cyan`), a 0-255 palette index, or `#rrggbb`. Unrecognized values are ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent_color: Option<String>,
+    /// When the user expects this session key to stop working: a date
+    /// (`2026-11-01`, midnight UTC) or an RFC 3339 datetime, quoted or not. Session
+    /// keys carry no expiry of their own, so this is only what the user wrote down;
+    /// the table warns as it approaches.
+    #[serde(default, deserialize_with = "deserialize_expiry", skip_serializing_if = "Option::is_none")]
+    pub session_key_expires: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Read `session_key_expires` from a string or a TOML date/datetime (see `parse_expiry`).
+pub(crate) fn deserialize_expiry<'de, D>(deserializer: D) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    let text = match Option::<toml::Value>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(toml::Value::String(text)) => text,
+        Some(toml::Value::Datetime(datetime)) => datetime.to_string(),
+        Some(other) => return Err(D::Error::custom(format!("expected a date, got {}", other.type_str()))),
+    };
+    parse_expiry(&text)
+        .map(Some)
+        .ok_or_else(|| D::Error::custom(format!("'{text}' is not a date (2026-11-01) or RFC 3339 datetime")))
+}
+
+/// RFC 3339, a datetime without an offset, or a bare date; the last two count as UTC.
+fn parse_expiry(text: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let text = text.trim();
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.to_utc());
+    }
+    if let Ok(datetime) = chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(datetime.and_utc());
+    }
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc())
 }
 
 fn default_poll_interval() -> u64 {
     180
 }
 
+fn default_fetch_stagger_ms() -> u64 {
+    100
+}
+
+fn default_once_deadline_secs() -> u64 {
+    15
+}
+
+fn default_fast_poll_window_secs() -> u64 {
+    600
+}
+
+fn default_status_message_secs() -> u64 {
+    5
+}
+
+fn default_error_message_secs() -> u64 {
+    15
+}
+
+fn default_bar_width() -> u16 {
+    10
+}
+
+fn default_keychain_service() -> String {
+    crate::oauth::DEFAULT_KEYCHAIN_SERVICE.to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
             poll_interval_secs: default_poll_interval(),
-            active_account: 0,
+            active_account: None,
+            fetch_stagger_ms: default_fetch_stagger_ms(),
+            api_base_url: None,
+            csv_export_path: None,
+            allow_missing_five_hour: true,
+            show_help_bar: true,
+            status_message_secs: default_status_message_secs(),
+            error_message_secs: default_error_message_secs(),
+            claude_code_sync_secs: 0,
+            sort_status_first: false,
+            resort_threshold_pct: 0,
+            keyring_audit: false,
+            show_token_counts: false,
+            active_marker_color: false,
+            selection_follows_active: false,
+            low_power_render: false,
+            recalculate_after_reset: false,
+            on_swap_command: None,
+            notify_on_reset: false,
+            claude_code_keychain_service: default_keychain_service(),
+            org_aggregate: None,
+            max_rows: None,
+            page_size: None,
+            notes_file: None,
+            last_seen_version: None,
+            once_deadline_secs: default_once_deadline_secs(),
+            fast_poll_window_secs: default_fast_poll_window_secs(),
+            validate_on_add: false,
+            bar_width: default_bar_width(),
+            bar_glyphs: BarGlyphs::default(),
+            limit_alert: LimitAlert::default(),
+            countdown_format: CountdownFormat::default(),
+            compact_header: CompactHeader::default(),
+            weekly_hide_below_pct: 0,
         }
     }
 }
 
+/// Environment variable that points the tracker at an explicit config directory.
+pub const CONFIG_DIR_ENV: &str = "CLAUDE_TRACKER_CONFIG_DIR";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConfigDirSource {
+    Env,
+    Home,
+    Xdg,
+    CurrentDir,
+}
+
+/// Pick the config directory: explicit override, then `~/.config`, then
+/// `$XDG_CONFIG_HOME`, then the working directory. The later two only matter in
+/// sandboxes where the home directory can't be determined.
+fn resolve_config_dir(
+    env_override: Option<PathBuf>,
+    home: Option<PathBuf>,
+    xdg_config_home: Option<PathBuf>,
+    cwd: Option<PathBuf>,
+) -> Option<(PathBuf, ConfigDirSource)> {
+    let non_empty = |p: Option<PathBuf>| p.filter(|p| !p.as_os_str().is_empty());
+    if let Some(dir) = non_empty(env_override) {
+        return Some((dir, ConfigDirSource::Env));
+    }
+    if let Some(home) = home {
+        return Some((home.join(".config").join("claude-tracker"), ConfigDirSource::Home));
+    }
+    if let Some(xdg) = non_empty(xdg_config_home) {
+        return Some((xdg.join("claude-tracker"), ConfigDirSource::Xdg));
+    }
+    cwd.map(|cwd| (cwd.join(".claude-tracker"), ConfigDirSource::CurrentDir))
+}
+
+fn locate_config_dir() -> Option<(PathBuf, ConfigDirSource)> {
+    resolve_config_dir(
+        std::env::var_os(CONFIG_DIR_ENV).map(PathBuf::from),
+        dirs::home_dir(),
+        std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+        std::env::current_dir().ok(),
+    )
+}
+
 pub fn config_dir() -> Result<PathBuf, ConfigError> {
-    let home = dirs::home_dir().ok_or(ConfigError::NoHomeDir)?;
-    Ok(home.join(".config").join("claude-tracker"))
+    locate_config_dir()
+        .map(|(dir, _)| dir)
+        .ok_or(ConfigError::NoHomeDir)
+}
+
+/// A message worth showing at startup when the home directory was unavailable and
+/// the config lives somewhere the user might not expect.
+pub fn config_dir_notice() -> Option<String> {
+    match locate_config_dir()? {
+        (dir, ConfigDirSource::Xdg | ConfigDirSource::CurrentDir) => Some(format!(
+            "No home directory — using config at {} (set {CONFIG_DIR_ENV} to choose)",
+            dir.display()
+        )),
+        _ => None,
+    }
+}
+
+/// Profile chosen with `--profile`, set once at startup before anything reads config.
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Use the named profile for this run: its own config file under `profiles/` and its
+/// own keyring service, so account sets stay fully separate.
+pub fn set_profile(name: &str) -> Result<(), ConfigError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(ConfigError::InvalidProfile(name.to_string()));
+    }
+    // Only called once, from main; a second call would keep the first profile
+    let _ = PROFILE.set(name.to_string());
+    Ok(())
+}
+
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+fn profile_file(dir: &Path, profile: Option<&str>, default_name: &str, extension: &str) -> PathBuf {
+    match profile {
+        Some(name) => dir.join("profiles").join(format!("{name}.{extension}")),
+        None => dir.join(default_name),
+    }
+}
+
+/// Keyring service the tracker's own credentials are stored under.
+pub fn keyring_service() -> String {
+    keyring_service_for(profile())
+}
+
+fn keyring_service_for(profile: Option<&str>) -> String {
+    match profile {
+        Some(name) => format!("claude-tracker-{name}"),
+        None => "claude-tracker".to_string(),
+    }
 }
 
 pub fn config_path() -> Result<PathBuf, ConfigError> {
-    Ok(config_dir()?.join("config.toml"))
+    Ok(profile_file(&config_dir()?, profile(), "config.toml", "toml"))
 }
 
-const MIN_POLL_INTERVAL_SECS: u64 = 30;
+pub fn default_csv_path() -> Result<PathBuf, ConfigError> {
+    Ok(profile_file(&config_dir()?, profile(), "usage.csv", "usage.csv"))
+}
+
+/// Where `y` writes the copied line when there's no clipboard to put it on.
+pub fn copy_fallback_path() -> Result<PathBuf, ConfigError> {
+    Ok(profile_file(&config_dir()?, profile(), "copy.txt", "copy.txt"))
+}
+
+/// Where `W` writes the redacted debug bundle.
+pub fn debug_bundle_path() -> Result<PathBuf, ConfigError> {
+    Ok(profile_file(&config_dir()?, profile(), "debug-bundle.json", "debug-bundle.json"))
+}
+
+pub(crate) const MIN_POLL_INTERVAL_SECS: u64 = 30;
+/// A day; also keeps the window within what `chrono::Duration` can hold.
+const MAX_FAST_POLL_WINDOW_SECS: u64 = 24 * 60 * 60;
+const BAR_WIDTH_RANGE: std::ops::RangeInclusive<u16> = 3..=40;
+/// Per account, so a typo can't push the last account's fetch minutes out.
+const MAX_FETCH_STAGGER_MS: u64 = 5_000;
+
+impl Settings {
+    /// Clamp values with hard limits (the poll floor, bar width) and replace unusable
+    /// ones. Applied on load and again on save, where the TUI also normalizes its own
+    /// settings, so a value set at runtime can't stay in use or reach the file out of range.
+    pub fn normalized(mut self) -> Self {
+        self.poll_interval_secs = self.poll_interval_secs.max(MIN_POLL_INTERVAL_SECS);
+        self.fast_poll_window_secs = self.fast_poll_window_secs.min(MAX_FAST_POLL_WINDOW_SECS);
+        self.fetch_stagger_ms = self.fetch_stagger_ms.min(MAX_FETCH_STAGGER_MS);
+        // 0 would time out every fetch before it starts
+        self.once_deadline_secs = self.once_deadline_secs.max(1);
+        self.weekly_hide_below_pct = self.weekly_hide_below_pct.min(100);
+        self.bar_width = self
+            .bar_width
+            .clamp(*BAR_WIDTH_RANGE.start(), *BAR_WIDTH_RANGE.end());
+        // Used as a prefix: empty would match every keychain item
+        if self.claude_code_keychain_service.trim().is_empty() {
+            self.claude_code_keychain_service = default_keychain_service();
+        }
+        self
+    }
+}
+
+/// Upgrade a raw config table to `CONFIG_VERSION` in place, one version step at a
+/// time. Returns true if anything changed. Running it on a current config is a no-op.
+fn migrate(raw: &mut toml::Table) -> bool {
+    let mut version = raw
+        .get("version")
+        .and_then(|v| v.as_integer())
+        .unwrap_or(0);
+    if version >= i64::from(CONFIG_VERSION) {
+        return false;
+    }
+
+    if version == 0 {
+        // v0 -> v1: early builds wrote `auth_method = "o_auth"`.
+        if let Some(accounts) = raw.get_mut("accounts").and_then(|a| a.as_array_mut()) {
+            for account in accounts.iter_mut().filter_map(|a| a.as_table_mut()) {
+                if account.get("auth_method").and_then(|m| m.as_str()) == Some("o_auth") {
+                    account.insert("auth_method".to_string(), "oauth".into());
+                }
+            }
+        }
+        version = 1;
+    }
+
+    if version == 1 {
+        // v1 -> v2: `active_account` was an index into `accounts`; store the name.
+        let index = raw
+            .get("settings")
+            .and_then(|s| s.get("active_account"))
+            .and_then(|a| a.as_integer());
+        if let Some(index) = index {
+            let name = raw
+                .get("accounts")
+                .and_then(|a| a.as_array())
+                .and_then(|accounts| accounts.get(usize::try_from(index).ok()?))
+                .and_then(|account| account.get("name"))
+                .and_then(|n| n.as_str())
+                .map(str::to_string);
+            if let Some(settings) = raw.get_mut("settings").and_then(|s| s.as_table_mut()) {
+                match name {
+                    Some(name) => settings.insert("active_account".to_string(), name.into()),
+                    None => settings.remove("active_account"),
+                };
+            }
+        }
+        version = 2;
+    }
+
+    raw.insert("version".to_string(), toml::Value::Integer(version));
+    true
+}
+
+/// Parse config file contents, migrating older schemas. Returns the config and whether
+/// a migration changed it. A BOM or whitespace-only file reads as the default config.
+fn parse_config(contents: &str) -> Result<(Config, bool), toml::de::Error> {
+    let contents = contents.trim_start_matches('\u{feff}');
+    let mut raw: toml::Table = if contents.trim().is_empty() {
+        toml::Table::new()
+    } else {
+        toml::from_str(contents)?
+    };
+    let migrated = migrate(&mut raw);
+    let config: Config = toml::Value::Table(raw).try_into()?;
+    Ok((config, migrated))
+}
 
 pub fn load_or_init() -> Result<Config, ConfigError> {
-    let path = config_path()?;
-    match std::fs::read_to_string(&path) {
+    load_or_init_at(&config_path()?)
+}
+
+fn load_or_init_at(path: &Path) -> Result<Config, ConfigError> {
+    let _lock = lock_config(path)?;
+    read_locked(path)
+}
+
+/// Load the config, apply `change` and save it, all under the config lock, so no
+/// other instance can save in between and have its change overwritten. A file from a
+/// newer build is left alone: rewriting it here would drop the fields we don't know.
+pub fn update<T>(change: impl FnOnce(&mut Config) -> T) -> Result<T, ConfigError> {
+    update_at(&config_path()?, change)
+}
+
+fn update_at<T>(path: &Path, change: impl FnOnce(&mut Config) -> T) -> Result<T, ConfigError> {
+    let _lock = lock_config(path)?;
+    let mut config = read_locked(path)?;
+    if config.version > CONFIG_VERSION {
+        return Err(ConfigError::NewerVersion { path: path.to_path_buf(), found: config.version });
+    }
+    let result = change(&mut config);
+    config.settings = std::mem::take(&mut config.settings).normalized();
+    write_locked(path, &config)?;
+    Ok(result)
+}
+
+/// Read (or create) the config while the caller holds the lock. A migrated or
+/// missing file is written back, so the file on disk carries the new version.
+fn read_locked(path: &Path) -> Result<Config, ConfigError> {
+    match std::fs::read_to_string(path) {
         Ok(contents) => {
-            let mut config: Config = toml::from_str(&contents)?;
-            config.settings.poll_interval_secs =
-                config.settings.poll_interval_secs.max(MIN_POLL_INTERVAL_SECS);
+            let (mut config, migrated) = parse_config(&contents)
+                .map_err(|source| ConfigError::InvalidFile { path: path.to_path_buf(), source })?;
+            if migrated {
+                write_locked(path, &config)?;
+            }
+            config.settings = config.settings.normalized();
             Ok(config)
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             let config = Config {
+                version: CONFIG_VERSION,
                 settings: Settings::default(),
                 accounts: vec![],
             };
-            save(&config)?;
+            write_locked(path, &config)?;
             Ok(config)
         }
         Err(e) => Err(e.into()),
     }
 }
 
-pub fn save(config: &Config) -> Result<(), ConfigError> {
-    let path = config_path()?;
+/// Write while the caller holds the lock.
+fn write_locked(path: &Path, config: &Config) -> Result<(), ConfigError> {
+    let toml_str = toml::to_string_pretty(config)?;
+    // Atomic write: write to temp file then rename, so a crash can't corrupt the config
+    let tmp_path = unique_tmp_path(path);
+    let written = std::fs::write(&tmp_path, &toml_str).and_then(|()| std::fs::rename(&tmp_path, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    Ok(written?)
+}
+
+/// Advisory lock on a sibling `config.toml.lock`, released when the file is dropped.
+/// Held across a whole read-modify-write (see `update`), not just the write.
+fn lock_config(path: &Path) -> std::io::Result<std::fs::File> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
-    let toml_str = toml::to_string_pretty(config)?;
-    // Atomic write: write to temp file then rename, so a crash can't corrupt the config
-    let tmp_path = path.with_extension("toml.tmp");
-    std::fs::write(&tmp_path, &toml_str)?;
-    std::fs::rename(&tmp_path, &path)?;
-    Ok(())
+    let lock = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(path.with_extension("toml.lock"))?;
+    lock.lock()?;
+    Ok(lock)
+}
+
+/// `config.toml.<pid>.<random>.tmp`, so concurrent writers never share a temp file.
+fn unique_tmp_path(path: &Path) -> PathBuf {
+    path.with_extension(format!("toml.{}.{:08x}.tmp", std::process::id(), rand::random::<u32>()))
+}
+
+/// One tab-separated line per account: name, org_id, auth method. Used by `list`.
+pub fn account_list(config: &Config) -> String {
+    config
+        .accounts
+        .iter()
+        .map(|acct| {
+            let auth = match acct.auth_method {
+                AuthMethod::SessionKey => "session_key",
+                AuthMethod::OAuth => "oauth",
+            };
+            let org = if acct.org_id.is_empty() { "-" } else { &acct.org_id };
+            format!("{}\t{}\t{}\n", acct.name, org, auth)
+        })
+        .collect()
+}
+
+/// Accounts whose name contains `query`, ignoring case. An exact (case-insensitive)
+/// name match wins outright, so `work` can still pick "work" next to "work-2".
+pub fn match_accounts<'a>(accounts: &'a [AccountConfig], query: &str) -> Vec<&'a AccountConfig> {
+    let query = query.to_lowercase();
+    if let Some(exact) = accounts.iter().find(|a| a.name.to_lowercase() == query) {
+        return vec![exact];
+    }
+    accounts
+        .iter()
+        .filter(|a| a.name.to_lowercase().contains(&query))
+        .collect()
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_list_prints_one_line_per_account() {
+        let (config, _) = parse_config(
+            r#"
+            [[accounts]]
+            name = "work"
+            org_id = "org-1"
+            auth_method = "oauth"
+
+            [[accounts]]
+            name = "personal"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(account_list(&config), "work\torg-1\toauth\npersonal\t-\tsession_key\n");
+    }
+
+    #[test]
+    fn concurrent_saves_leave_a_valid_config_and_no_temp_files() {
+        let dir = std::env::temp_dir().join(format!("claude-tracker-save-{}", std::process::id()));
+        let path = dir.join("config.toml");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_at(&path, |config| config.settings.poll_interval_secs = 30 + i).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let (config, _) = parse_config(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!((30..38).contains(&config.settings.poll_interval_secs));
+        let leftovers: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_updates_keep_every_added_account() {
+        let dir = std::env::temp_dir().join(format!("claude-tracker-update-{}", std::process::id()));
+        let path = dir.join("config.toml");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_at(&path, |config| {
+                        config.accounts.push(AccountConfig {
+                            name: format!("account-{i}"),
+                            ..Default::default()
+                        });
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let config = load_or_init_at(&path).unwrap();
+        assert_eq!(config.accounts.len(), 8, "No update overwrote another");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn save_enforces_poll_floor_set_at_runtime() {
+        let dir = std::env::temp_dir().join(format!("claude-tracker-floor-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        update_at(&path, |config| {
+            config.settings.poll_interval_secs = 5;
+            config.settings.bar_width = 200;
+        })
+        .unwrap();
+
+        let (saved, _) = parse_config(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.settings.poll_interval_secs, MIN_POLL_INTERVAL_SECS);
+        assert_eq!(saved.settings.bar_width, *BAR_WIDTH_RANGE.end());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn newer_config_version_is_not_overwritten() {
+        let dir = std::env::temp_dir().join(format!("claude-tracker-newer-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        let contents = "version = 99\nfuture_field = true\n";
+        std::fs::write(&path, contents).unwrap();
+
+        let err = update_at(&path, |config| config.settings.poll_interval_secs = 600).unwrap_err();
+        assert!(matches!(err, ConfigError::NewerVersion { found: 99, .. }), "{err}");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn huge_fast_poll_window_is_capped() {
+        let (config, _) = parse_config(&format!("[settings]\nfast_poll_window_secs = {}", i64::MAX)).unwrap();
+        let settings = config.settings.normalized();
+        assert_eq!(settings.fast_poll_window_secs, MAX_FAST_POLL_WINDOW_SECS);
+    }
+
+    #[test]
+    fn fetch_stagger_is_capped() {
+        let (config, _) = parse_config("[settings]\nfetch_stagger_ms = 600000").unwrap();
+        assert_eq!(config.settings.normalized().fetch_stagger_ms, MAX_FETCH_STAGGER_MS);
+    }
+
+    #[test]
+    fn weekly_hide_threshold_is_capped_at_100() {
+        let (config, _) = parse_config("[settings]\nweekly_hide_below_pct = 500").unwrap();
+        assert_eq!(config.settings.normalized().weekly_hide_below_pct, 100);
+    }
+
+    #[test]
+    fn zero_once_deadline_is_raised() {
+        let (config, _) = parse_config("[settings]\nonce_deadline_secs = 0").unwrap();
+        assert_eq!(config.settings.normalized().once_deadline_secs, 1);
+    }
+
+    #[test]
+    fn session_key_expiry_accepts_dates_and_toml_datetimes() {
+        let expiry = |value: &str| {
+            let toml = format!("[[accounts]]\nname = \"a\"\norg_id = \"o\"\nsession_key_expires = {value}");
+            parse_config(&toml).map(|(config, _)| config.accounts[0].session_key_expires.unwrap().to_rfc3339())
+        };
+        let midnight = "2026-11-01T00:00:00+00:00";
+        assert_eq!(expiry("\"2026-11-01\"").unwrap(), midnight);
+        assert_eq!(expiry("2026-11-01").unwrap(), midnight);
+        assert_eq!(expiry("2026-11-01T00:00:00Z").unwrap(), midnight);
+        assert_eq!(expiry("\"2026-11-01T02:00:00+02:00\"").unwrap(), midnight);
+        assert_eq!(expiry("2026-11-01T00:00:00").unwrap(), midnight);
+        assert!(expiry("\"next week\"").unwrap_err().to_string().contains("not a date"));
+    }
+
+    #[test]
+    fn empty_keychain_service_falls_back_to_default() {
+        let (config, _) = parse_config("[settings]\nclaude_code_keychain_service = \" \"").unwrap();
+        let settings = config.settings.normalized();
+        assert_eq!(settings.claude_code_keychain_service, crate::oauth::DEFAULT_KEYCHAIN_SERVICE);
+    }
+
+    #[test]
+    fn profiles_get_their_own_file_and_keyring_service() {
+        let dir = Path::new("/cfg");
+        assert_eq!(profile_file(dir, None, "config.toml", "toml"), dir.join("config.toml"));
+        assert_eq!(
+            profile_file(dir, Some("work"), "config.toml", "toml"),
+            dir.join("profiles").join("work.toml")
+        );
+        assert_eq!(keyring_service_for(None), "claude-tracker");
+        assert_eq!(keyring_service_for(Some("work")), "claude-tracker-work");
+    }
+
+    #[test]
+    fn profile_names_cannot_escape_the_profiles_dir() {
+        for bad in ["", "../x", "a/b", "a b"] {
+            assert!(matches!(set_profile(bad), Err(ConfigError::InvalidProfile(_))), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn match_accounts_by_substring_with_exact_preference() {
+        let (config, _) = parse_config(
+            r#"
+            [[accounts]]
+            name = "Work@corp.example"
+            [[accounts]]
+            name = "work"
+            [[accounts]]
+            name = "me@home.example"
+            "#,
+        )
+        .unwrap();
+        let names = |q: &str| -> Vec<String> {
+            match_accounts(&config.accounts, q).iter().map(|a| a.name.clone()).collect()
+        };
+        assert_eq!(names("WORK"), ["work"]);
+        assert_eq!(names("example"), ["Work@corp.example", "me@home.example"]);
+        assert_eq!(names("corp"), ["Work@corp.example"]);
+        assert!(names("nobody").is_empty());
+    }
+
+    #[test]
+    fn config_dir_prefers_override_then_home() {
+        let home = Some(PathBuf::from("/home/u"));
+        let xdg = Some(PathBuf::from("/xdg"));
+        let cwd = Some(PathBuf::from("/work"));
+
+        let (dir, source) =
+            resolve_config_dir(Some("/custom".into()), home.clone(), xdg.clone(), cwd.clone()).unwrap();
+        assert_eq!((dir, source), (PathBuf::from("/custom"), ConfigDirSource::Env));
+
+        let (dir, source) = resolve_config_dir(None, home, xdg, cwd).unwrap();
+        assert_eq!(dir, PathBuf::from("/home/u/.config/claude-tracker"));
+        assert_eq!(source, ConfigDirSource::Home);
+    }
+
+    #[test]
+    fn config_dir_falls_back_without_home() {
+        let cwd = Some(PathBuf::from("/work"));
+
+        let (dir, source) =
+            resolve_config_dir(None, None, Some("/xdg".into()), cwd.clone()).unwrap();
+        assert_eq!((dir, source), (PathBuf::from("/xdg/claude-tracker"), ConfigDirSource::Xdg));
+
+        // Empty env values count as unset
+        let (dir, source) =
+            resolve_config_dir(Some("".into()), None, Some("".into()), cwd).unwrap();
+        assert_eq!(dir, PathBuf::from("/work/.claude-tracker"));
+        assert_eq!(source, ConfigDirSource::CurrentDir);
+
+        assert!(resolve_config_dir(None, None, None, None).is_none());
+    }
+
+    #[test]
+    fn migrate_v0_renames_o_auth_and_stamps_version() {
+        let mut raw: toml::Table = toml::from_str(
+            r#"
+            [settings]
+            poll_interval_secs = 60
+
+            [[accounts]]
+            name = "a@example.com"
+            org_id = "org-a"
+            auth_method = "o_auth"
+            "#,
+        )
+        .unwrap();
+
+        assert!(migrate(&mut raw));
+        assert_eq!(raw["version"].as_integer(), Some(i64::from(CONFIG_VERSION)));
+        assert_eq!(raw["accounts"][0]["auth_method"].as_str(), Some("oauth"));
+
+        let config: Config = toml::Value::Table(raw).try_into().unwrap();
+        assert_eq!(config.accounts[0].auth_method, AuthMethod::OAuth);
+        assert_eq!(config.settings.poll_interval_secs, 60);
+    }
+
+    #[test]
+    fn migrate_v1_stores_active_account_by_name() {
+        let mut raw: toml::Table = toml::from_str(
+            r#"
+            version = 1
+            [settings]
+            active_account = 1
+
+            [[accounts]]
+            name = "a@example.com"
+
+            [[accounts]]
+            name = "b@example.com"
+            "#,
+        )
+        .unwrap();
+        assert!(migrate(&mut raw));
+        let config: Config = toml::Value::Table(raw).try_into().unwrap();
+        assert_eq!(config.settings.active_account.as_deref(), Some("b@example.com"));
+
+        // An index past the end is dropped rather than guessed
+        let mut raw: toml::Table =
+            toml::from_str("version = 1\n[settings]\nactive_account = 5\n").unwrap();
+        assert!(migrate(&mut raw));
+        let config: Config = toml::Value::Table(raw).try_into().unwrap();
+        assert_eq!(config.settings.active_account, None);
+    }
+
+    #[test]
+    fn empty_or_bom_only_file_reads_as_default() {
+        for contents in ["", "   \n\t\n", "\u{feff}", "\u{feff}\n"] {
+            let (config, migrated) = parse_config(contents).unwrap();
+            assert!(config.accounts.is_empty());
+            assert_eq!(config.settings.poll_interval_secs, default_poll_interval());
+            assert!(migrated, "Defaults are written back");
+        }
+    }
+
+    #[test]
+    fn bom_prefixed_config_parses() {
+        let (config, _) = parse_config("\u{feff}version = 1\n[settings]\npoll_interval_secs = 90\n").unwrap();
+        assert_eq!(config.settings.poll_interval_secs, 90);
+    }
+
+    #[test]
+    fn invalid_config_error_names_file_and_suggests_deleting() {
+        let source = parse_config("[settings\npoll = ").unwrap_err();
+        let err = ConfigError::InvalidFile {
+            path: PathBuf::from("/tmp/config.toml"),
+            source,
+        };
+        let msg = err.to_string();
+        assert!(msg.contains("/tmp/config.toml"), "got: {msg}");
+        assert!(msg.contains("delete"), "got: {msg}");
+    }
+
+    #[test]
+    fn migrate_is_idempotent() {
+        let mut raw: toml::Table = toml::from_str("[settings]\n").unwrap();
+        assert!(migrate(&mut raw));
+        let once = raw.clone();
+        assert!(!migrate(&mut raw), "Second run must report no change");
+        assert_eq!(raw, once);
+    }
 }
@@ -0,0 +1,133 @@
+//! Redacted dump of the running state for bug reports (`W`). Everything a maintainer
+//! needs in one file; nothing that could authenticate as the user.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::app::AppState;
+use crate::config::Config;
+use crate::keyring_store::KeyringAuditEntry;
+use crate::usage_snapshot::UsageSnapshot;
+
+/// Most recent keyring log entries to include.
+const LOG_ENTRIES: usize = 50;
+
+const REDACTED: &str = "***";
+
+#[derive(Debug, Serialize)]
+pub struct DebugBundle {
+    pub version: &'static str,
+    /// `os/arch`, e.g. "macos/aarch64".
+    pub platform: String,
+    pub generated_at: DateTime<Utc>,
+    pub config: Config,
+    pub usage: UsageSnapshot,
+    pub fetch_stats: Vec<FetchStatsEntry>,
+    /// Empty unless the `keyring_audit` setting is on.
+    pub keyring_log: Vec<LogEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FetchStatsEntry {
+    pub name: String,
+    pub attempts: u32,
+    pub successes: u32,
+    pub average_latency_ms: Option<u128>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogEntry {
+    pub at: DateTime<Utc>,
+    pub operation: &'static str,
+    pub account: String,
+    pub error: Option<String>,
+}
+
+impl DebugBundle {
+    pub fn from_app(app: &AppState, now: DateTime<Utc>) -> Self {
+        let log = app.keyring_audit.as_ref().map(|a| a.entries()).unwrap_or_default();
+        let skip = log.len().saturating_sub(LOG_ENTRIES);
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            platform: format!("{}/{}", std::env::consts::OS, std::env::consts::ARCH),
+            generated_at: now,
+            config: redact(app.current_config()),
+            usage: UsageSnapshot::from_app(app, now),
+            fetch_stats: app
+                .accounts
+                .iter()
+                .map(|a| FetchStatsEntry {
+                    name: a.config.name.clone(),
+                    attempts: a.stats.attempts,
+                    successes: a.stats.successes,
+                    average_latency_ms: a.stats.average_latency().map(|d| d.as_millis()),
+                    last_error_at: a.stats.last_error_at,
+                })
+                .collect(),
+            keyring_log: log.into_iter().skip(skip).map(LogEntry::from).collect(),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl From<KeyringAuditEntry> for LogEntry {
+    fn from(entry: KeyringAuditEntry) -> Self {
+        Self {
+            at: entry.at,
+            operation: entry.operation,
+            account: entry.account_name,
+            error: entry.outcome.err(),
+        }
+    }
+}
+
+/// Credentials live in the keyring, not the config, but header values and the
+/// swap hook's command line can carry tokens too.
+fn redact(mut cfg: Config) -> Config {
+    for account in &mut cfg.accounts {
+        for value in account.headers.values_mut() {
+            *value = REDACTED.to_string();
+        }
+    }
+    if let Some(command) = &mut cfg.settings.on_swap_command {
+        *command = REDACTED.to_string();
+    }
+    cfg
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AccountConfig;
+
+    #[test]
+    fn redact_strips_header_values_and_swap_command() {
+        let mut cfg: Config = toml::from_str("").unwrap();
+        cfg.settings.on_swap_command = Some("notify --token abc123".to_string());
+        cfg.accounts.push(AccountConfig {
+            name: "work".to_string(),
+            org_id: "org-1".to_string(),
+            headers: [("Cookie".to_string(), "sessionKey=sk-ant-secret".to_string())].into(),
+            ..Default::default()
+        });
+
+        let json = serde_json::to_string(&redact(cfg)).unwrap();
+        assert!(!json.contains("sk-ant-secret"), "{json}");
+        assert!(!json.contains("abc123"), "{json}");
+        assert!(json.contains("\"Cookie\":\"***\""), "Header names stay: {json}");
+        assert!(json.contains("org-1"), "{json}");
+    }
+}
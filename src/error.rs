@@ -7,11 +7,15 @@ pub enum TrackerError {
 
     #[error("Keyring error: {0}")]
     Keyring(String),
+
+    /// The keyring answered, and has no entry for this account.
+    #[error("No key for '{0}' in the keyring")]
+    KeyNotFound(String),
 }
 
 #[derive(Error, Debug)]
 pub enum ConfigError {
-    #[error("Could not determine home directory")]
+    #[error("Could not determine a config directory (set CLAUDE_TRACKER_CONFIG_DIR)")]
     NoHomeDir,
 
     #[error("Failed to read config: {0}")]
@@ -20,6 +24,24 @@ pub enum ConfigError {
     #[error("Failed to parse config: {0}")]
     ParseFailed(#[from] toml::de::Error),
 
+    #[error("Config file {} is not valid TOML — fix it, or delete it to start fresh:\n{source}", path.display())]
+    InvalidFile {
+        path: std::path::PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error(
+        "Config file {} is from a newer claude-tracker (version {found}); not saving, so its newer settings aren't lost. Upgrade to change it",
+        path.display()
+    )]
+    NewerVersion {
+        path: std::path::PathBuf,
+        found: u32,
+    },
+
+    #[error("Invalid profile name '{0}' (use letters, digits, '-' and '_')")]
+    InvalidProfile(String),
+
     #[error("Failed to serialize config: {0}")]
     SerializeFailed(#[from] toml::ser::Error),
 }
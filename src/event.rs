@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use crossterm::event::{EventStream, KeyEvent};
 use futures::StreamExt;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio::task::JoinHandle;
 
 use crate::app::UsageData;
@@ -10,11 +10,20 @@ use crate::app::UsageData;
 #[derive(Debug)]
 pub enum Event {
     Key(KeyEvent),
+    /// Bracketed paste — the whole clipboard payload in one event.
+    Paste(String),
     Tick,
     Render,
     UsageResult {
         account_name: String,
         result: Result<UsageData, String>,
+        /// Wall time from request to result, including any token refresh.
+        elapsed: Duration,
+    },
+    /// Pretty-printed, secret-masked usage body for the raw JSON viewer.
+    RawUsageResult {
+        account_name: String,
+        result: Result<String, String>,
     },
     OAuthImportResult {
         result: Result<Vec<OAuthImportData>, String>,
@@ -26,16 +35,44 @@ pub enum Event {
     OAuthLoginResult {
         result: Result<OAuthImportData, String>,
     },
+    /// Claude Code's keychain holds a newer credential for a tracked account.
+    ClaudeCodeSynced {
+        account_name: String,
+        raw_credential: String,
+    },
+    /// `on_swap_command` finished after a swap to this account.
+    SwapHookFinished {
+        account_name: String,
+        result: Result<(), String>,
+    },
+    /// Whose credential was pasted to repair `account_name`.
+    ReplacementIdentified {
+        account_name: String,
+        result: Result<OAuthImportData, String>,
+    },
+    /// Outcome of the Add dialog's pre-save check.
+    AccountValidated {
+        result: Result<(), String>,
+    },
+    /// Orgs for the session key in the Add/Edit dialog (Ctrl+O).
+    OrgsFound {
+        result: Result<Vec<crate::app::OrgChoice>, String>,
+    },
     LoggedInDetected {
         account_name: Option<String>,
     },
     Resize,
+    /// SIGTERM/SIGINT arrived outside the terminal (session manager, `kill`):
+    /// leave the loop so the terminal is restored.
+    Quit,
 }
 
 #[derive(Debug)]
 pub struct OAuthImportData {
     pub name: String,
     pub org_id: String,
+    /// Subscription plan reported by the profile endpoint, if any.
+    pub plan: Option<String>,
     /// Raw credential JSON (contains access token, refresh token, expiry).
     pub raw_credential: String,
 }
@@ -43,13 +80,16 @@ pub struct OAuthImportData {
 pub struct EventHandler {
     tx: mpsc::UnboundedSender<Event>,
     rx: mpsc::UnboundedReceiver<Event>,
-    _task: JoinHandle<()>,
+    render_rate: watch::Sender<Duration>,
+    task: JoinHandle<()>,
+    signals: JoinHandle<()>,
 }
 
 impl EventHandler {
     pub fn new(tick_rate: Duration, render_rate: Duration) -> Self {
         let (tx, rx) = mpsc::unbounded_channel();
         let sender = tx.clone();
+        let (render_rate_tx, mut render_rate_rx) = watch::channel(render_rate);
         let task = tokio::spawn(async move {
             let mut reader = EventStream::new();
             let mut tick_interval = tokio::time::interval(tick_rate);
@@ -62,6 +102,9 @@ impl EventHandler {
                                 crossterm::event::Event::Key(key) => {
                                     let _ = sender.send(Event::Key(key));
                                 }
+                                crossterm::event::Event::Paste(text) => {
+                                    let _ = sender.send(Event::Paste(text));
+                                }
                                 crossterm::event::Event::Resize(..) => {
                                     let _ = sender.send(Event::Resize);
                                 }
@@ -77,16 +120,31 @@ impl EventHandler {
                     _ = render_interval.tick() => {
                         let _ = sender.send(Event::Render);
                     }
+                    Ok(()) = render_rate_rx.changed() => {
+                        render_interval = tokio::time::interval(*render_rate_rx.borrow_and_update());
+                    }
                 }
             }
         });
+        let signals = tokio::spawn(wait_for_termination(tx.clone()));
         Self {
             tx,
             rx,
-            _task: task,
+            render_rate: render_rate_tx,
+            task,
+            signals,
         }
     }
 
+    /// Change how often `Render` is emitted, taking effect immediately.
+    pub fn set_render_rate(&self, rate: Duration) {
+        self.render_rate.send_if_modified(|current| {
+            let changed = *current != rate;
+            *current = rate;
+            changed
+        });
+    }
+
     pub fn sender(&self) -> mpsc::UnboundedSender<Event> {
         self.tx.clone()
     }
@@ -94,4 +152,77 @@ impl EventHandler {
     pub async fn next(&mut self) -> Option<Event> {
         self.rx.recv().await
     }
+
+    /// Stop reading terminal input and emitting ticks. Events already queued can
+    /// still be drained with `next`. Also runs on drop.
+    pub fn shutdown(&self) {
+        self.task.abort();
+        self.signals.abort();
+    }
+}
+
+impl Drop for EventHandler {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.signals.abort();
+    }
+}
+
+/// Send `Event::Quit` on the first SIGTERM or SIGINT. In raw mode Ctrl+C arrives as a
+/// key, so SIGINT here only comes from outside the terminal.
+#[cfg(unix)]
+async fn wait_for_termination(tx: mpsc::UnboundedSender<Event>) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let (Ok(mut term), Ok(mut int)) = (signal(SignalKind::terminate()), signal(SignalKind::interrupt()))
+    else {
+        return;
+    };
+    tokio::select! {
+        _ = term.recv() => {}
+        _ = int.recv() => {}
+    }
+    let _ = tx.send(Event::Quit);
+}
+
+#[cfg(not(unix))]
+async fn wait_for_termination(tx: mpsc::UnboundedSender<Event>) {
+    if tokio::signal::ctrl_c().await.is_ok() {
+        let _ = tx.send(Event::Quit);
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn shutdown_stops_the_event_task() {
+        let handler = EventHandler::new(Duration::from_secs(60), Duration::from_secs(60));
+        handler.shutdown();
+        for _ in 0..100 {
+            if handler.task.is_finished() && handler.signals.is_finished() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("Event task still running after shutdown");
+    }
+
+    #[tokio::test]
+    async fn drop_aborts_the_event_task() {
+        let handler = EventHandler::new(Duration::from_secs(60), Duration::from_secs(60));
+        let abort = handler.task.abort_handle();
+        drop(handler);
+        for _ in 0..100 {
+            if abort.is_finished() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("Event task still running after drop");
+    }
 }
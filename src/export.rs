@@ -0,0 +1,112 @@
+use std::io::Write;
+use std::path::Path;
+
+use crate::usage_snapshot::UsageSnapshot;
+
+const CSV_HEADER: &str =
+    "timestamp,account,five_hour_pct,seven_day_pct,five_hour_reset,seven_day_reset";
+
+/// One CSV line per account, using the same reset-adjusted utilization the table shows.
+/// Accounts with no usage yet get empty percentage/reset fields.
+pub fn csv_rows(snapshot: &UsageSnapshot) -> Vec<String> {
+    let timestamp = snapshot.taken_at.to_rfc3339();
+    let opt = |v: Option<String>| v.unwrap_or_default();
+    snapshot
+        .accounts
+        .iter()
+        .map(|account| {
+            [
+                timestamp.clone(),
+                csv_field(&account.name),
+                opt(account.five_hour_pct.map(|p| p.to_string())),
+                opt(account.seven_day_pct.map(|p| p.to_string())),
+                opt(account.five_hour_resets_at.map(|t| t.to_rfc3339())),
+                opt(account.seven_day_resets_at.map(|t| t.to_rfc3339())),
+            ]
+            .join(",")
+        })
+        .collect()
+}
+
+/// Append rows to `path`, writing the header first if the file is new or empty.
+pub fn append_csv(path: &Path, rows: &[String]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let needs_header = std::fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true);
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if needs_header {
+        writeln!(file, "{CSV_HEADER}")?;
+    }
+    for row in rows {
+        writeln!(file, "{row}")?;
+    }
+    Ok(())
+}
+
+/// Quote a field if it contains a delimiter, quote, or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::app::tests::test_account;
+    use crate::app::{AccountState, AccountStatus, UsageData};
+
+    fn account(name: &str, usage: Option<UsageData>) -> AccountState {
+        AccountState {
+            usage,
+            status: AccountStatus::Ok,
+            ..test_account(name)
+        }
+    }
+
+    #[test]
+    fn csv_row_uses_reset_adjusted_utilization() {
+        let now = Utc::now();
+        let usage = UsageData {
+            utilization: 80,
+            resets_at: Some(now - chrono::Duration::minutes(1)),
+            weekly_utilization: Some(40),
+            weekly_resets_at: None,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
+        };
+        let snapshot = UsageSnapshot::new(&[account("work", Some(usage))], None, None, now);
+        let rows = csv_rows(&snapshot);
+        let fields: Vec<&str> = rows[0].split(',').collect();
+        assert_eq!(fields[1], "work");
+        assert_eq!(fields[2], "0", "Passed 5h reset must export as 0%");
+        assert_eq!(fields[3], "40");
+        assert_eq!(fields[5], "", "No weekly reset known");
+    }
+
+    #[test]
+    fn csv_row_without_usage_has_empty_fields() {
+        let snapshot = UsageSnapshot::new(&[account("new", None)], None, None, Utc::now());
+        let rows = csv_rows(&snapshot);
+        assert!(rows[0].ends_with(",new,,,,"));
+    }
+
+    #[test]
+    fn csv_field_quotes_commas_and_quotes() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
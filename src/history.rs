@@ -0,0 +1,161 @@
+//! Bounded per-account usage history for trend display. Recent samples keep full
+//! resolution; older ones are thinned so a session left open for days stays small.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Samples within this age keep one per minute.
+const FINE_WINDOW_SECS: i64 = 3600;
+const FINE_BUCKET_SECS: i64 = 60;
+/// Older samples keep one per five minutes, up to a day; anything older is dropped.
+const COARSE_WINDOW_SECS: i64 = 86400;
+const COARSE_BUCKET_SECS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub at: DateTime<Utc>,
+    pub pct: u32,
+}
+
+/// At most 60 fine + 276 coarse samples (plus one per boundary bucket).
+#[derive(Debug, Clone, Default)]
+pub struct HistoryBuffer {
+    samples: VecDeque<Sample>,
+}
+
+impl HistoryBuffer {
+    /// Record `pct` at `at`. Samples are expected in time order; a sample in the
+    /// same bucket as the previous one replaces it, so the latest value wins.
+    pub fn push(&mut self, at: DateTime<Utc>, pct: u32) {
+        self.samples.push_back(Sample { at, pct });
+        self.compact(at);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// At most `width` values, oldest first. Over-long history is split into `width`
+    /// even runs and each run shows its peak, so short spikes aren't averaged away.
+    pub fn samples_for_width(&self, width: usize) -> Vec<u32> {
+        let len = self.samples.len();
+        if len <= width {
+            return self.samples.iter().map(|s| s.pct).collect();
+        }
+        (0..width)
+            .map(|i| {
+                let (start, end) = (i * len / width, (i + 1) * len / width);
+                self.samples.range(start..end).map(|s| s.pct).max().unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Drop samples past the coarse window and keep the last sample per bucket,
+    /// using the bucket size for each sample's age.
+    fn compact(&mut self, now: DateTime<Utc>) {
+        let oldest = now - Duration::seconds(COARSE_WINDOW_SECS);
+        while self.samples.front().is_some_and(|s| s.at < oldest) {
+            self.samples.pop_front();
+        }
+        let bucket = |s: &Sample| {
+            let size = if now - s.at < Duration::seconds(FINE_WINDOW_SECS) {
+                FINE_BUCKET_SECS
+            } else {
+                COARSE_BUCKET_SECS
+            };
+            (size, s.at.timestamp().div_euclid(size))
+        };
+        let mut kept: VecDeque<Sample> = VecDeque::with_capacity(self.samples.len());
+        for sample in self.samples.drain(..) {
+            match kept.back() {
+                Some(prev) if bucket(prev) == bucket(&sample) => {
+                    *kept.back_mut().expect("checked above") = sample;
+                }
+                _ => kept.push_back(sample),
+            }
+        }
+        self.samples = kept;
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    fn all(history: &HistoryBuffer) -> Vec<u32> {
+        history.samples_for_width(usize::MAX)
+    }
+
+    #[test]
+    fn same_minute_keeps_latest() {
+        let mut history = HistoryBuffer::default();
+        history.push(start(), 10);
+        history.push(start() + Duration::seconds(30), 12);
+        assert_eq!(history.samples_for_width(10), vec![12]);
+    }
+
+    #[test]
+    fn last_hour_keeps_one_sample_per_minute() {
+        let mut history = HistoryBuffer::default();
+        for minute in 0..60 {
+            history.push(start() + Duration::minutes(minute), minute as u32);
+        }
+        assert_eq!(all(&history).len(), 60);
+    }
+
+    #[test]
+    fn samples_older_than_an_hour_thin_to_five_minutes() {
+        let mut history = HistoryBuffer::default();
+        for minute in 0..120 {
+            history.push(start() + Duration::minutes(minute), minute as u32);
+        }
+        // Newest at minute 119: minutes 60..=119 are within the hour (60 samples),
+        // minutes 0..=59 collapse into twelve 5-minute buckets.
+        let values = all(&history);
+        assert_eq!(values.len(), 60 + 12);
+        // Each coarse bucket keeps its latest sample
+        assert_eq!(&values[..3], &[4, 9, 14]);
+        assert_eq!(values[12], 60);
+    }
+
+    #[test]
+    fn samples_older_than_a_day_are_dropped() {
+        let mut history = HistoryBuffer::default();
+        history.push(start(), 99);
+        history.push(start() + Duration::seconds(COARSE_WINDOW_SECS), 1);
+        assert_eq!(history.samples_for_width(10), vec![99, 1], "Exactly a day old is kept");
+
+        history.push(start() + Duration::seconds(COARSE_WINDOW_SECS + 60), 2);
+        assert_eq!(history.samples_for_width(10), vec![1, 2]);
+    }
+
+    #[test]
+    fn stays_bounded_over_days() {
+        let mut history = HistoryBuffer::default();
+        for minute in 0..(3 * 24 * 60) {
+            history.push(start() + Duration::minutes(minute), 50);
+        }
+        let len = all(&history).len();
+        assert!(len <= 60 + 276 + 1, "len {len}");
+    }
+
+    #[test]
+    fn width_downsampling_keeps_peaks() {
+        let mut history = HistoryBuffer::default();
+        for (minute, pct) in [5, 80, 5, 5, 5, 5].into_iter().enumerate() {
+            history.push(start() + Duration::minutes(minute as i64), pct);
+        }
+        assert_eq!(history.samples_for_width(3), vec![80, 5, 5]);
+        assert_eq!(history.samples_for_width(0), Vec::<u32>::new());
+    }
+}
@@ -1,30 +1,45 @@
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 
-use crate::error::TrackerError;
+use chrono::{DateTime, Utc};
 
-const SERVICE_NAME: &str = "claude-tracker";
+use crate::error::TrackerError;
 
 /// Trait for keyring operations, allowing injection of mocks in tests.
 pub trait KeyringBackend: Send + Sync {
     fn get_session_key(&self, account_name: &str) -> Result<String, TrackerError>;
     fn set_session_key(&self, account_name: &str, session_key: &str) -> Result<(), TrackerError>;
     fn delete_session_key(&self, account_name: &str) -> Result<(), TrackerError>;
+
+    /// Whether an entry exists. `Err` means the keyring couldn't be asked (e.g. it's
+    /// locked), which is not the same as the entry being gone.
+    fn has_key(&self, account_name: &str) -> Result<bool, TrackerError> {
+        match self.get_session_key(account_name) {
+            Ok(_) => Ok(true),
+            Err(TrackerError::KeyNotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 /// Real keyring backend that uses macOS Keychain.
-pub struct SystemKeyring;
+pub struct SystemKeyring {
+    /// `claude-tracker`, or `claude-tracker-<profile>` under `--profile`.
+    service: String,
+}
 
 impl KeyringBackend for SystemKeyring {
     fn get_session_key(&self, account_name: &str) -> Result<String, TrackerError> {
-        let entry = keyring::Entry::new(SERVICE_NAME, account_name)
+        let entry = keyring::Entry::new(&self.service, account_name)
             .map_err(|e| TrackerError::Keyring(format!("Failed to create keyring entry: {e}")))?;
-        entry
-            .get_password()
-            .map_err(|e| TrackerError::Keyring(format!("Failed to get session key for '{account_name}': {e}")))
+        entry.get_password().map_err(|e| match e {
+            keyring::Error::NoEntry => TrackerError::KeyNotFound(account_name.to_string()),
+            e => TrackerError::Keyring(format!("Failed to get session key for '{account_name}': {e}")),
+        })
     }
 
     fn set_session_key(&self, account_name: &str, session_key: &str) -> Result<(), TrackerError> {
-        let entry = keyring::Entry::new(SERVICE_NAME, account_name)
+        let entry = keyring::Entry::new(&self.service, account_name)
             .map_err(|e| TrackerError::Keyring(format!("Failed to create keyring entry: {e}")))?;
         entry
             .set_password(session_key)
@@ -32,7 +47,7 @@ impl KeyringBackend for SystemKeyring {
     }
 
     fn delete_session_key(&self, account_name: &str) -> Result<(), TrackerError> {
-        let entry = keyring::Entry::new(SERVICE_NAME, account_name)
+        let entry = keyring::Entry::new(&self.service, account_name)
             .map_err(|e| TrackerError::Keyring(format!("Failed to create keyring entry: {e}")))?;
         entry
             .delete_credential()
@@ -41,5 +56,116 @@ impl KeyringBackend for SystemKeyring {
 }
 
 pub fn system_keyring() -> Arc<dyn KeyringBackend> {
-    Arc::new(SystemKeyring)
+    Arc::new(SystemKeyring {
+        service: crate::config::keyring_service(),
+    })
+}
+
+/// Keep only the most recent audit entries so a long session can't grow without bound.
+const AUDIT_CAPACITY: usize = 200;
+
+/// One recorded keyring call.
+#[derive(Debug, Clone)]
+pub struct KeyringAuditEntry {
+    pub at: DateTime<Utc>,
+    pub operation: &'static str,
+    pub account_name: String,
+    /// `Err` holds the backend's error message.
+    pub outcome: Result<(), String>,
+}
+
+/// Decorator that records every call in memory before handing back the inner
+/// backend's result. Enabled by the `keyring_audit` setting for troubleshooting
+/// keychain prompts and failures.
+pub struct AuditingKeyring {
+    inner: Arc<dyn KeyringBackend>,
+    log: Mutex<VecDeque<KeyringAuditEntry>>,
+}
+
+impl AuditingKeyring {
+    pub fn new(inner: Arc<dyn KeyringBackend>) -> Self {
+        Self {
+            inner,
+            log: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Recorded calls, oldest first.
+    pub fn entries(&self) -> Vec<KeyringAuditEntry> {
+        self.log.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn record<T>(&self, operation: &'static str, account_name: &str, result: &Result<T, TrackerError>) {
+        let mut log = self.log.lock().unwrap();
+        if log.len() == AUDIT_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(KeyringAuditEntry {
+            at: Utc::now(),
+            operation,
+            account_name: account_name.to_string(),
+            outcome: result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+        });
+    }
+}
+
+impl KeyringBackend for AuditingKeyring {
+    fn get_session_key(&self, account_name: &str) -> Result<String, TrackerError> {
+        let result = self.inner.get_session_key(account_name);
+        self.record("get", account_name, &result);
+        result
+    }
+
+    fn set_session_key(&self, account_name: &str, session_key: &str) -> Result<(), TrackerError> {
+        let result = self.inner.set_session_key(account_name, session_key);
+        self.record("set", account_name, &result);
+        result
+    }
+
+    fn delete_session_key(&self, account_name: &str) -> Result<(), TrackerError> {
+        let result = self.inner.delete_session_key(account_name);
+        self.record("delete", account_name, &result);
+        result
+    }
+
+    fn has_key(&self, account_name: &str) -> Result<bool, TrackerError> {
+        let result = self.inner.has_key(account_name);
+        self.record("has_key", account_name, &result);
+        result
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers every read with the same error.
+    struct FailingRead(fn(&str) -> TrackerError);
+
+    impl KeyringBackend for FailingRead {
+        fn get_session_key(&self, account_name: &str) -> Result<String, TrackerError> {
+            Err((self.0)(account_name))
+        }
+
+        fn set_session_key(&self, _: &str, _: &str) -> Result<(), TrackerError> {
+            Ok(())
+        }
+
+        fn delete_session_key(&self, _: &str) -> Result<(), TrackerError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn has_key_reports_missing_only_for_not_found() {
+        let missing = FailingRead(|name| TrackerError::KeyNotFound(name.to_string()));
+        assert!(!missing.has_key("work").unwrap());
+
+        let locked = FailingRead(|_| TrackerError::Keyring("keychain is locked".to_string()));
+        assert!(locked.has_key("work").is_err(), "A locked keyring isn't a missing key");
+    }
 }
@@ -1,12 +1,21 @@
 mod api;
 mod app;
+mod clipboard;
 mod config;
+mod debug_bundle;
 mod error;
 mod event;
+mod export;
+mod history;
 mod keyring_store;
 mod oauth;
+mod snapshot;
 mod ui;
+mod usage_snapshot;
+mod whats_new;
 
+use std::io::IsTerminal;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
@@ -15,38 +24,180 @@ use crossterm::event::KeyEventKind;
 use app::AppState;
 use event::Event;
 
-#[tokio::main]
-async fn main() -> Result<()> {
+/// Sizes the runtime's worker pool; unset means one worker per core.
+const WORKER_THREADS_ENV: &str = "CLAUDE_TRACKER_WORKER_THREADS";
+
+fn main() -> Result<()> {
+    let worker_threads = std::env::var(WORKER_THREADS_ENV).ok();
+    build_runtime(worker_threads.as_deref())?.block_on(async_main())
+}
+
+/// Keychain reads run on `spawn_blocking`, so this is always the
+/// multi-thread runtime with its separate blocking pool; only the worker count is
+/// configurable, and a bad value stops startup instead of silently falling back.
+fn build_runtime(worker_threads: Option<&str>) -> Result<tokio::runtime::Runtime> {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder.enable_all();
+    if let Some(raw) = worker_threads {
+        match raw.trim().parse::<usize>() {
+            Ok(n) if n >= 1 => {
+                builder.worker_threads(n);
+            }
+            _ => anyhow::bail!("{WORKER_THREADS_ENV} must be a whole number of at least 1, got '{raw}'"),
+        }
+    }
+    Ok(builder.build()?)
+}
+
+async fn async_main() -> Result<()> {
+    // Non-interactive commands; these never touch the terminal
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(pos) = args.iter().position(|a| a == "--profile") {
+        let Some(name) = args.get(pos + 1) else {
+            anyhow::bail!("--profile needs a profile name");
+        };
+        config::set_profile(name)?;
+        args.drain(pos..=pos + 1);
+    }
+    match args.as_slice() {
+        [flag, path] if flag == "--snapshot" => {
+            return snapshot::print(std::path::Path::new(path));
+        }
+        [flag] if flag == "--once" => return once_command().await,
+        // Configured accounts only: no keychain reads, no network
+        [cmd] if cmd == "list" => {
+            print!("{}", config::account_list(&config::load_or_init()?));
+            return Ok(());
+        }
+        [cmd, query] if cmd == "swap" => return swap_command(query).await,
+        [cmd, flag, name] if cmd == "raw" && flag == "--name" => return raw_command(name).await,
+        _ => {}
+    }
+
+    // Without a terminal the TUI would draw into a pipe and exit on the first read
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        anyhow::bail!(
+            "claude-tracker needs an interactive terminal.\n\
+             For scripts, cron or pipes use:\n  \
+             claude-tracker --once              usage for every account as JSON\n  \
+             claude-tracker list                configured accounts\n  \
+             claude-tracker swap <name>         change the active account\n  \
+             claude-tracker raw --name <name>   one account's raw API response\n  \
+             claude-tracker --snapshot <path>   render a table fixture as text"
+        );
+    }
+
     // Panic hook to restore terminal
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
-        let _ = ratatui::restore();
+        restore_terminal();
         original_hook(panic_info);
     }));
 
     let result = run().await;
 
-    ratatui::restore();
+    restore_terminal();
     result
 }
 
+/// `swap <query>`: make the one account matching `query` active, without the TUI.
+async fn swap_command(query: &str) -> Result<()> {
+    // Held under the config lock, so a running TUI's save can't land in between
+    let (account, hook) = config::update(|cfg| {
+        let account = find_account(cfg, query)?.clone();
+        cfg.settings.active_account = Some(account.name.clone());
+        anyhow::Ok((account, cfg.settings.on_swap_command.clone()))
+    })??;
+    println!("Active: {}", account.name);
+
+    if let Some(command) = &hook {
+        if let Err(e) = api::run_swap_hook(command, &account.name, &account.org_id).await {
+            anyhow::bail!("on_swap_command {e}");
+        }
+    }
+    Ok(())
+}
+
+/// `--once`: fetch every account concurrently, print the usage snapshot as JSON, exit.
+/// The fetches are bounded by `once_deadline_secs` however the network behaves. The
+/// keychain reads in `AppState::from_config` come first and aren't: a keychain that
+/// prompts waits for the user before the deadline starts.
+async fn once_command() -> Result<()> {
+    let cfg = config::load_or_init()?;
+    let deadline = Duration::from_secs(cfg.settings.once_deadline_secs);
+    let mut app = AppState::from_config(cfg, keyring_store::system_keyring());
+    for (name, result) in api::fetch_all_with_deadline(&app.accounts, &app.settings, deadline).await {
+        app.apply_usage_result(&name, result);
+    }
+    let snapshot = usage_snapshot::UsageSnapshot::from_app(&app, chrono::Utc::now());
+    println!("{}", serde_json::to_string_pretty(&snapshot)?);
+    Ok(())
+}
+
+/// `raw --name <query>`: one fetch for the matching account, printing the response
+/// body as pretty JSON (secret-looking fields masked) without parsing it.
+async fn raw_command(query: &str) -> Result<()> {
+    let cfg = config::load_or_init()?;
+    let account = find_account(&cfg, query)?;
+    let token = keyring_store::system_keyring()
+        .get_session_key(&account.name)
+        .map_err(|e| anyhow::anyhow!("No credential for '{}': {e}", account.name))?;
+    let body = api::fetch_raw_body(&cfg.settings, account, &token)
+        .await
+        .map_err(|e| anyhow::anyhow!("Fetch failed for '{}': {e}", account.name))?;
+    println!("{body}");
+    Ok(())
+}
+
+/// The one account matching `query` (see `config::match_accounts`).
+fn find_account<'a>(cfg: &'a config::Config, query: &str) -> Result<&'a config::AccountConfig> {
+    match config::match_accounts(&cfg.accounts, query).as_slice() {
+        [account] => Ok(account),
+        [] => anyhow::bail!("No account matches '{query}'"),
+        candidates => {
+            let names: Vec<&str> = candidates.iter().map(|a| a.name.as_str()).collect();
+            anyhow::bail!("'{query}' matches several accounts:\n  {}", names.join("\n  "));
+        }
+    }
+}
+
+fn restore_terminal() {
+    let _ = crossterm::execute!(std::io::stdout(), crossterm::event::DisableBracketedPaste);
+    ratatui::restore();
+}
+
 async fn run() -> Result<()> {
     let cfg = config::load_or_init()?;
     let mut terminal = ratatui::init();
-    let mut app = AppState::from_config(cfg, keyring_store::system_keyring());
+    // Pasted text arrives as one Event::Paste, so a trailing newline can't submit a dialog
+    crossterm::execute!(std::io::stdout(), crossterm::event::EnableBracketedPaste)?;
+    let audit = cfg
+        .settings
+        .keyring_audit
+        .then(|| Arc::new(keyring_store::AuditingKeyring::new(keyring_store::system_keyring())));
+    let keyring: Arc<dyn keyring_store::KeyringBackend> = match &audit {
+        Some(audit) => audit.clone(),
+        None => keyring_store::system_keyring(),
+    };
+    let mut app = AppState::from_config(cfg, keyring);
+    app.keyring_audit = audit;
+    app.color_support = app::ColorSupport::detect();
+    app.check_keyring_drift();
+    app.show_whats_new();
+    if let Some(notice) = config::config_dir_notice() {
+        app.set_error(notice);
+    }
 
-    let mut events = event::EventHandler::new(
-        Duration::from_secs(1),
-        Duration::from_millis(33),
-    );
+    let mut events = event::EventHandler::new(Duration::from_secs(1), app.render_interval());
     let event_tx = events.sender();
 
     // Initial fetch + detect logged-in account
     api::spawn_fetch_all(&app, &event_tx);
     api::spawn_detect_logged_in(&app, &event_tx);
+    app.schedule_next_poll();
 
-    let poll_interval = Duration::from_secs(app.poll_interval_secs);
     let mut last_poll = Instant::now();
+    let mut last_sync = Instant::now();
 
     loop {
         let Some(evt) = events.next().await else {
@@ -55,51 +206,92 @@ async fn run() -> Result<()> {
 
         match evt {
             Event::Render => {
+                for name in app.detect_completed_resets(chrono::Utc::now()) {
+                    if app.settings.notify_on_reset {
+                        api::notify_desktop("Claude usage reset", &format!("5h window reset for {name}"));
+                    }
+                }
                 terminal.draw(|frame| ui::draw(frame, &app))?;
             }
+            // Input is drawn at once; only idle redraws follow the (maybe slow) render rate
             Event::Key(key) if key.kind == KeyEventKind::Press => {
                 app::handle_key(&mut app, key, &event_tx);
+                events.set_render_rate(app.render_interval());
+                terminal.draw(|frame| ui::draw(frame, &app))?;
+            }
+            Event::Paste(text) => {
+                app::handle_paste(&mut app, &text);
+                terminal.draw(|frame| ui::draw(frame, &app))?;
             }
             Event::Tick => {
-                if last_poll.elapsed() >= poll_interval {
+                // Read each tick: a config reload (Ctrl+R) can change them
+                let poll_interval = Duration::from_secs(app.settings.poll_interval_secs);
+                // `Instant` can stop during a system sleep, so without this the first poll
+                // after waking would wait out the rest of the pre-sleep interval
+                if app.detect_resume(chrono::Utc::now(), Instant::now()) {
+                    last_poll = Instant::now().checked_sub(poll_interval).unwrap_or(last_poll);
+                }
+                let sync_interval = Duration::from_secs(app.settings.claude_code_sync_secs);
+                // Deferred while a dialog is open; fires on the first tick after it closes
+                let poll_due = last_poll.elapsed() >= poll_interval && !app.in_input_dialog();
+                if poll_due && !app.paused {
                     api::spawn_fetch_all(&app, &event_tx);
                     api::spawn_detect_logged_in(&app, &event_tx);
+                    app.schedule_next_poll();
+                    app.defer_fast_polls(chrono::Utc::now());
                     last_poll = Instant::now();
                 }
+                app.expire_fast_polls(chrono::Utc::now());
+                if !app.paused && !app.in_input_dialog() {
+                    for name in app.due_fast_polls(chrono::Utc::now()) {
+                        api::spawn_fetch_one(&app, &name, &event_tx);
+                    }
+                }
+                if !sync_interval.is_zero() && last_sync.elapsed() >= sync_interval && !app.paused {
+                    api::spawn_claude_code_sync(&app, &event_tx);
+                    last_sync = Instant::now();
+                }
                 app.clear_stale_messages();
             }
             Event::UsageResult {
                 account_name,
                 result,
+                elapsed,
             } => {
+                app.record_fetch_latency(&account_name, elapsed);
                 app.apply_usage_result(&account_name, result);
             }
+            Event::RawUsageResult {
+                account_name,
+                result,
+            } => {
+                app.show_raw_json(account_name, result);
+            }
             Event::OAuthImportResult { result } => {
                 match result {
                     Ok(accounts) => {
-                        for data in accounts {
-                            if let Some(idx) = app.import_oauth_account(data) {
-                                api::spawn_fetch_one(&app, idx, &event_tx);
-                            }
+                        // Stored names may differ (org suffix for a same-email account)
+                        for name in app.import_oauth_accounts(accounts) {
+                            api::spawn_fetch_one(&app, &name, &event_tx);
                         }
                         api::spawn_detect_logged_in(&app, &event_tx);
                     }
                     Err(msg) => {
-                        app.set_status(format!("Import failed: {msg}"));
+                        app.set_error(format!("Import failed: {msg}"));
                     }
                 }
             }
             Event::OAuthLoginResult { result } => {
                 match result {
                     Ok(data) => {
-                        let name = data.name.clone();
                         if let Some(idx) = app.import_oauth_account(data) {
-                            api::spawn_fetch_one(&app, idx, &event_tx);
+                            let name = app.accounts[idx].config.name.clone();
+                            api::spawn_fetch_one(&app, &name, &event_tx);
                             app.set_status(format!("Logged in as '{name}'"));
                         }
                     }
                     Err(msg) => {
-                        app.set_status(format!("Login failed: {msg}"));
+                        app.set_error(format!("Login failed: {msg}"));
                     }
                 }
             }
@@ -109,9 +301,40 @@ async fn run() -> Result<()> {
             } => {
                 app.apply_token_refresh(&account_name, raw_credential);
             }
+            Event::ClaudeCodeSynced {
+                account_name,
+                raw_credential,
+            } => {
+                app.apply_token_refresh(&account_name, raw_credential);
+                app.set_logged_in(Some(account_name.clone()));
+                api::spawn_fetch_one(&app, &account_name, &event_tx);
+                app.set_status(format!("Synced '{account_name}' from Claude Code"));
+            }
+            Event::SwapHookFinished {
+                account_name,
+                result,
+            } => match result {
+                Ok(()) => app.set_status(format!("on_swap_command finished for '{account_name}'")),
+                Err(e) => app.set_error(format!("on_swap_command {e}")),
+            },
             Event::LoggedInDetected { account_name } => {
-                app.logged_in_account = account_name;
+                app.set_logged_in(account_name);
+            }
+            Event::ReplacementIdentified { account_name, result } => {
+                let repaired = app.apply_replacement_identity(&account_name, result);
+                if repaired {
+                    api::spawn_fetch_one(&app, &account_name, &event_tx);
+                }
+            }
+            Event::AccountValidated { result } => {
+                if let Some(name) = app.apply_add_validation(result) {
+                    api::spawn_fetch_one(&app, &name, &event_tx);
+                }
+            }
+            Event::OrgsFound { result } => {
+                app.apply_org_lookup(result);
             }
+            Event::Quit => break,
             _ => {}
         }
 
@@ -120,5 +343,6 @@ async fn run() -> Result<()> {
         }
     }
 
+    events.shutdown();
     Ok(())
 }
@@ -2,34 +2,46 @@ use std::time::Duration;
 
 use chrono::Utc;
 
-use crate::app::UsageData;
+use crate::app::{TokenCounts, UsageData, WeeklyCadence};
 
-const USAGE_ENDPOINT: &str = "https://api.anthropic.com/api/oauth/usage";
+pub(crate) const OAUTH_API_BASE: &str = "https://api.anthropic.com";
+const USAGE_PATH: &str = "/api/oauth/usage";
 const PROFILE_ENDPOINT: &str = "https://api.anthropic.com/api/oauth/profile";
 const REFRESH_ENDPOINT: &str = "https://api.anthropic.com/v1/oauth/token";
 pub const CLIENT_ID: &str = "9d1c250a-e61b-44d9-88ed-5944d1962f5e";
 const BETA_HEADER: &str = "oauth-2025-04-20";
 const USER_AGENT: &str = "claude-code/2.0.32";
 
+#[derive(Debug, Clone)]
 pub struct OAuthProfile {
     pub email: String,
     pub org_id: String,
+    pub plan: Option<String>,
 }
 
-/// Read Claude Code's access token from the default macOS Keychain entry.
-pub fn read_claude_code_access_token() -> anyhow::Result<String> {
-    read_keychain_token("Claude Code-credentials")
+/// Keychain service Claude Code stores its default credential under.
+pub const DEFAULT_KEYCHAIN_SERVICE: &str = "Claude Code-credentials";
+
+/// Read Claude Code's access token from the default credential entry.
+pub fn read_claude_code_access_token(service: &str) -> anyhow::Result<String> {
+    read_keychain_token(service)
+}
+
+/// Read Claude Code's full raw credential (including refresh token) from the default entry.
+pub fn read_claude_code_credential(service: &str) -> anyhow::Result<String> {
+    read_keychain_raw(service)
 }
 
-/// Read all Claude Code raw credentials from macOS Keychain.
+/// Read all Claude Code raw credentials (macOS Keychain, or the credentials file
+/// on other platforms).
 ///
-/// Claude Code uses per-config-directory keychain entries:
-/// - Default: `"Claude Code-credentials"`
-/// - Alternate: `"Claude Code-credentials-{hash}"` where hash = first 8 chars of sha256(config_dir)
+/// On macOS, Claude Code uses per-config-directory keychain entries:
+/// - Default: `service` (normally `"Claude Code-credentials"`)
+/// - Alternate: `"{service}-{hash}"` where hash = first 8 chars of sha256(config_dir)
 ///
 /// Returns deduplicated raw credential JSON strings (preserving refresh tokens).
-pub fn read_all_claude_code_credentials() -> anyhow::Result<Vec<String>> {
-    let service_names = discover_credential_services()?;
+pub fn read_all_claude_code_credentials(service: &str) -> anyhow::Result<Vec<String>> {
+    let service_names = discover_credential_services(service)?;
     if service_names.is_empty() {
         return Err(anyhow::anyhow!(
             "No Claude Code credentials found. Log into Claude Code first."
@@ -58,8 +70,9 @@ pub fn read_all_claude_code_credentials() -> anyhow::Result<Vec<String>> {
     Ok(credentials)
 }
 
-/// Discover all `Claude Code-credentials*` service names in the login keychain.
-fn discover_credential_services() -> anyhow::Result<Vec<String>> {
+/// Discover all `{service}*` service names in the login keychain.
+#[cfg(target_os = "macos")]
+fn discover_credential_services(service: &str) -> anyhow::Result<Vec<String>> {
     let output = std::process::Command::new("security")
         .args(["dump-keychain"])
         .output()
@@ -71,7 +84,7 @@ fn discover_credential_services() -> anyhow::Result<Vec<String>> {
         // Lines look like: "svce"<blob>="Claude Code-credentials"
         if let Some(rest) = line.strip_prefix("    \"svce\"<blob>=\"") {
             if let Some(name) = rest.strip_suffix('"') {
-                if name.starts_with("Claude Code-credentials") {
+                if name.starts_with(service) {
                     services.push(name.to_string());
                 }
             }
@@ -88,6 +101,7 @@ fn read_keychain_token(service: &str) -> anyhow::Result<String> {
 }
 
 /// Read the raw credential string from a keychain service (preserving all fields).
+#[cfg(target_os = "macos")]
 fn read_keychain_raw(service: &str) -> anyhow::Result<String> {
     let output = std::process::Command::new("security")
         .args(["find-generic-password", "-s", service, "-w"])
@@ -106,6 +120,51 @@ fn read_keychain_raw(service: &str) -> anyhow::Result<String> {
     Ok(raw.trim().to_string())
 }
 
+// Off macOS (Windows, Linux), Claude Code doesn't use the OS credential store: it
+// keeps the same JSON in `.credentials.json` under its config directory
+// (`%USERPROFILE%\.claude` on Windows, `~/.claude` elsewhere, or `$CLAUDE_CONFIG_DIR`).
+// There is one file per config directory, so it stands in for the default service.
+
+/// Claude Code's credentials file for a given `$CLAUDE_CONFIG_DIR` and home directory.
+#[cfg(not(target_os = "macos"))]
+fn credentials_file_path(
+    config_dir_env: Option<std::path::PathBuf>,
+    home: Option<std::path::PathBuf>,
+) -> Option<std::path::PathBuf> {
+    let dir = config_dir_env
+        .filter(|p| !p.as_os_str().is_empty())
+        .or_else(|| home.map(|h| h.join(".claude")))?;
+    Some(dir.join(".credentials.json"))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn claude_code_credentials_file() -> Option<std::path::PathBuf> {
+    credentials_file_path(
+        std::env::var_os("CLAUDE_CONFIG_DIR").map(std::path::PathBuf::from),
+        dirs::home_dir(),
+    )
+}
+
+#[cfg(not(target_os = "macos"))]
+fn discover_credential_services(service: &str) -> anyhow::Result<Vec<String>> {
+    let exists = claude_code_credentials_file().is_some_and(|p| p.is_file());
+    Ok(if exists {
+        vec![service.to_string()]
+    } else {
+        vec![]
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_keychain_raw(_service: &str) -> anyhow::Result<String> {
+    let path = claude_code_credentials_file()
+        .ok_or_else(|| anyhow::anyhow!("Could not locate Claude Code's config directory"))?;
+    let raw = std::fs::read_to_string(&path).map_err(|e| {
+        anyhow::anyhow!("No Claude Code credentials at {}. ({e})", path.display())
+    })?;
+    Ok(raw.trim().to_string())
+}
+
 fn parse_access_token(json_str: &str) -> anyhow::Result<String> {
     let value: serde_json::Value = serde_json::from_str(json_str)?;
 
@@ -143,7 +202,11 @@ pub async fn fetch_profile(access_token: &str) -> anyhow::Result<OAuthProfile> {
     }
 
     let json: serde_json::Value = resp.json().await?;
+    parse_profile_response(&json)
+}
 
+/// Extract email, org and plan from a profile response body.
+pub(crate) fn parse_profile_response(json: &serde_json::Value) -> anyhow::Result<OAuthProfile> {
     let account = json
         .get("account")
         .ok_or_else(|| anyhow::anyhow!("Missing account in profile response"))?;
@@ -163,20 +226,67 @@ pub async fn fetch_profile(access_token: &str) -> anyhow::Result<OAuthProfile> {
             .filter(|s| !s.is_empty())
             .ok_or_else(|| anyhow::anyhow!("Missing or empty org ID in profile response"))?
             .to_string(),
+        plan: parse_plan(json),
     })
 }
 
-/// Fetch usage data using an OAuth access token.
-pub async fn fetch_oauth_usage(access_token: &str) -> anyhow::Result<UsageData> {
+/// Derive a human-readable plan name from a profile response. The profile shape
+/// isn't documented, so every field is optional and unknown values pass through.
+fn parse_plan(profile: &serde_json::Value) -> Option<String> {
+    let org = profile.get("organization");
+    let account = profile.get("account");
+
+    let org_type = org
+        .and_then(|o| o.get("organization_type"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty());
+    let flag = |name: &str| {
+        account
+            .and_then(|a| a.get(name))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    };
+
+    let base = match org_type {
+        Some("claude_max") => "Max".to_string(),
+        Some("claude_pro") => "Pro".to_string(),
+        Some("claude_team") => "Team".to_string(),
+        Some("claude_enterprise") => "Enterprise".to_string(),
+        Some(other) => other.to_string(),
+        None if flag("has_claude_max") => "Max".to_string(),
+        None if flag("has_claude_pro") => "Pro".to_string(),
+        None => return None,
+    };
+
+    // Max plans come in multiple sizes, reported via the rate-limit tier
+    let tier = org
+        .and_then(|o| o.get("rate_limit_tier"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if base == "Max" {
+        if tier.contains("20x") {
+            return Some("Max 20x".to_string());
+        }
+        if tier.contains("5x") {
+            return Some("Max 5x".to_string());
+        }
+    }
+    Some(base)
+}
+
+/// Fetch the raw usage response body using an OAuth access token.
+pub async fn fetch_oauth_usage_body(
+    access_token: &str,
+    options: &crate::api::FetchOptions,
+) -> anyhow::Result<serde_json::Value> {
     let client = crate::api::http_client();
-    let resp = client
-        .get(USAGE_ENDPOINT)
+    let request = client
+        .get(crate::api::api_url(options.api_base.as_deref(), OAUTH_API_BASE, USAGE_PATH))
         .header("Authorization", format!("Bearer {}", access_token))
         .header("anthropic-beta", BETA_HEADER)
         .header("User-Agent", USER_AGENT)
-        .timeout(Duration::from_secs(10))
-        .send()
-        .await?;
+        .timeout(Duration::from_secs(10));
+    let resp = options.apply_headers(request).send().await?;
 
     let status = resp.status();
     if !status.is_success() {
@@ -203,19 +313,26 @@ pub async fn fetch_oauth_usage(access_token: &str) -> anyhow::Result<UsageData>
 
     let raw_body = resp.text().await?;
     eprintln!("[oauth/usage] raw response: {}", &raw_body[..raw_body.len().min(1000)]);
-    let body: serde_json::Value = serde_json::from_str(&raw_body)?;
-
-    let five_hour = body
-        .get("five_hour")
-        .ok_or_else(|| anyhow::anyhow!("Missing five_hour field"))?;
+    Ok(serde_json::from_str(&raw_body)?)
+}
 
-    let utilization = parse_utilization(five_hour);
-    let resets_at = parse_resets_at(five_hour);
+/// Parse a usage response body (same shape for OAuth and session-key endpoints).
+/// A missing `five_hour` bucket — seen on brand-new accounts — reads as 0% with no
+/// reset when `allow_missing_five_hour` is set, and as an error otherwise.
+pub(crate) fn parse_usage_response(
+    body: &serde_json::Value,
+    allow_missing_five_hour: bool,
+) -> anyhow::Result<UsageData> {
+    let (utilization, resets_at) = match body.get("five_hour").filter(|v| !v.is_null()) {
+        Some(five_hour) => (parse_utilization(five_hour), parse_resets_at(five_hour)),
+        None if allow_missing_five_hour => (0, None),
+        None => return Err(anyhow::anyhow!("Missing five_hour field")),
+    };
 
     let (weekly_utilization, weekly_resets_at) = body
         .get("seven_day")
         .filter(|v| !v.is_null())
-        .map(|seven_day| (Some(parse_utilization(seven_day)), parse_resets_at(seven_day)))
+        .map(|seven_day| (parse_optional_utilization(seven_day), parse_resets_at(seven_day)))
         .unwrap_or((None, None));
 
     Ok(UsageData {
@@ -223,9 +340,64 @@ pub async fn fetch_oauth_usage(access_token: &str) -> anyhow::Result<UsageData>
         resets_at,
         weekly_utilization,
         weekly_resets_at,
+        tokens: body.get("five_hour").and_then(parse_token_counts),
+        weekly_tokens: body.get("seven_day").and_then(parse_token_counts),
+        weekly_cadence: body.get("seven_day").and_then(parse_cadence),
     })
 }
 
+/// Reset cadence from an optional type/label field on a bucket. Unrecognized or
+/// missing values return None so the UI keeps its rolling-window countdown.
+pub(crate) fn parse_cadence(bucket: &serde_json::Value) -> Option<WeeklyCadence> {
+    let label = ["reset_type", "window_type", "cadence", "type"]
+        .iter()
+        .find_map(|k| bucket.get(*k).and_then(|v| v.as_str()))?
+        .to_lowercase();
+    if label.contains("calendar") || label.contains("fixed") {
+        Some(WeeklyCadence::Calendar)
+    } else if label.contains("rolling") {
+        Some(WeeklyCadence::Rolling)
+    } else {
+        None
+    }
+}
+
+/// Absolute used/limit counts from a usage bucket. The endpoint doesn't document
+/// these, so accept the likely spellings and return None unless both are present.
+pub(crate) fn parse_token_counts(bucket: &serde_json::Value) -> Option<TokenCounts> {
+    let first = |keys: &[&str]| keys.iter().find_map(|k| bucket.get(*k).and_then(|v| v.as_u64()));
+    let used = first(&["tokens_used", "used_tokens", "used"])?;
+    let limit = first(&["tokens_limit", "token_limit", "limit"])?;
+    (limit > 0).then_some(TokenCounts { used, limit })
+}
+
+/// Validate a Claude Code credential JSON blob (wrapped in `claudeAiOauth` or
+/// top-level) and return it in the wrapped storage format. Requires an access token;
+/// the refresh token and expiry are kept when present.
+pub(crate) fn parse_credential_json(raw: &str) -> anyhow::Result<String> {
+    let value: serde_json::Value = serde_json::from_str(raw.trim())
+        .map_err(|e| anyhow::anyhow!("Not valid JSON: {e}"))?;
+    let creds = value.get("claudeAiOauth").unwrap_or(&value);
+    if !creds.is_object() {
+        return Err(anyhow::anyhow!("Expected a JSON object"));
+    }
+
+    let field = |camel: &str, snake: &str| creds.get(camel).or_else(|| creds.get(snake)).cloned();
+    let access_token = field("accessToken", "access_token")
+        .filter(|v| v.as_str().is_some_and(|s| !s.is_empty()))
+        .ok_or_else(|| anyhow::anyhow!("Missing accessToken"))?;
+
+    let mut wrapped = serde_json::Map::new();
+    wrapped.insert("accessToken".into(), access_token);
+    if let Some(rt) = field("refreshToken", "refresh_token").filter(|v| v.is_string()) {
+        wrapped.insert("refreshToken".into(), rt);
+    }
+    if let Some(exp) = field("expiresAt", "expires_at").filter(|v| v.is_number()) {
+        wrapped.insert("expiresAt".into(), exp);
+    }
+    Ok(serde_json::json!({ "claudeAiOauth": wrapped }).to_string())
+}
+
 /// Extract the refresh token from a stored credential (JSON formats only).
 /// Returns None for plain access token strings (no refresh token available).
 pub(crate) fn extract_refresh_token(raw: &str) -> Option<String> {
@@ -241,6 +413,21 @@ pub(crate) fn extract_refresh_token(raw: &str) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Access-token expiry (`expiresAt`, epoch millis) from a stored credential JSON.
+/// Returns None for plain token strings or credentials without an expiry.
+pub(crate) fn extract_expires_at(raw: &str) -> Option<chrono::DateTime<Utc>> {
+    if !raw.starts_with('{') {
+        return None;
+    }
+    let value: serde_json::Value = serde_json::from_str(raw).ok()?;
+    let creds = value.get("claudeAiOauth").unwrap_or(&value);
+    let millis = creds
+        .get("expiresAt")
+        .or_else(|| creds.get("expires_at"))
+        .and_then(|v| v.as_i64())?;
+    chrono::DateTime::from_timestamp_millis(millis)
+}
+
 /// Refresh an expired access token using the refresh token.
 pub async fn refresh_access_token(refresh_token: &str) -> anyhow::Result<RefreshResponse> {
     let client = crate::api::http_client();
@@ -370,11 +557,16 @@ pub fn generate_random_state() -> String {
 }
 
 pub(crate) fn parse_utilization(bucket: &serde_json::Value) -> u32 {
+    parse_optional_utilization(bucket).unwrap_or(0)
+}
+
+/// Utilization if the bucket reports a number; None when it's missing or null
+/// (e.g. a weekly window that hasn't been measured yet), which is not the same as 0%.
+pub(crate) fn parse_optional_utilization(bucket: &serde_json::Value) -> Option<u32> {
     bucket
         .get("utilization")
         .and_then(|v| v.as_u64().map(|n| n as f64).or_else(|| v.as_f64()))
         .map(|v| v.round() as u32)
-        .unwrap_or(0)
 }
 
 pub(crate) fn parse_resets_at(bucket: &serde_json::Value) -> Option<chrono::DateTime<Utc>> {
@@ -447,6 +639,35 @@ mod tests {
         assert_eq!(extract_refresh_token("eyJplaintoken"), None);
     }
 
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn credentials_file_prefers_claude_config_dir() {
+        use std::path::PathBuf;
+        let home = Some(PathBuf::from("/home/u"));
+        assert_eq!(
+            credentials_file_path(Some(PathBuf::from("/cfg")), home.clone()),
+            Some(PathBuf::from("/cfg/.credentials.json"))
+        );
+        assert_eq!(
+            credentials_file_path(Some(PathBuf::new()), home),
+            Some(PathBuf::from("/home/u/.claude/.credentials.json"))
+        );
+        assert_eq!(credentials_file_path(None, None), None);
+    }
+
+    #[test]
+    fn extract_expires_at_from_wrapped_credential() {
+        let raw = r#"{"claudeAiOauth":{"accessToken":"at","expiresAt":1700000000000}}"#;
+        let expires = extract_expires_at(raw).unwrap();
+        assert_eq!(expires.timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn extract_expires_at_missing_or_plain() {
+        assert_eq!(extract_expires_at(r#"{"access_token":"at"}"#), None);
+        assert_eq!(extract_expires_at("eyJplaintoken"), None);
+    }
+
     #[test]
     fn update_credential_json_wrapped() {
         let raw = r#"{"claudeAiOauth":{"accessToken":"old-at","refreshToken":"old-rt","expiresAt":0}}"#;
@@ -458,6 +679,182 @@ mod tests {
         assert_eq!(creds["expiresAt"], 999);
     }
 
+    #[test]
+    fn parse_profile_response_real_shape() {
+        let json = serde_json::json!({
+            "account": {"uuid": "acc-1", "email": "me@example.com", "has_claude_pro": true},
+            "organization": {"uuid": "org-1", "name": "Me", "organization_type": "claude_pro"},
+        });
+        let profile = parse_profile_response(&json).unwrap();
+        assert_eq!(profile.email, "me@example.com");
+        assert_eq!(profile.org_id, "org-1");
+        assert_eq!(profile.plan.as_deref(), Some("Pro"));
+    }
+
+    #[test]
+    fn parse_profile_response_missing_email_is_unknown() {
+        let json = serde_json::json!({"account": {}, "organization": {"uuid": "org-1"}});
+        assert_eq!(parse_profile_response(&json).unwrap().email, "unknown");
+    }
+
+    #[test]
+    fn parse_profile_response_malformed_bodies() {
+        let no_account = serde_json::json!({"organization": {"uuid": "org-1"}});
+        assert!(parse_profile_response(&no_account).is_err());
+
+        let no_org = serde_json::json!({"account": {"email": "a@b.c"}});
+        assert!(parse_profile_response(&no_org).is_err());
+
+        let empty_org_id = serde_json::json!({"account": {}, "organization": {"uuid": ""}});
+        assert!(parse_profile_response(&empty_org_id).is_err());
+    }
+
+    #[test]
+    fn parse_usage_response_string_utilization_defaults_to_zero() {
+        let body = serde_json::json!({"five_hour": {"utilization": "lots", "resets_at": "garbage"}});
+        let usage = parse_usage_response(&body, false).unwrap();
+        assert_eq!(usage.utilization, 0);
+        assert!(usage.resets_at.is_none(), "Unparseable timestamp must not panic");
+    }
+
+    #[test]
+    fn parse_plan_from_organization_type() {
+        let json = serde_json::json!({
+            "account": {"email": "a@b.c"},
+            "organization": {"uuid": "org", "organization_type": "claude_pro"},
+        });
+        assert_eq!(parse_plan(&json).as_deref(), Some("Pro"));
+    }
+
+    #[test]
+    fn parse_plan_max_includes_tier_size() {
+        let json = serde_json::json!({
+            "organization": {"organization_type": "claude_max", "rate_limit_tier": "default_claude_max_20x"},
+        });
+        assert_eq!(parse_plan(&json).as_deref(), Some("Max 20x"));
+    }
+
+    #[test]
+    fn parse_plan_falls_back_to_account_flags() {
+        let json = serde_json::json!({
+            "account": {"has_claude_max": true, "has_claude_pro": false},
+            "organization": {"uuid": "org"},
+        });
+        assert_eq!(parse_plan(&json).as_deref(), Some("Max"));
+    }
+
+    #[test]
+    fn parse_plan_absent_returns_none() {
+        let json = serde_json::json!({
+            "account": {"email": "a@b.c"},
+            "organization": {"uuid": "org"},
+        });
+        assert_eq!(parse_plan(&json), None);
+    }
+
+    #[test]
+    fn parse_usage_response_full_body() {
+        let body = serde_json::json!({
+            "five_hour": {"utilization": 42.4, "resets_at": "2025-01-01T05:00:00Z"},
+            "seven_day": {"utilization": 17, "resets_at": "2025-01-07T00:00:00Z"},
+        });
+        let usage = parse_usage_response(&body, false).unwrap();
+        assert_eq!(usage.utilization, 42);
+        assert!(usage.resets_at.is_some());
+        assert_eq!(usage.weekly_utilization, Some(17));
+        assert!(usage.weekly_resets_at.is_some());
+    }
+
+    #[test]
+    fn parse_usage_response_missing_five_hour_reads_as_zero_when_allowed() {
+        let body = serde_json::json!({"seven_day": null});
+        let usage = parse_usage_response(&body, true).unwrap();
+        assert_eq!(usage.utilization, 0);
+        assert!(usage.resets_at.is_none());
+        assert_eq!(usage.weekly_utilization, None);
+    }
+
+    #[test]
+    fn parse_usage_response_token_counts_when_present() {
+        let body = serde_json::json!({
+            "five_hour": {"utilization": 62, "tokens_used": 123_000, "tokens_limit": 200_000},
+            "seven_day": {"utilization": 10},
+        });
+        let usage = parse_usage_response(&body, false).unwrap();
+        assert_eq!(usage.tokens, Some(TokenCounts { used: 123_000, limit: 200_000 }));
+        assert_eq!(usage.weekly_tokens, None, "Percent-only bucket has no counts");
+    }
+
+    #[test]
+    fn parse_cadence_labels() {
+        let cadence = |v: serde_json::Value| parse_cadence(&v);
+        assert_eq!(cadence(serde_json::json!({"reset_type": "calendar_week"})), Some(WeeklyCadence::Calendar));
+        assert_eq!(cadence(serde_json::json!({"type": "FIXED"})), Some(WeeklyCadence::Calendar));
+        assert_eq!(cadence(serde_json::json!({"window_type": "rolling_7d"})), Some(WeeklyCadence::Rolling));
+        assert_eq!(cadence(serde_json::json!({"type": "something_new"})), None);
+        assert_eq!(cadence(serde_json::json!({"utilization": 3})), None);
+    }
+
+    #[test]
+    fn parse_token_counts_requires_both_fields_and_a_limit() {
+        assert_eq!(parse_token_counts(&serde_json::json!({"used": 5})), None);
+        assert_eq!(parse_token_counts(&serde_json::json!({"used": 5, "limit": 0})), None);
+        assert_eq!(parse_token_counts(&serde_json::json!(null)), None);
+        assert_eq!(
+            parse_token_counts(&serde_json::json!({"used": 5, "limit": 10})),
+            Some(TokenCounts { used: 5, limit: 10 })
+        );
+    }
+
+    #[test]
+    fn parse_usage_response_null_weekly_utilization_is_unknown() {
+        let body = serde_json::json!({
+            "five_hour": {"utilization": 5},
+            "seven_day": {"utilization": null, "resets_at": "2025-01-07T00:00:00Z"},
+        });
+        let usage = parse_usage_response(&body, false).unwrap();
+        assert_eq!(usage.weekly_utilization, None, "null must not read as 0%");
+
+        let body = serde_json::json!({
+            "five_hour": {"utilization": 5},
+            "seven_day": {"utilization": 0},
+        });
+        let usage = parse_usage_response(&body, false).unwrap();
+        assert_eq!(usage.weekly_utilization, Some(0), "Measured zero stays 0%");
+    }
+
+    #[test]
+    fn parse_usage_response_missing_five_hour_errors_when_strict() {
+        let body = serde_json::json!({});
+        let err = parse_usage_response(&body, false).unwrap_err();
+        assert_eq!(format!("{err}"), "Missing five_hour field");
+    }
+
+    #[test]
+    fn parse_credential_json_wraps_top_level_format() {
+        let raw = r#"{"access_token":"at","refresh_token":"rt","expires_at":123}"#;
+        let parsed = parse_credential_json(raw).unwrap();
+        let v: serde_json::Value = serde_json::from_str(&parsed).unwrap();
+        assert_eq!(v["claudeAiOauth"]["accessToken"], "at");
+        assert_eq!(v["claudeAiOauth"]["refreshToken"], "rt");
+        assert_eq!(v["claudeAiOauth"]["expiresAt"], 123);
+    }
+
+    #[test]
+    fn parse_credential_json_accepts_wrapped_with_whitespace() {
+        let raw = "\n  {\"claudeAiOauth\":{\"accessToken\":\"at\"}}\n";
+        let parsed = parse_credential_json(raw).unwrap();
+        assert_eq!(normalize_stored_token(&parsed), "at");
+        assert_eq!(extract_refresh_token(&parsed), None);
+    }
+
+    #[test]
+    fn parse_credential_json_rejects_malformed() {
+        assert!(parse_credential_json("not json").is_err());
+        assert!(parse_credential_json("[1, 2]").is_err());
+        assert!(parse_credential_json(r#"{"claudeAiOauth":{"refreshToken":"rt"}}"#).is_err());
+    }
+
     #[test]
     fn update_credential_json_no_refresh_rotation() {
         let raw = r#"{"claudeAiOauth":{"accessToken":"old","refreshToken":"keep-me","expiresAt":0}}"#;
@@ -0,0 +1,497 @@
+//! Render a fixture of account states once, at a fixed time, and print the table as
+//! text. Lets rendering bugs be reported with a reproducible fixture:
+//!
+//! ```text
+//! claude-tracker --snapshot fixture.toml
+//! ```
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::Terminal;
+use serde::Deserialize;
+
+use crate::app::{AccountStatus, AppState, UsageData};
+use crate::config::{AccountConfig, Config, Settings, CONFIG_VERSION};
+use crate::error::TrackerError;
+use crate::keyring_store::KeyringBackend;
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    now: DateTime<Utc>,
+    #[serde(default = "default_width")]
+    width: u16,
+    #[serde(default = "default_height")]
+    height: u16,
+    #[serde(default)]
+    settings: Settings,
+    #[serde(default)]
+    accounts: Vec<FixtureAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureAccount {
+    name: String,
+    #[serde(default)]
+    org_id: String,
+    /// "ok", "idle" or "error".
+    #[serde(default = "default_status")]
+    status: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    logged_in: bool,
+    #[serde(default)]
+    utilization: Option<u32>,
+    #[serde(default)]
+    resets_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    weekly_utilization: Option<u32>,
+    #[serde(default)]
+    weekly_resets_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    last_fetched: Option<DateTime<Utc>>,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    accent_color: Option<String>,
+    #[serde(default, deserialize_with = "crate::config::deserialize_expiry")]
+    session_key_expires: Option<DateTime<Utc>>,
+}
+
+fn default_width() -> u16 {
+    120
+}
+
+fn default_height() -> u16 {
+    10
+}
+
+fn default_status() -> String {
+    "ok".to_string()
+}
+
+/// Fixtures never touch the real keychain.
+struct NoKeyring;
+
+impl KeyringBackend for NoKeyring {
+    fn get_session_key(&self, account_name: &str) -> Result<String, TrackerError> {
+        Err(TrackerError::KeyNotFound(account_name.to_string()))
+    }
+
+    fn set_session_key(&self, _: &str, _: &str) -> Result<(), TrackerError> {
+        Ok(())
+    }
+
+    fn delete_session_key(&self, _: &str) -> Result<(), TrackerError> {
+        Ok(())
+    }
+}
+
+fn app_from_fixture(fixture: Fixture) -> Result<AppState> {
+    let config = Config {
+        version: CONFIG_VERSION,
+        settings: fixture.settings,
+        accounts: fixture
+            .accounts
+            .iter()
+            .map(|a| AccountConfig {
+                name: a.name.clone(),
+                org_id: a.org_id.clone(),
+                label: a.label.clone(),
+                accent_color: a.accent_color.clone(),
+                session_key_expires: a.session_key_expires,
+                ..Default::default()
+            })
+            .collect(),
+    };
+    let mut app = AppState::from_config(config, Arc::new(NoKeyring));
+
+    for (state, account) in app.accounts.iter_mut().zip(&fixture.accounts) {
+        state.status = match account.status.as_str() {
+            "ok" => AccountStatus::Ok,
+            "idle" => AccountStatus::Idle,
+            "error" => AccountStatus::Error(account.error.clone().unwrap_or_default()),
+            other => anyhow::bail!("Unknown status '{other}' for '{}'", account.name),
+        };
+        state.usage = account.utilization.map(|utilization| UsageData {
+            utilization,
+            resets_at: account.resets_at,
+            weekly_utilization: account.weekly_utilization,
+            weekly_resets_at: account.weekly_resets_at,
+            tokens: None,
+            weekly_tokens: None,
+            weekly_cadence: None,
+        });
+        state.last_fetched = account.last_fetched;
+    }
+    app.logged_in_account = fixture
+        .accounts
+        .iter()
+        .find(|a| a.logged_in)
+        .map(|a| a.name.clone());
+    Ok(app)
+}
+
+fn buffer_lines(buffer: &Buffer) -> Vec<String> {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let line: String = (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Parse a fixture and render the accounts table as plain text lines.
+fn render_fixture(contents: &str) -> Result<Vec<String>> {
+    let fixture: Fixture = toml::from_str(contents).context("Invalid snapshot fixture")?;
+    let (width, height, now) = (fixture.width, fixture.height, fixture.now);
+    let app = app_from_fixture(fixture)?;
+    render_table(&app, width, height, now)
+}
+
+fn render_table(app: &AppState, width: u16, height: u16, now: DateTime<Utc>) -> Result<Vec<String>> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|frame| crate::ui::draw_table_at(frame, app, now))?;
+    Ok(buffer_lines(terminal.backend().buffer()))
+}
+
+pub fn print(path: &Path) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    for line in render_fixture(&contents)? {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"
+        now = "2025-01-01T12:00:00Z"
+        width = 110
+        height = 5
+
+        [[accounts]]
+        name = "reset@example.com"
+        utilization = 90
+        resets_at = "2025-01-01T11:59:00Z"
+        last_fetched = "2025-01-01T11:58:30Z"
+
+        [[accounts]]
+        name = "busy@example.com"
+        utilization = 40
+        resets_at = "2025-01-01T14:00:00Z"
+        weekly_utilization = 18
+        weekly_resets_at = "2025-01-04T12:00:00Z"
+        last_fetched = "2025-01-01T11:00:00Z"
+
+        [[accounts]]
+        name = "broken@example.com"
+        status = "error"
+        error = "Timeout"
+    "#;
+
+    #[test]
+    fn fixture_renders_reproducibly() {
+        let lines = render_fixture(FIXTURE).unwrap();
+        assert_eq!(lines, render_fixture(FIXTURE).unwrap());
+
+        let row = |name: &str| lines.iter().find(|l| l.contains(name)).unwrap().clone();
+
+        // Reset passed since the last fetch: shown as 0%, not the cached 90%
+        let reset = row("reset@example.com");
+        assert!(reset.contains(" 0%"), "got: {reset}");
+        assert!(!reset.contains("90%"), "got: {reset}");
+        assert!(reset.contains("Live"), "got: {reset}");
+
+        let busy = row("busy@example.com");
+        assert!(busy.contains("40%") && busy.contains("2h 00m"), "got: {busy}");
+        assert!(busy.contains("3d 0h") && busy.contains("1h ago"), "got: {busy}");
+
+        assert!(row("broken@example.com").contains("Timeout"));
+    }
+
+    /// Render `FIXTURE` with `settings` (TOML lines) as its `[settings]` table.
+    fn render_with_settings(settings: &str) -> Vec<String> {
+        render_fixture(&FIXTURE.replacen("height = 5", &format!("height = 5\n[settings]\n{settings}"), 1)).unwrap()
+    }
+
+    /// `FIXTURE` with `field` (a TOML line) added to the account `name`.
+    fn fixture_with(name: &str, field: &str) -> String {
+        let entry = format!("name = \"{name}\"");
+        FIXTURE.replacen(&entry, &format!("{entry}\n{field}"), 1)
+    }
+
+    /// The rendered row for account `name`.
+    fn row<'a>(lines: &'a [String], name: &str) -> &'a str {
+        lines
+            .iter()
+            .find(|l| l.contains(name))
+            .unwrap_or_else(|| panic!("no row for {name}: {lines:#?}"))
+    }
+
+    #[test]
+    fn color_active_marker_drops_name_suffix() {
+        assert!(render_fixture(FIXTURE).unwrap()[1].contains("reset@example.com *"));
+        let lines = render_with_settings("active_marker_color = true");
+        assert!(!lines[1].contains("reset@example.com *"), "{lines:#?}");
+    }
+
+    #[test]
+    fn org_column_sits_between_name_and_usage() {
+        let fixture = fixture_with("busy@example.com", r#"org_id = "65f10de7-1c2d-4e5f-8a9b-0123456789ab""#);
+        let fixture: Fixture = toml::from_str(&fixture).unwrap();
+        let now = fixture.now;
+        let mut app = app_from_fixture(fixture).unwrap();
+        app.show_org_column = true;
+        let lines = render_table(&app, 130, 5, now).unwrap();
+
+        assert!(lines[0].contains("Org"), "{lines:#?}");
+        let busy = row(&lines, "busy@example.com");
+        let org_at = busy.find("65f10de7…").expect("short org id shown");
+        assert!(busy.find("busy@").unwrap() < org_at && org_at < busy.find("40%").unwrap(), "{busy}");
+    }
+
+    #[test]
+    fn label_goes_before_the_name() {
+        let fixture = fixture_with("busy@example.com", "label = \"[W]\"\naccent_color = \"magenta\"");
+        let lines = render_fixture(&fixture).unwrap();
+        row(&lines, "[W] busy@example.com");
+    }
+
+    #[test]
+    fn weekly_usage_below_threshold_is_dimmed_out() {
+        let lines = render_with_settings("weekly_hide_below_pct = 20");
+        let busy = row(&lines, "busy@example.com");
+        assert!(busy.contains("<20%") && !busy.contains("18%"), "got: {busy}");
+
+        let lines = render_with_settings("weekly_hide_below_pct = 10");
+        assert!(row(&lines, "busy@example.com").contains("18%"), "{lines:#?}");
+    }
+
+    #[test]
+    fn session_key_expiry_warns_when_near() {
+        let near = fixture_with("busy@example.com", r#"session_key_expires = "2025-01-03T12:00:00Z""#);
+        let lines = render_fixture(&near).unwrap();
+        assert!(row(&lines, "busy@example.com").contains("⚠ key 2d"), "{lines:#?}");
+
+        let far = fixture_with("busy@example.com", r#"session_key_expires = "2025-03-01T00:00:00Z""#);
+        let lines = render_fixture(&far).unwrap();
+        assert!(!row(&lines, "busy@example.com").contains("key"), "{lines:#?}");
+    }
+
+    #[test]
+    fn rows_without_usage_still_show_an_expired_key() {
+        let expired = fixture_with("broken@example.com", "session_key_expires = 2024-12-31");
+        let lines = render_fixture(&expired).unwrap();
+        assert!(row(&lines, "broken@example.com").contains("⚠ key expired"), "{lines:#?}");
+    }
+
+    #[test]
+    fn page_size_shows_one_page_with_an_indicator() {
+        let lines = render_with_settings("page_size = 2");
+        assert!(!lines.iter().any(|l| l.contains("broken@example.com")), "Page 2 not drawn: {lines:#?}");
+        assert!(lines.iter().any(|l| l.contains("Page 1/2 (n/p)")), "{lines:#?}");
+    }
+
+    #[test]
+    fn passed_reset_can_show_as_recalculating() {
+        let lines = render_with_settings("recalculate_after_reset = true");
+        let reset = row(&lines, "reset@example.com");
+        assert!(reset.contains("recalc…") && !reset.contains(" 0%"), "got: {reset}");
+    }
+
+    #[test]
+    fn fetch_after_reset_shows_fresh_usage_despite_stale_reset_time() {
+        let fetched_after_reset = FIXTURE.replacen(
+            r#"last_fetched = "2025-01-01T11:58:30Z""#,
+            r#"last_fetched = "2025-01-01T11:59:30Z""#,
+            1,
+        );
+        let lines = render_fixture(&fetched_after_reset).unwrap();
+        assert!(row(&lines, "reset@example.com").contains("90%"), "{lines:#?}");
+    }
+
+    #[test]
+    fn limit_badge_marks_maxed_accounts() {
+        let fixture = r#"
+            now = "2025-01-01T12:00:00Z"
+            height = 6
+            [settings]
+            limit_alert = "badge"
+
+            [[accounts]]
+            name = "maxed@example.com"
+            utilization = 100
+            resets_at = "2025-01-01T13:00:00Z"
+            last_fetched = "2025-01-01T11:59:30Z"
+
+            [[accounts]]
+            name = "fine@example.com"
+            utilization = 40
+            resets_at = "2025-01-01T13:00:00Z"
+            last_fetched = "2025-01-01T11:59:30Z"
+
+            [[accounts]]
+            name = "reset@example.com"
+            utilization = 100
+            resets_at = "2025-01-01T11:59:00Z"
+            last_fetched = "2025-01-01T11:58:30Z"
+
+            [[accounts]]
+            name = "stale@example.com"
+            utilization = 100
+            resets_at = "2025-01-01T11:59:00Z"
+            last_fetched = "2025-01-01T11:59:30Z"
+        "#;
+        let lines = render_fixture(fixture).unwrap();
+        assert!(row(&lines, "maxed@example.com").contains("LIMIT"));
+        assert!(row(&lines, "fine@example.com").contains("Live"));
+        // The badge follows the percentage the row shows, not the cached one
+        let reset = row(&lines, "reset@example.com");
+        assert!(reset.contains(" 0%") && !reset.contains("LIMIT"), "{reset}");
+        let stale = row(&lines, "stale@example.com");
+        assert!(stale.contains("100%") && stale.contains("LIMIT"), "{stale}");
+    }
+
+    #[test]
+    fn blinking_active_row_keeps_its_background() {
+        let fixture = r#"
+            now = "2025-01-01T12:00:00Z"
+            height = 3
+            [settings]
+            limit_alert = "blink"
+            active_marker_color = true
+
+            [[accounts]]
+            name = "maxed@example.com"
+            utilization = 100
+            resets_at = "2025-01-01T13:00:00Z"
+            last_fetched = "2025-01-01T11:59:30Z"
+        "#;
+        let fixture: Fixture = toml::from_str(fixture).unwrap();
+        let now = fixture.now;
+        let app = app_from_fixture(fixture).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(120, 3)).unwrap();
+        terminal.draw(|frame| crate::ui::draw_table_at(frame, &app, now)).unwrap();
+
+        let cell = &terminal.backend().buffer()[(0, 1)];
+        assert!(cell.modifier.contains(ratatui::style::Modifier::REVERSED), "{cell:?}");
+        assert_ne!(cell.bg, ratatui::style::Color::Reset, "Active background kept: {cell:?}");
+    }
+
+    /// Four accounts in one org; b and c are failing.
+    const ORG_FIXTURE: &str = r#"
+        now = "2025-01-01T12:00:00Z"
+        height = 4
+        [settings]
+        org_aggregate = "sum"
+
+        [[accounts]]
+        name = "a@example.com"
+        org_id = "org-a"
+        utilization = 10
+
+        [[accounts]]
+        name = "b@example.com"
+        org_id = "org-a"
+        status = "error"
+        error = "Timeout"
+        utilization = 20
+
+        [[accounts]]
+        name = "c@example.com"
+        org_id = "org-a"
+        status = "error"
+        error = "Timeout"
+        utilization = 30
+
+        [[accounts]]
+        name = "d@example.com"
+        org_id = "org-a"
+        utilization = 40
+    "#;
+
+    #[test]
+    fn org_summaries_stay_pinned_and_follow_the_displayed_rows() {
+        let lines = render_fixture(ORG_FIXTURE).unwrap();
+        assert!(lines.iter().any(|l| l.contains("sum of 4") && l.contains("100%")), "{lines:#?}");
+        assert!(lines.iter().any(|l| l.contains("… 3 more")), "Only account rows count: {lines:#?}");
+
+        let fixture: Fixture = toml::from_str(ORG_FIXTURE).unwrap();
+        let now = fixture.now;
+        let mut app = app_from_fixture(fixture).unwrap();
+        app.errors_only = true;
+        let lines = render_table(&app, 120, 10, now).unwrap();
+        assert!(lines.iter().any(|l| l.contains("sum of 2") && l.contains("50%")), "{lines:#?}");
+    }
+
+    #[test]
+    fn org_summaries_cover_the_page_without_using_its_rows() {
+        let paged = ORG_FIXTURE.replacen("height = 4", "height = 10", 1).replacen(
+            "org_aggregate = \"sum\"",
+            "org_aggregate = \"sum\"\npage_size = 2",
+            1,
+        );
+        let lines = render_fixture(&paged).unwrap();
+        row(&lines, "b@example.com");
+        assert!(lines.iter().any(|l| l.contains("sum of 2") && l.contains("30%")), "{lines:#?}");
+    }
+
+    /// The full screen in focus mode on account `name`.
+    fn render_focus(app: &mut AppState, name: &str) -> String {
+        app.mode = crate::app::AppMode::Focus(name.to_string());
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|frame| crate::ui::draw(frame, app)).unwrap();
+        buffer_lines(terminal.backend().buffer()).join("\n")
+    }
+
+    #[test]
+    fn focus_mode_fills_screen_with_one_account() {
+        let fixture: Fixture = toml::from_str(FIXTURE).unwrap();
+        let mut app = app_from_fixture(fixture).unwrap();
+        // An earlier account going away doesn't move focus onto another one
+        app.accounts.remove(0);
+        let screen = render_focus(&mut app, "busy@example.com");
+
+        assert!(screen.contains("busy@example.com"), "{screen}");
+        assert!(screen.contains(" 5h window ") && screen.contains(" 7d window "), "{screen}");
+        assert!(!screen.contains("broken@example.com"), "Other accounts hidden:\n{screen}");
+    }
+
+    #[test]
+    fn focus_mode_shows_the_same_reading_as_the_row() {
+        let fixture: Fixture = toml::from_str(FIXTURE).unwrap();
+        let mut app = app_from_fixture(fixture).unwrap();
+        app.settings.recalculate_after_reset = true;
+        let screen = render_focus(&mut app, "reset@example.com");
+        assert!(screen.contains("recalc…") && screen.contains("recalculating…"), "{screen}");
+        assert!(!screen.contains("90%"), "{screen}");
+    }
+
+    #[test]
+    fn unknown_status_is_rejected() {
+        let fixture = r#"
+            now = "2025-01-01T12:00:00Z"
+            [[accounts]]
+            name = "a"
+            status = "weird"
+        "#;
+        let err = render_fixture(fixture).unwrap_err();
+        assert!(err.to_string().contains("Unknown status"));
+    }
+}
@@ -5,9 +5,36 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
 use ratatui::Frame;
 
-use crate::app::{AccountStatus, AppState, UsageData};
+use crate::app::{
+    AccountState, AccountStatus, AppState, ColorSupport, TokenCounts, UsageData, WeeklyCadence, WindowReading,
+};
+use crate::config::{AuthMethod, BarGlyphs, CountdownFormat, LimitAlert, OrgAggregate, Settings};
 
-fn utilization_color(pct: u32) -> Color {
+/// Background for the active account's row when `active_marker_color` is set.
+fn active_row_bg(colors: ColorSupport) -> Color {
+    match colors {
+        ColorSupport::Basic => Color::Blue,
+        _ => Color::Indexed(236),
+    }
+}
+
+/// Unfilled bar segments and other de-emphasized chrome.
+fn dim_color(colors: ColorSupport) -> Color {
+    match colors {
+        ColorSupport::Basic => Color::DarkGray,
+        _ => Color::Indexed(238),
+    }
+}
+
+pub(crate) fn utilization_color(pct: u32, colors: ColorSupport) -> Color {
+    if colors == ColorSupport::Basic {
+        // A ten-step ramp collapses to mud in 16 colors; use three clear bands
+        return match pct {
+            0..=50 => Color::Green,
+            51..=80 => Color::Yellow,
+            _ => Color::Red,
+        };
+    }
     match pct {
         0..=10 => Color::Indexed(22),
         11..=20 => Color::Indexed(28),
@@ -22,146 +49,355 @@ fn utilization_color(pct: u32) -> Color {
     }
 }
 
-fn progress_bar_line(pct: u32, color: Color) -> Line<'static> {
-    let filled = if pct == 0 {
-        0
-    } else if pct >= 100 {
-        10
-    } else {
-        ((pct * 10 + 50) / 100).clamp(1, 10) as usize
-    };
-    let empty = 10 - filled;
+/// Usage bar geometry and glyphs, from settings.
+#[derive(Debug, Clone, Copy)]
+struct Bar {
+    width: usize,
+    glyphs: BarGlyphs,
+    colors: ColorSupport,
+}
+
+impl Bar {
+    fn new(settings: &Settings, colors: ColorSupport) -> Self {
+        Self {
+            width: settings.bar_width.max(1) as usize,
+            glyphs: settings.bar_glyphs,
+            colors,
+        }
+    }
+
+    /// (filled, empty, no-data) characters.
+    fn chars(&self) -> (&'static str, &'static str, &'static str) {
+        match self.glyphs {
+            BarGlyphs::Blocks => ("\u{2588}", "\u{2591}", "\u{2500}"),
+            BarGlyphs::Ascii => ("#", "-", "."),
+            BarGlyphs::Braille => ("\u{28ff}", "\u{2840}", "\u{2812}"),
+        }
+    }
+
+    /// Segments to fill for `pct`: none at 0%, all at 100%+, otherwise rounded
+    /// but never fewer than one so any usage is visible.
+    fn filled(&self, pct: u32) -> usize {
+        let width = self.width as u32;
+        if pct == 0 {
+            0
+        } else if pct >= 100 {
+            self.width
+        } else {
+            ((pct * width + 50) / 100).clamp(1, width) as usize
+        }
+    }
 
-    let filled_str: String = "\u{2588}".repeat(filled);
-    let empty_str: String = "\u{2591}".repeat(empty);
+    fn line(&self, pct: u32, color: Color) -> Line<'static> {
+        let (full, empty, _) = self.chars();
+        let filled = self.filled(pct);
 
-    Line::from(vec![
-        Span::styled(filled_str, Style::default().fg(color)),
-        Span::styled(empty_str, Style::default().fg(Color::Indexed(238))),
-    ])
+        Line::from(vec![
+            Span::styled(full.repeat(filled), Style::default().fg(color)),
+            Span::styled(
+                empty.repeat(self.width - filled),
+                Style::default().fg(dim_color(self.colors)),
+            ),
+        ])
+    }
+
+    fn empty_line(&self) -> Line<'static> {
+        let (_, _, none) = self.chars();
+        Line::from(Span::styled(
+            none.repeat(self.width),
+            Style::default().fg(dim_color(self.colors)),
+        ))
+    }
 }
 
-fn empty_bar_line() -> Line<'static> {
-    Line::from(Span::styled(
-        "\u{2500}".repeat(10),
-        Style::default().fg(Color::Indexed(238)),
-    ))
+/// Display options shared by every row.
+struct RowOptions {
+    show_tokens: bool,
+    /// Show a passed reset as pending rather than 0% until the next fetch.
+    recalculate_after_reset: bool,
+    bar: Bar,
+    colors: ColorSupport,
+    countdown: CountdownFormat,
+    /// Weekly usage under this percentage is shown as a dimmed "<N%" with no bar.
+    weekly_hide_below: u32,
+}
+
+/// The `#` and name cells' text, and the account's accent color if it has one.
+struct RowLabel {
+    num: String,
+    name: String,
+    accent: Option<Color>,
+    /// Shortened org_id when the org column is on (`o`).
+    org: Option<String>,
+}
+
+/// Enough of an org UUID to tell orgs apart at a glance.
+fn short_org_id(org_id: &str) -> String {
+    match org_id.char_indices().nth(8) {
+        Some((end, _)) => format!("{}…", &org_id[..end]),
+        None if org_id.is_empty() => "-".to_string(),
+        None => org_id.to_string(),
+    }
+}
+
+/// Insert the org column's cell after the name when it's shown.
+fn with_org_cell(mut cells: Vec<Cell<'static>>, org: Option<String>) -> Row<'static> {
+    if let Some(org) = org {
+        cells.insert(2, Cell::from(Span::styled(org, Style::default().fg(Color::DarkGray))));
+    }
+    Row::new(cells)
+}
+
+/// The account's configured accent, if it parses as a color.
+fn accent_color(account: &AccountState) -> Option<Color> {
+    account.config.accent_color.as_deref()?.trim().parse().ok()
 }
 
 /// Build a placeholder row with "--" for all usage columns and a custom status cell.
-fn placeholder_row(num: String, name: String, status: &str, color: Color) -> Row<'static> {
+fn placeholder_row(label: RowLabel, status_cell: Cell<'static>, color: Color, bar: Bar) -> Row<'static> {
     let style = Style::default().fg(color);
-    Row::new(vec![
-        Cell::from(Span::styled(num, style)),
-        Cell::from(Span::styled(name, style)),
+    let label_style = Style::default().fg(label.accent.unwrap_or(color));
+    let cells = vec![
+        Cell::from(Span::styled(label.num, label_style)),
+        Cell::from(Span::styled(label.name, label_style)),
         Cell::from(Span::styled("--", style)),
-        Cell::from(empty_bar_line()),
+        Cell::from(bar.empty_line()),
         Cell::from(Span::styled("--", style)),
         Cell::from(Span::styled("--", style)),
-        Cell::from(empty_bar_line()),
+        Cell::from(bar.empty_line()),
         Cell::from(Span::styled("--", style)),
-        Cell::from(Span::styled(status.to_string(), style)),
-    ])
+        status_cell,
+    ];
+    with_org_cell(cells, label.org)
+}
+
+/// Compact token count: 950, 123k, 1.2M.
+pub(crate) fn format_tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{}k", n / 1_000)
+    } else {
+        n.to_string()
+    }
+}
+
+/// "123k/200k" when counts are known and wanted, otherwise the percentage.
+fn usage_label(pct: u32, tokens: Option<TokenCounts>, show_tokens: bool) -> String {
+    match tokens {
+        Some(t) if show_tokens => format!("{}/{}", format_tokens(t.used), format_tokens(t.limit)),
+        _ => format!("{pct}%"),
+    }
 }
 
 /// Build a row with usage data (timers, bars, percentages) and a custom status cell.
 fn usage_row(
-    num: String,
-    name: String,
+    label: RowLabel,
     usage: &UsageData,
     is_selected: bool,
+    options: &RowOptions,
     status_cell: Cell<'static>,
+    last_fetched: Option<chrono::DateTime<Utc>>,
+    now: chrono::DateTime<Utc>,
 ) -> Row<'static> {
-    let now = Utc::now();
+    let recalculate = options.recalculate_after_reset;
+    let (h5_pct, h5_bar, h5_reset, h5_color) = if let WindowReading::Pct(h5_util) =
+        usage.five_hour_reading(last_fetched, recalculate, now)
+    {
+        let color = utilization_color(h5_util, options.colors);
+        // Counts are stale once the window has reset, so fall back to the 0%
+        let tokens = usage.tokens.filter(|_| h5_util == usage.utilization);
+        (
+            usage_label(h5_util, tokens, options.show_tokens),
+            options.bar.line(h5_util, color),
+            reset_text(usage, Window::FiveHour, options.countdown, now),
+            color,
+        )
+    } else {
+        recalculating_cells(&options.bar)
+    };
 
-    // If resets_at has passed, the server has reset the window —
-    // show 0% locally instead of stale cached utilization.
-    let h5_util = if usage.resets_at.map_or(false, |r| now > r) {
-        0
+    let d7 = usage.weekly_reading(last_fetched, recalculate, now);
+    let (d7_pct, d7_bar, d7_reset, d7_color) = if d7 == Some(WindowReading::Recalculating) {
+        recalculating_cells(&options.bar)
+    } else if d7.is_some_and(|reading| reading.pct() < options.weekly_hide_below) {
+        (
+            format!("<{}%", options.weekly_hide_below),
+            options.bar.empty_line(),
+            "--".to_string(),
+            Color::DarkGray,
+        )
+    } else if let Some(WindowReading::Pct(effective)) = d7 {
+        let color = utilization_color(effective, options.colors);
+        let reset = reset_text(usage, Window::Weekly, options.countdown, now);
+        let tokens = usage
+            .weekly_tokens
+            .filter(|_| Some(effective) == usage.weekly_utilization);
+        (
+            usage_label(effective, tokens, options.show_tokens),
+            options.bar.line(effective, color),
+            reset,
+            color,
+        )
     } else {
-        usage.utilization
+        (
+            "--".to_string(),
+            options.bar.empty_line(),
+            "--".to_string(),
+            Color::DarkGray,
+        )
     };
-    let h5_color = utilization_color(h5_util);
-    let h5_pct = format!("{}%", h5_util);
-    let h5_bar = progress_bar_line(h5_util, h5_color);
-    let h5_reset = usage
-        .resets_at
-        .as_ref()
-        .map(format_countdown)
-        .unwrap_or_else(|| "--".to_string());
-
-    let (d7_pct, d7_bar, d7_reset, d7_color) =
-        if let Some(weekly_util) = usage.weekly_utilization {
-            let effective = if usage.weekly_resets_at.map_or(false, |r| now > r) {
-                0
-            } else {
-                weekly_util
-            };
-            let color = utilization_color(effective);
-            let reset = usage
-                .weekly_resets_at
-                .as_ref()
-                .map(format_countdown)
-                .unwrap_or_else(|| "--".to_string());
-            (
-                format!("{}%", effective),
-                progress_bar_line(effective, color),
-                reset,
-                color,
-            )
-        } else {
-            (
-                "--".to_string(),
-                empty_bar_line(),
-                "--".to_string(),
-                Color::DarkGray,
-            )
-        };
 
+    let label_color = label.accent.unwrap_or(h5_color);
     let name_style = if is_selected {
         Style::default()
-            .fg(h5_color)
+            .fg(label_color)
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(h5_color)
+        Style::default().fg(label_color)
     };
 
     let _ = d7_color; // used for bar already
 
-    Row::new(vec![
-        Cell::from(Span::styled(num, Style::default().fg(h5_color))),
-        Cell::from(Span::styled(name, name_style)),
+    let cells = vec![
+        Cell::from(Span::styled(label.num, Style::default().fg(label_color))),
+        Cell::from(Span::styled(label.name, name_style)),
         Cell::from(Span::styled(h5_pct, Style::default().fg(h5_color))),
         Cell::from(h5_bar),
-        Cell::from(Span::styled(h5_reset, Style::default().fg(Color::Gray))),
+        Cell::from(Span::styled(h5_reset, Style::default().fg(reset_color(usage.resets_at, now)))),
         Cell::from(Span::styled(d7_pct, Style::default().fg(d7_color))),
         Cell::from(d7_bar),
-        Cell::from(Span::styled(d7_reset, Style::default().fg(Color::Gray))),
+        Cell::from(Span::styled(
+            d7_reset,
+            Style::default().fg(reset_color(usage.weekly_resets_at, now)),
+        )),
         status_cell,
-    ])
+    ];
+    with_org_cell(cells, label.org)
 }
 
-fn format_countdown(resets_at: &chrono::DateTime<Utc>) -> String {
-    let now = Utc::now();
+/// % / bar / reset / color for a window whose reset has passed but hasn't been
+/// re-fetched yet, when `recalculate_after_reset` is on.
+fn recalculating_cells(bar: &Bar) -> (String, Line<'static>, String, Color) {
+    ("…".to_string(), bar.empty_line(), "recalc…".to_string(), Color::DarkGray)
+}
+
+/// Reset column color: yellow flags a suspect reset time.
+fn reset_color(resets_at: Option<chrono::DateTime<Utc>>, now: chrono::DateTime<Utc>) -> Color {
+    if resets_at.is_some_and(|r| is_suspect_reset(&r, now)) {
+        Color::Yellow
+    } else {
+        Color::Gray
+    }
+}
+
+pub(crate) fn format_countdown(resets_at: &chrono::DateTime<Utc>, format: CountdownFormat) -> String {
+    format_countdown_at(resets_at, Utc::now(), format)
+}
+
+/// No window the API reports is longer than a week; anything past this is a bad value
+/// (e.g. an epoch mix-up), not a real reset time.
+const MAX_COUNTDOWN_SECS: i64 = 30 * 86400;
+
+/// The reset time is too far out to be real, so it's shown as "?" rather than as
+/// something like "50000d 3h".
+fn is_suspect_reset(resets_at: &chrono::DateTime<Utc>, now: chrono::DateTime<Utc>) -> bool {
+    resets_at.signed_duration_since(now).num_seconds() > MAX_COUNTDOWN_SECS
+}
+
+fn format_countdown_at(
+    resets_at: &chrono::DateTime<Utc>,
+    now: chrono::DateTime<Utc>,
+    format: CountdownFormat,
+) -> String {
     let diff = resets_at.signed_duration_since(now);
     let total_secs = diff.num_seconds();
 
     if total_secs <= 0 {
         return "now".to_string();
     }
+    if is_suspect_reset(resets_at, now) {
+        return "?".to_string();
+    }
 
     let days = total_secs / 86400;
     let hours = (total_secs % 86400) / 3600;
     let mins = (total_secs % 3600) / 60;
+    let secs = total_secs % 60;
 
-    if days > 0 {
-        format!("{}d {}h", days, hours)
-    } else if hours > 0 {
-        format!("{}h {:02}m", hours, mins)
-    } else if mins > 0 {
-        format!("{}m {:02}s", mins, total_secs % 60)
-    } else {
-        format!("{}s", total_secs)
+    match format {
+        CountdownFormat::Full => {
+            if days > 0 {
+                format!("{}d {}h", days, hours)
+            } else if hours > 0 {
+                format!("{}h {:02}m", hours, mins)
+            } else if mins > 0 {
+                format!("{}m {:02}s", mins, secs)
+            } else {
+                format!("{}s", total_secs)
+            }
+        }
+        CountdownFormat::Compact => {
+            if days > 0 {
+                format!("{days}d")
+            } else if hours > 0 {
+                format!("{hours}h")
+            } else if mins > 0 {
+                format!("{mins}m")
+            } else {
+                format!("{secs}s")
+            }
+        }
+        CountdownFormat::Clock => {
+            if days > 0 {
+                format!("{days}d {hours:02}:{mins:02}")
+            } else if hours > 0 {
+                format!("{hours}:{mins:02}:{secs:02}")
+            } else {
+                format!("{mins}:{secs:02}")
+            }
+        }
+    }
+}
+
+/// Which of an account's two usage windows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Window {
+    FiveHour,
+    Weekly,
+}
+
+/// When `window` resets, as the table and focus view show it: a countdown in the
+/// configured format (the reset day for a calendar week), or "--" when unknown.
+pub(crate) fn reset_text(
+    usage: &UsageData,
+    window: Window,
+    format: CountdownFormat,
+    now: chrono::DateTime<Utc>,
+) -> String {
+    match window {
+        Window::FiveHour => usage.resets_at.map(|r| format_countdown_at(&r, now, format)),
+        Window::Weekly => usage
+            .weekly_resets_at
+            .map(|r| weekly_reset_label(&r, usage.weekly_cadence, format, now)),
+    }
+    .unwrap_or_else(|| "--".to_string())
+}
+
+/// Calendar-boundary windows read better as the reset day ("Mon 09:00", local time)
+/// than as a countdown; rolling or unknown windows keep the countdown.
+fn weekly_reset_label(
+    resets_at: &chrono::DateTime<Utc>,
+    cadence: Option<WeeklyCadence>,
+    format: CountdownFormat,
+    now: chrono::DateTime<Utc>,
+) -> String {
+    match cadence {
+        Some(WeeklyCadence::Calendar) if *resets_at > now && !is_suspect_reset(resets_at, now) => resets_at
+            .with_timezone(&chrono::Local)
+            .format("%a %H:%M")
+            .to_string(),
+        _ => format_countdown_at(resets_at, now, format),
     }
 }
 
@@ -174,103 +410,378 @@ fn truncate_error(msg: &str) -> String {
     }
 }
 
-fn ok_status_cell(account_name: &str, last_fetched: Option<&chrono::DateTime<Utc>>, logged_in_account: Option<&String>) -> Cell<'static> {
-    let is_logged_in = logged_in_account
-        .map(|n| n == account_name)
-        .unwrap_or(false);
+/// Short hint for when an errored account will be retried by the next automatic poll.
+fn retry_hint(next_poll_at: Option<&chrono::DateTime<Utc>>, now: chrono::DateTime<Utc>) -> Option<String> {
+    let secs = next_poll_at?.signed_duration_since(now).num_seconds();
+    Some(if secs <= 0 {
+        "retrying".to_string()
+    } else if secs < 60 {
+        format!("retry in {secs}s")
+    } else {
+        format!("retry in {}m", (secs + 59) / 60)
+    })
+}
 
-    if is_logged_in {
-        Cell::from(Span::styled(
-            "Logged In",
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD),
-        ))
-    } else if let Some(fetched) = last_fetched {
-        let ago = Utc::now().signed_duration_since(*fetched).num_minutes();
-        let label = if ago < 2 {
-            "Live".to_string()
-        } else if ago < 60 {
-            format!("{}m ago", ago)
-        } else {
-            format!("{}h ago", ago / 60)
-        };
-        let color = if ago < 2 { Color::Gray } else { Color::Yellow };
-        Cell::from(Span::styled(label, Style::default().fg(color)))
+/// Status column text and color for an account with usage data: "Logged In" when
+/// Claude Code is using it, otherwise how fresh the data is.
+pub(crate) fn status_label(
+    account: &AccountState,
+    logged_in_account: Option<&str>,
+    now: chrono::DateTime<Utc>,
+) -> (String, Color) {
+    if logged_in_account == Some(account.config.name.as_str()) {
+        return ("Logged In".to_string(), Color::Green);
+    }
+    let Some(fetched) = &account.last_fetched else {
+        return ("--".to_string(), Color::DarkGray);
+    };
+    let ago = now.signed_duration_since(*fetched).num_minutes();
+    if ago < 2 {
+        ("Live".to_string(), Color::Gray)
+    } else if ago < 60 {
+        (format!("{}m ago", ago), Color::Yellow)
     } else {
-        Cell::from(Span::styled("--", Style::default().fg(Color::DarkGray)))
+        (format!("{}h ago", ago / 60), Color::Yellow)
     }
 }
 
-pub fn render(frame: &mut Frame, area: Rect, app: &AppState) {
-    let header = Row::new(vec![
-        Cell::from(" # "),
-        Cell::from("Name"),
-        Cell::from("5h %"),
-        Cell::from("5h Bar"),
-        Cell::from("5h Reset"),
-        Cell::from("7d %"),
-        Cell::from("7d Bar"),
-        Cell::from("7d Reset"),
-        Cell::from("Status"),
-    ])
-    .style(
+fn ok_status_cell(
+    account: &AccountState,
+    logged_in_account: Option<&str>,
+    now: chrono::DateTime<Utc>,
+) -> Cell<'static> {
+    let (label, color) = status_label(account, logged_in_account, now);
+    let mut style = Style::default().fg(color);
+    if logged_in_account == Some(account.config.name.as_str()) {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    with_key_expiry_badge(Span::styled(label, style), account, now)
+}
+
+/// Start warning this long before a recorded session-key expiry.
+const KEY_EXPIRY_WARN_DAYS: i64 = 7;
+
+/// "key 3d" / "key expired" once a user-recorded `session_key_expires` is near or past.
+pub(crate) fn key_expiry_badge(account: &AccountState, now: chrono::DateTime<Utc>) -> Option<(String, Color)> {
+    if account.config.auth_method != AuthMethod::SessionKey {
+        return None;
+    }
+    let expires = account.config.session_key_expires?;
+    let left = expires.signed_duration_since(now);
+    if left <= chrono::Duration::zero() {
+        Some(("key expired".to_string(), Color::Red))
+    } else if left < chrono::Duration::days(KEY_EXPIRY_WARN_DAYS) {
+        let countdown = format_countdown_at(&expires, now, CountdownFormat::Compact);
+        Some((format!("key {countdown}"), Color::Yellow))
+    } else {
+        None
+    }
+}
+
+fn with_key_expiry_badge(status: Span<'static>, account: &AccountState, now: chrono::DateTime<Utc>) -> Cell<'static> {
+    match key_expiry_badge(account, now) {
+        Some((badge, color)) => Cell::from(Line::from(vec![
+            status,
+            Span::styled(format!(" ⚠ {badge}"), Style::default().fg(color)),
+        ])),
+        None => Cell::from(status),
+    }
+}
+
+/// Either window is used up as of `now`, by the same reading the row displays.
+fn is_at_limit(account: &AccountState, recalculate: bool, now: chrono::DateTime<Utc>) -> bool {
+    account.five_hour_reading(recalculate, now).is_some_and(|r| r.pct() >= 100)
+        || account.weekly_reading(recalculate, now).is_some_and(|r| r.pct() >= 100)
+}
+
+fn limit_badge_cell() -> Cell<'static> {
+    Cell::from(Span::styled(
+        " LIMIT ",
         Style::default()
-            .fg(Color::DarkGray)
+            .fg(Color::White)
+            .bg(Color::Red)
             .add_modifier(Modifier::BOLD),
-    );
+    ))
+}
 
-    let rows: Vec<Row> = app
-        .accounts
+/// Table rows for `order` (indices into `app.accounts`) as of `now`.
+pub(crate) fn build_rows(app: &AppState, order: &[usize], now: chrono::DateTime<Utc>) -> Vec<Row<'static>> {
+    let options = RowOptions {
+        show_tokens: app.settings.show_token_counts,
+        recalculate_after_reset: app.settings.recalculate_after_reset,
+        bar: Bar::new(&app.settings, app.color_support),
+        colors: app.color_support,
+        countdown: app.settings.countdown_format,
+        weekly_hide_below: app.settings.weekly_hide_below_pct,
+    };
+    order
         .iter()
-        .enumerate()
-        .map(|(i, account)| {
+        .map(|&i| {
+            let account = &app.accounts[i];
             let is_selected = i == app.selected_index;
             let is_active = i == app.active_account_index;
 
             let prefix = if is_selected { ">" } else { " " };
-            let num = format!("{}{}", prefix, i + 1);
+            let star = if account.config.favorite { "★" } else { "" };
+            let num = format!("{}{}{}", prefix, star, i + 1);
 
-            let name = if is_active {
-                format!("{} *", account.config.name)
-            } else {
-                account.config.name.clone()
+            let color_marker = app.settings.active_marker_color;
+            let mut name = match &account.config.label {
+                Some(tag) => format!("{tag} {}", account.config.name),
+                None => account.config.name.clone(),
+            };
+            if is_active && !color_marker {
+                name.push_str(" *");
+            }
+            if app.fast_poll.contains_key(&account.config.name) {
+                name.push_str(" [fast]");
+            }
+            let label = RowLabel {
+                num,
+                name,
+                accent: accent_color(account),
+                org: app.show_org_column.then(|| short_org_id(&account.config.org_id)),
             };
 
-            match &account.status {
+            // Rows without usage still warn about a recorded key expiry
+            let placeholder_status = |text: &str, color: Color| {
+                with_key_expiry_badge(Span::styled(text.to_string(), Style::default().fg(color)), account, now)
+            };
+            let alert = app.settings.limit_alert;
+            let at_limit = is_at_limit(account, options.recalculate_after_reset, now);
+
+            let row = match &account.status {
                 AccountStatus::Idle => {
-                    placeholder_row(num, name, "Idle", Color::DarkGray)
+                    placeholder_row(label, placeholder_status("Idle", Color::DarkGray), Color::DarkGray, options.bar)
                 }
                 AccountStatus::Ok => {
                     if let Some(usage) = &account.usage {
-                        let status_cell = ok_status_cell(
-                            &account.config.name,
-                            account.last_fetched.as_ref(),
-                            app.logged_in_account.as_ref(),
-                        );
-                        usage_row(num, name, usage, is_selected, status_cell)
+                        let status_cell = if at_limit && alert == LimitAlert::Badge {
+                            limit_badge_cell()
+                        } else {
+                            ok_status_cell(account, app.logged_in_account.as_deref(), now)
+                        };
+                        usage_row(label, usage, is_selected, &options, status_cell, account.last_fetched, now)
                     } else {
-                        placeholder_row(num, name, "OK", Color::Gray)
+                        placeholder_row(label, placeholder_status("OK", Color::Gray), Color::Gray, options.bar)
                     }
                 }
                 AccountStatus::Error(ref msg) => {
-                    let short = truncate_error(msg);
+                    let short = match retry_hint(app.next_poll_at.as_ref(), now) {
+                        Some(hint) => format!("{} · {hint}", truncate_error(msg)),
+                        None => truncate_error(msg),
+                    };
 
                     // If we have last-known usage data, keep showing timers + percentages.
                     // Only the Status column shows the error.
                     if let Some(usage) = &account.usage {
-                        let status_cell = Cell::from(Span::styled(
-                            short,
-                            Style::default().fg(Color::Red),
-                        ));
-                        usage_row(num, name, usage, is_selected, status_cell)
+                        let status_cell = with_key_expiry_badge(
+                            Span::styled(short, Style::default().fg(Color::Red)),
+                            account,
+                            now,
+                        );
+                        usage_row(label, usage, is_selected, &options, status_cell, account.last_fetched, now)
                     } else {
-                        placeholder_row(num, name, &short, Color::Red)
+                        placeholder_row(label, placeholder_status(&short, Color::Red), Color::Red, options.bar)
                     }
                 }
+            };
+
+            let mut style = Style::default();
+            if is_active && color_marker {
+                style = style.bg(active_row_bg(options.colors));
+            }
+            // Blink once a second while a just-reset window is being announced, or
+            // (opt-in) while the account is maxed out. Patched over the row style so
+            // the active-row background survives the blink.
+            let blinking = app.is_flashing(&account.config.name, now)
+                || (at_limit && alert == LimitAlert::Blink);
+            if blinking && now.timestamp() % 2 == 0 {
+                style = style.patch(Style::default().add_modifier(Modifier::REVERSED));
             }
+            row.style(style)
         })
-        .collect();
+        .collect()
+}
+
+/// Rolled-up usage for one org with several accounts.
+#[derive(Debug, PartialEq)]
+struct OrgSummary {
+    org_id: String,
+    accounts: usize,
+    /// `Recalculating` while any member is.
+    utilization: WindowReading,
+    weekly_utilization: Option<WindowReading>,
+    /// Soonest 5h reset among the org's accounts — when headroom next frees up.
+    resets_at: Option<chrono::DateTime<Utc>>,
+}
+
+/// Summaries for each org_id shared by two or more of `accounts` with usage data, in
+/// order of first appearance.
+fn org_summaries(
+    accounts: &[&AccountState],
+    mode: OrgAggregate,
+    recalculate: bool,
+    now: chrono::DateTime<Utc>,
+) -> Vec<OrgSummary> {
+    let mut org_ids: Vec<&str> = Vec::new();
+    for account in accounts {
+        let org_id = account.config.org_id.as_str();
+        if !org_id.is_empty() && !org_ids.contains(&org_id) {
+            org_ids.push(org_id);
+        }
+    }
+
+    let combine = |readings: Vec<WindowReading>| {
+        if readings.contains(&WindowReading::Recalculating) {
+            return WindowReading::Recalculating;
+        }
+        let values = readings.into_iter().map(WindowReading::pct);
+        WindowReading::Pct(match mode {
+            OrgAggregate::Sum => values.sum(),
+            OrgAggregate::Max => values.max().unwrap_or(0),
+        })
+    };
+
+    org_ids
+        .into_iter()
+        .filter_map(|org_id| {
+            let members: Vec<&AccountState> = accounts
+                .iter()
+                .copied()
+                .filter(|a| a.config.org_id == org_id && a.usage.is_some())
+                .collect();
+            if members.len() < 2 {
+                return None;
+            }
+            let weekly: Vec<WindowReading> = members
+                .iter()
+                .filter_map(|a| a.weekly_reading(recalculate, now))
+                .collect();
+            Some(OrgSummary {
+                org_id: org_id.to_string(),
+                accounts: members.len(),
+                utilization: combine(
+                    members
+                        .iter()
+                        .filter_map(|a| a.five_hour_reading(recalculate, now))
+                        .collect(),
+                ),
+                weekly_utilization: (!weekly.is_empty()).then(|| combine(weekly)),
+                resets_at: members
+                    .iter()
+                    .filter_map(|a| a.usage.as_ref()?.resets_at)
+                    .filter(|r| *r > now)
+                    .min(),
+            })
+        })
+        .collect()
+}
+
+fn org_summary_row(
+    summary: &OrgSummary,
+    mode: OrgAggregate,
+    bar: Bar,
+    show_org: bool,
+    countdown: CountdownFormat,
+    now: chrono::DateTime<Utc>,
+) -> Row<'static> {
+    let label = match mode {
+        OrgAggregate::Sum => "sum",
+        OrgAggregate::Max => "max",
+    };
+    let org = short_org_id(&summary.org_id);
+    let style = Style::default().fg(Color::Cyan).add_modifier(Modifier::ITALIC);
+    let dim = Style::default().fg(Color::DarkGray);
+    let pct_cell = |reading: Option<WindowReading>| match reading {
+        Some(WindowReading::Pct(pct)) => Cell::from(Span::styled(format!("{pct}%"), style)),
+        Some(WindowReading::Recalculating) => Cell::from(Span::styled("…", dim)),
+        None => Cell::from(Span::styled("--", dim)),
+    };
+    let bar_cell = |reading: Option<WindowReading>| match reading {
+        Some(WindowReading::Pct(pct)) => Cell::from(bar.line(pct, utilization_color(pct.min(100), bar.colors))),
+        _ => Cell::from(bar.empty_line()),
+    };
+
+    // With the org column on, the org moves out of the name cell into it
+    let (name, org_cell) = if show_org {
+        (format!("{label} of {}", summary.accounts), Some(org))
+    } else {
+        (format!("{org} {label} of {}", summary.accounts), None)
+    };
+    let cells = vec![
+        Cell::from(Span::styled(" Σ", style)),
+        Cell::from(Span::styled(name, style)),
+        pct_cell(Some(summary.utilization)),
+        bar_cell(Some(summary.utilization)),
+        Cell::from(Span::styled(
+            summary
+                .resets_at
+                .as_ref()
+                .map(|r| format_countdown_at(r, now, countdown))
+                .unwrap_or_else(|| "--".to_string()),
+            Style::default().fg(Color::Gray),
+        )),
+        pct_cell(summary.weekly_utilization),
+        bar_cell(summary.weekly_utilization),
+        Cell::from(Span::styled("--", dim)),
+        Cell::from(""),
+    ];
+    with_org_cell(cells, org_cell)
+}
+
+pub fn render(frame: &mut Frame, area: Rect, app: &AppState, compact: bool) {
+    render_at(frame, area, app, compact, Utc::now());
+}
+
+/// Render as of a fixed `now`, so countdowns and reset-passed logic are reproducible.
+pub(crate) fn render_at(
+    frame: &mut Frame,
+    area: Rect,
+    app: &AppState,
+    compact: bool,
+    now: chrono::DateTime<Utc>,
+) {
+    let show_org = app.show_org_column;
+    let header = with_org_cell(vec![
+        Cell::from(" # "),
+        Cell::from("Name"),
+        Cell::from("5h %"),
+        Cell::from("5h Bar"),
+        Cell::from("5h Reset"),
+        Cell::from("7d %"),
+        Cell::from("7d Bar"),
+        Cell::from("7d Reset"),
+        Cell::from("Status"),
+    ], show_org.then(|| "Org".to_string()))
+    .style(
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let show_tokens = app.settings.show_token_counts;
+    let mut order = app.display_order();
+    let page = app.page();
+    if let (Some((page, _)), Some(size)) = (page, app.settings.page_size) {
+        order = order.into_iter().skip(page * size).take(size).collect();
+    }
+    let rows = build_rows(app, &order, now);
+    // Summaries of the displayed accounts are pinned below their rows: j/k can't
+    // select them, so they must not scroll away or count toward the hidden rows
+    let mut summary_rows: Vec<Row> = match app.settings.org_aggregate.filter(|_| !rows.is_empty()) {
+        Some(mode) => {
+            let displayed: Vec<&AccountState> = order.iter().map(|&i| &app.accounts[i]).collect();
+            org_summaries(&displayed, mode, app.settings.recalculate_after_reset, now)
+                .iter()
+                .map(|summary| {
+                    let bar = Bar::new(&app.settings, app.color_support);
+                    org_summary_row(summary, mode, bar, show_org, app.settings.countdown_format, now)
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
 
     if app.accounts.is_empty() {
         frame.render_widget(
@@ -283,26 +794,104 @@ pub fn render(frame: &mut Frame, area: Rect, app: &AppState) {
         return;
     }
 
-    let widths = [
-        Constraint::Length(4),  // #
-        Constraint::Min(20),    // Name (flex for long emails)
-        Constraint::Length(5),  // 5h %
-        Constraint::Length(12), // 5h Bar
-        Constraint::Length(9),  // 5h Reset
-        Constraint::Length(5),  // 7d %
-        Constraint::Length(12), // 7d Bar
-        Constraint::Length(9),  // 7d Reset
-        Constraint::Min(8),    // Status
+    if rows.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                "  No accounts in error. Press 'E' to show all accounts.",
+                Style::default().fg(Color::DarkGray),
+            ))),
+            area,
+        );
+        return;
+    }
+
+    // "1.2M/2.5M" needs more room than "100%"
+    let pct_width = if show_tokens { 11 } else { 5 };
+    let bar_width = app.settings.bar_width + 2;
+    let mut widths = vec![
+        Constraint::Length(4),         // #
+        Constraint::Min(20),           // Name (flex for long emails)
+        Constraint::Length(pct_width), // 5h %
+        Constraint::Length(bar_width), // 5h Bar
+        Constraint::Length(9),         // 5h Reset
+        Constraint::Length(pct_width), // 7d %
+        Constraint::Length(bar_width), // 7d Bar
+        Constraint::Length(9),         // 7d Reset
+        Constraint::Min(8),            // Status
     ];
+    if show_org {
+        widths.insert(2, Constraint::Length(10)); // Org (8 chars + "…")
+    }
 
-    let table = Table::new(rows, widths)
-        .header(header)
-        .block(Block::default().borders(Borders::NONE));
+    let header_height = if compact { 0 } else { 1 };
+    let room = area.height.saturating_sub(header_height) as usize;
+    // Account rows keep at least half the room
+    summary_rows.truncate(room / 2);
+    let summary_table_height = summary_rows.len() as u16;
+    let room = room - summary_rows.len();
+    let page_label = page.filter(|&(_, pages)| pages > 1);
+    let (visible, hidden) = if page_label.is_some() {
+        // The page indicator always takes the last line
+        let visible = rows.len().min(room.saturating_sub(1)).min(app.settings.max_rows.unwrap_or(usize::MAX));
+        (visible, rows.len() - visible)
+    } else {
+        visible_rows(rows.len(), room, app.settings.max_rows)
+    };
+
+    let summary_table = Table::new(summary_rows, widths.clone());
+    let mut table = Table::new(rows, widths).block(Block::default().borders(Borders::NONE));
+    if !compact {
+        table = table.header(header);
+    }
 
     let mut state = TableState::default();
-    state.select(Some(app.selected_index));
+    state.select(order.iter().position(|&i| i == app.selected_index));
 
-    frame.render_stateful_widget(table, area, &mut state);
+    let table_area = Rect {
+        height: header_height + visible as u16,
+        ..area
+    };
+    frame.render_stateful_widget(table, table_area, &mut state);
+    let summary_area = Rect {
+        y: table_area.y + table_area.height,
+        height: summary_table_height,
+        ..area
+    };
+    frame.render_widget(summary_table, summary_area);
+
+    let mut footer = Vec::new();
+    if let Some((page, pages)) = page_label {
+        footer.push(format!("Page {}/{pages} (n/p)", page + 1));
+    }
+    if hidden > 0 {
+        footer.push(format!("… {hidden} more (j/k to scroll)"));
+    }
+    if !footer.is_empty() {
+        let footer_area = Rect {
+            y: summary_area.y + summary_area.height,
+            height: 1,
+            ..area
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                format!("  {}", footer.join(" · ")),
+                Style::default().fg(Color::DarkGray),
+            ))),
+            footer_area,
+        );
+    }
+}
+
+/// How many of `total` rows to draw in `room` lines, and how many that hides. When
+/// rows are hidden, one line is kept free for the "N more" footer.
+fn visible_rows(total: usize, room: usize, max_rows: Option<usize>) -> (usize, usize) {
+    let limit = max_rows.map_or(room, |max| max.min(room));
+    if total <= limit {
+        return (total, 0);
+    }
+    // The footer fits below a max_rows cap, but otherwise takes the last line
+    let visible = if limit < room { limit } else { limit.saturating_sub(1) };
+    (visible, total - visible)
 }
 
 // =============================================================================
@@ -333,6 +922,262 @@ mod tests {
         assert_eq!(truncate_error(msg), "This is a long error messag...");
     }
 
+    #[test]
+    fn countdown_formats_across_ranges() {
+        let now = Utc::now();
+        let at = |secs: i64| now + chrono::Duration::seconds(secs);
+        let sub_minute = at(45);
+        let sub_hour = at(12 * 60 + 34);
+        let hours = at(3 * 3600 + 5 * 60 + 12);
+        let days = at(2 * 86400 + 4 * 3600 + 30 * 60);
+
+        let full = |r| format_countdown_at(&r, now, CountdownFormat::Full);
+        assert_eq!(full(sub_minute), "45s");
+        assert_eq!(full(sub_hour), "12m 34s");
+        assert_eq!(full(hours), "3h 05m");
+        assert_eq!(full(days), "2d 4h");
+
+        let compact = |r| format_countdown_at(&r, now, CountdownFormat::Compact);
+        assert_eq!(compact(sub_minute), "45s");
+        assert_eq!(compact(sub_hour), "12m");
+        assert_eq!(compact(hours), "3h");
+        assert_eq!(compact(days), "2d");
+
+        let clock = |r| format_countdown_at(&r, now, CountdownFormat::Clock);
+        assert_eq!(clock(sub_minute), "0:45");
+        assert_eq!(clock(sub_hour), "12:34");
+        assert_eq!(clock(hours), "3:05:12");
+        assert_eq!(clock(days), "2d 04:30");
+    }
+
+    #[test]
+    fn countdown_edge_values_ignore_format() {
+        let now = Utc::now();
+        for format in [CountdownFormat::Full, CountdownFormat::Compact, CountdownFormat::Clock] {
+            assert_eq!(format_countdown_at(&(now - chrono::Duration::seconds(5)), now, format), "now");
+            assert_eq!(format_countdown_at(&(now + chrono::Duration::days(31)), now, format), "?");
+        }
+    }
+
+    #[test]
+    fn countdown_caps_at_thirty_days() {
+        let now = Utc::now();
+        let at = |secs: i64| now + chrono::Duration::seconds(secs);
+        assert_eq!(format_countdown_at(&at(30 * 86400), now, CountdownFormat::Full), "30d 0h");
+        assert_eq!(format_countdown_at(&at(30 * 86400 + 1), now, CountdownFormat::Full), "?");
+        assert_eq!(format_countdown_at(&at(i64::from(i32::MAX)), now, CountdownFormat::Full), "?");
+        assert_eq!(reset_color(Some(at(30 * 86400 + 1)), now), Color::Yellow);
+        assert_eq!(reset_color(Some(at(86400)), now), Color::Gray);
+    }
+
+    #[test]
+    fn suspect_calendar_reset_is_not_shown_as_a_weekday() {
+        let now = Utc::now();
+        let far = now + chrono::Duration::days(400);
+        assert_eq!(weekly_reset_label(&far, Some(WeeklyCadence::Calendar), CountdownFormat::Full, now), "?");
+    }
+
+    #[test]
+    fn retry_hint_rounds_up_to_minutes() {
+        let now = Utc::now();
+        let next = now + chrono::Duration::seconds(61);
+        assert_eq!(retry_hint(Some(&next), now).as_deref(), Some("retry in 2m"));
+    }
+
+    #[test]
+    fn short_org_id_keeps_eight_chars() {
+        assert_eq!(short_org_id("65f10de7-1c2d-4e5f"), "65f10de7…");
+        assert_eq!(short_org_id("org-1"), "org-1");
+        assert_eq!(short_org_id(""), "-");
+    }
+
+    #[test]
+    fn accent_color_accepts_names_indexes_and_hex() {
+        let mut acct = account("org", None);
+        assert_eq!(accent_color(&acct), None);
+        for (value, color) in [
+            ("magenta", Some(Color::Magenta)),
+            ("208", Some(Color::Indexed(208))),
+            ("#ff8800", Some(Color::Rgb(255, 136, 0))),
+            ("not-a-color", None),
+        ] {
+            acct.config.accent_color = Some(value.to_string());
+            assert_eq!(accent_color(&acct), color, "{value}");
+        }
+    }
+
+    #[test]
+    fn retry_hint_under_a_minute_shows_seconds() {
+        let now = Utc::now();
+        let next = now + chrono::Duration::seconds(45);
+        assert_eq!(retry_hint(Some(&next), now).as_deref(), Some("retry in 45s"));
+    }
+
+    #[test]
+    fn retry_hint_past_schedule_and_unscheduled() {
+        let now = Utc::now();
+        let past = now - chrono::Duration::seconds(1);
+        assert_eq!(retry_hint(Some(&past), now).as_deref(), Some("retrying"));
+        assert_eq!(retry_hint(None, now), None);
+    }
+
+    fn account(org_id: &str, usage: Option<(u32, Option<u32>)>) -> AccountState {
+        let mut account = crate::app::tests::test_account(&format!("{org_id}-user"));
+        account.config.org_id = org_id.to_string();
+        AccountState {
+            usage: usage.map(|(utilization, weekly_utilization)| UsageData {
+                utilization,
+                weekly_utilization,
+                ..Default::default()
+            }),
+            status: AccountStatus::Ok,
+            ..account
+        }
+    }
+
+    #[test]
+    fn status_label_freshness_boundaries() {
+        let now = Utc::now();
+        let mut acct = account("org-a", Some((10, None)));
+        assert_eq!(status_label(&acct, None, now), ("--".to_string(), Color::DarkGray));
+
+        let label_at = |acct: &mut AccountState, secs: i64| {
+            acct.last_fetched = Some(now - chrono::Duration::seconds(secs));
+            status_label(acct, None, now).0
+        };
+        assert_eq!(label_at(&mut acct, 119), "Live");
+        assert_eq!(label_at(&mut acct, 120), "2m ago");
+        assert_eq!(label_at(&mut acct, 59 * 60 + 59), "59m ago");
+        assert_eq!(label_at(&mut acct, 60 * 60), "1h ago");
+    }
+
+    #[test]
+    fn status_label_logged_in_wins_over_staleness() {
+        let now = Utc::now();
+        let mut acct = account("org-a", Some((10, None)));
+        acct.last_fetched = Some(now - chrono::Duration::hours(3));
+        assert_eq!(
+            status_label(&acct, Some("org-a-user"), now),
+            ("Logged In".to_string(), Color::Green)
+        );
+        assert_eq!(status_label(&acct, Some("someone-else"), now).0, "3h ago");
+    }
+
+    #[test]
+    fn org_summaries_sum_and_max() {
+        let accounts = [
+            account("org-a", Some((30, Some(10)))),
+            account("org-b", Some((50, None))),
+            account("org-a", Some((45, None))),
+            account("org-a", None),
+        ];
+        let now = Utc::now();
+
+        let accounts: Vec<&AccountState> = accounts.iter().collect();
+        let sums = org_summaries(&accounts, OrgAggregate::Sum, false, now);
+        assert_eq!(sums.len(), 1, "Single-account orgs get no summary");
+        assert_eq!(sums[0].org_id, "org-a");
+        assert_eq!(sums[0].accounts, 2, "Accounts without usage don't count");
+        assert_eq!(sums[0].utilization, WindowReading::Pct(75));
+        assert_eq!(sums[0].weekly_utilization, Some(WindowReading::Pct(10)));
+
+        let maxes = org_summaries(&accounts, OrgAggregate::Max, false, now);
+        assert_eq!(maxes[0].utilization, WindowReading::Pct(45));
+    }
+
+    #[test]
+    fn org_summaries_skip_blank_org_ids() {
+        let accounts = [account("", Some((10, None))), account("", Some((20, None)))];
+        assert!(org_summaries(&accounts.iter().collect::<Vec<_>>(), OrgAggregate::Sum, false, Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn weekly_reset_label_follows_cadence() {
+        let now = chrono::DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let resets_at = now + chrono::Duration::days(4);
+
+        assert_eq!(weekly_reset_label(&resets_at, None, CountdownFormat::Full, now), "4d 0h");
+        assert_eq!(weekly_reset_label(&resets_at, Some(WeeklyCadence::Rolling), CountdownFormat::Full, now), "4d 0h");
+
+        let expected = resets_at.with_timezone(&chrono::Local).format("%a %H:%M").to_string();
+        assert_eq!(weekly_reset_label(&resets_at, Some(WeeklyCadence::Calendar), CountdownFormat::Full, now), expected);
+
+        let past = now - chrono::Duration::minutes(1);
+        assert_eq!(weekly_reset_label(&past, Some(WeeklyCadence::Calendar), CountdownFormat::Full, now), "now");
+    }
+
+    fn bar(width: usize, glyphs: BarGlyphs) -> Bar {
+        Bar {
+            width,
+            glyphs,
+            colors: ColorSupport::Ansi256,
+        }
+    }
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn bar_width_5() {
+        let b = bar(5, BarGlyphs::Ascii);
+        assert_eq!(b.filled(0), 0);
+        assert_eq!(b.filled(1), 1, "Any usage shows at least one segment");
+        assert_eq!(b.filled(50), 3);
+        assert_eq!(b.filled(99), 5);
+        assert_eq!(b.filled(150), 5);
+        assert_eq!(line_text(&b.line(40, Color::Green)), "##---");
+        assert_eq!(line_text(&b.empty_line()), ".....");
+    }
+
+    #[test]
+    fn bar_width_20() {
+        let b = bar(20, BarGlyphs::Blocks);
+        assert_eq!(b.filled(0), 0);
+        assert_eq!(b.filled(1), 1);
+        assert_eq!(b.filled(42), 8);
+        assert_eq!(b.filled(100), 20);
+        let text = line_text(&b.line(42, Color::Green));
+        assert_eq!(text.chars().count(), 20);
+        assert_eq!(text.chars().filter(|&c| c == '\u{2588}').count(), 8);
+    }
+
+    #[test]
+    fn basic_terminals_get_three_distinct_bands() {
+        let basic = ColorSupport::Basic;
+        assert_eq!(utilization_color(10, basic), Color::Green);
+        assert_eq!(utilization_color(75, basic), Color::Yellow);
+        assert_eq!(utilization_color(95, basic), Color::Red);
+        assert_eq!(utilization_color(95, ColorSupport::Ansi256), Color::Indexed(124));
+        assert!(!matches!(dim_color(basic), Color::Indexed(_)));
+    }
+
+    #[test]
+    fn visible_rows_reserves_a_footer_line() {
+        assert_eq!(visible_rows(5, 10, None), (5, 0));
+        assert_eq!(visible_rows(12, 10, None), (9, 3));
+        assert_eq!(visible_rows(12, 10, Some(4)), (4, 8));
+        assert_eq!(visible_rows(4, 10, Some(4)), (4, 0));
+        assert_eq!(visible_rows(3, 0, None), (0, 3));
+    }
+
+    #[test]
+    fn format_tokens_scales() {
+        assert_eq!(format_tokens(950), "950");
+        assert_eq!(format_tokens(123_456), "123k");
+        assert_eq!(format_tokens(1_250_000), "1.2M");
+    }
+
+    #[test]
+    fn usage_label_falls_back_to_percent() {
+        let tokens = Some(TokenCounts { used: 123_000, limit: 200_000 });
+        assert_eq!(usage_label(62, tokens, true), "123k/200k");
+        assert_eq!(usage_label(62, tokens, false), "62%");
+        assert_eq!(usage_label(62, None, true), "62%");
+    }
+
     #[test]
     fn error_message_short_not_truncated() {
         let msg = "Short error";
@@ -4,7 +4,17 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 
-use crate::app::InputFields;
+use chrono::Utc;
+
+use crate::app::{
+    AccountState, AccountStatus, AddValidation, FetchStats, InputFields, OrgChoice, OrgLookup, RawJsonView,
+    TokenCounts,
+};
+use crate::config::{AuthMethod, CountdownFormat};
+use crate::history::HistoryBuffer;
+use crate::keyring_store::KeyringAuditEntry;
+
+use super::accounts_table::{format_countdown, format_tokens};
 
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let x = area.x + (area.width.saturating_sub(width)) / 2;
@@ -12,8 +22,30 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     Rect::new(x, y, width.min(area.width), height.min(area.height))
 }
 
+/// Mask a secret, keeping only its last 8 characters visible. Slices by char,
+/// not byte, so pasted multibyte junk can't split a code point.
+fn mask_secret(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let visible: String = chars[chars.len().saturating_sub(8)..].iter().collect();
+    format!("{}...{}", "*".repeat(8), visible)
+}
+
+/// Confirms a paste landed without echoing any of it: a fixed label for its shape
+/// plus the length.
+fn paste_preview(buffer: &str) -> String {
+    if buffer.is_empty() {
+        return "_".to_string();
+    }
+    let shape = match buffer.trim_start() {
+        s if s.starts_with('{') => "{…} JSON",
+        s if s.starts_with("sk-ant-") => "sk-ant-…",
+        _ => "…",
+    };
+    format!("{shape} ({} chars)", buffer.chars().count())
+}
+
 pub fn render_input_dialog(frame: &mut Frame, title: &str, fields: &InputFields) {
-    let area = centered_rect(50, 11, frame.area());
+    let area = centered_rect(60, 11, frame.area());
     frame.render_widget(Clear, area);
 
     let block = Block::default()
@@ -36,8 +68,8 @@ pub fn render_input_dialog(frame: &mut Frame, title: &str, fields: &InputFields)
     ])
     .split(inner);
 
-    let labels = ["Name:", "Session Key:", "Org ID:"];
-    let values = [&fields.name, &fields.session_key, &fields.org_id];
+    let labels: [&str; InputFields::FIELD_COUNT] = ["Name:", "Session Key:", "Org ID:"];
+    let values = fields.fields();
 
     for (i, (label, value)) in labels.iter().zip(values.iter()).enumerate() {
         let label_style = Style::default().fg(Color::DarkGray);
@@ -50,14 +82,7 @@ pub fn render_input_dialog(frame: &mut Frame, title: &str, fields: &InputFields)
         };
 
         let display_value = if i == 1 && !value.is_empty() {
-            // Mask session key, show last 8 chars (char-safe)
-            let chars: Vec<char> = value.chars().collect();
-            let visible: String = if chars.len() > 8 {
-                chars[chars.len() - 8..].iter().collect()
-            } else {
-                value.to_string()
-            };
-            format!("{}...{}", "*".repeat(8), visible)
+            mask_secret(value)
         } else {
             value.to_string()
         };
@@ -80,17 +105,151 @@ pub fn render_input_dialog(frame: &mut Frame, title: &str, fields: &InputFields)
         );
     }
 
-    frame.render_widget(
-        Paragraph::new(Line::from(Span::styled(
+    let (note, hint) = match &fields.validation {
+        AddValidation::Unchecked if fields.swap_warning.is_some() => (
+            fields.swap_warning.map(|msg| (format!(" ⚠ {msg}"), Color::Red)),
+            " Edit to re-check  Enter: continue anyway  Esc: cancel",
+        ),
+        AddValidation::Unchecked if fields.org_lookup == OrgLookup::Pending => (
+            Some((" Looking up this session key's orgs...".to_string(), Color::Yellow)),
             " Tab: next field  Enter: save  Esc: cancel",
-            Style::default().fg(Color::DarkGray),
-        ))),
+        ),
+        AddValidation::Unchecked => (None, " Tab: next  Enter: save  Ctrl+O: find org  Esc: cancel"),
+        AddValidation::Pending => (
+            Some((" Checking org_id and session key...".to_string(), Color::Yellow)),
+            " Checking...  Esc: cancel",
+        ),
+        AddValidation::Failed(msg) => (
+            Some((format!(" ⚠ {msg}"), Color::Red)),
+            " Edit to re-check  Enter: save anyway  Esc: cancel",
+        ),
+    };
+    if let Some((text, color)) = note {
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(text, Style::default().fg(color)))),
+            chunks[6],
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(hint, Style::default().fg(Color::DarkGray)))),
         chunks[7],
     );
+
+    if let OrgLookup::Choosing { orgs, selected } = &fields.org_lookup {
+        render_org_picker(frame, orgs, *selected);
+    }
+}
+
+/// The session key's orgs, drawn over the Add/Edit dialog.
+fn render_org_picker(frame: &mut Frame, orgs: &[OrgChoice], selected: usize) {
+    let area = centered_rect(60, orgs.len() as u16 + 4, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Pick an org ")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut text: Vec<Line> = orgs
+        .iter()
+        .enumerate()
+        .map(|(i, org)| {
+            let style = if i == selected {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            let name = if org.name.is_empty() { "(unnamed)" } else { org.name.as_str() };
+            Line::from(Span::styled(format!(" {name:<20} {}", org.uuid), style))
+        })
+        .collect();
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        " ↑/↓: move  Enter: use  Esc: back",
+        Style::default().fg(Color::DarkGray),
+    )));
+    frame.render_widget(Paragraph::new(text), inner);
+}
+
+/// `repair_target` names the account whose credential is being replaced; `None` means
+/// the pasted credential becomes a new account.
+pub fn render_paste_credential_dialog(frame: &mut Frame, buffer: &str, repair_target: Option<&str>) {
+    let area = centered_rect(50, 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let (title, prompt, action) = match repair_target {
+        Some(name) => (
+            " Repair Credential ",
+            format!(" Paste a fresh credential JSON for {name}:"),
+            "replace",
+        ),
+        None => (
+            " Paste Credential ",
+            " Paste Claude Code credential JSON:".to_string(),
+            "import",
+        ),
+    };
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let preview = paste_preview(buffer);
+
+    let lines = vec![
+        Line::from(Span::styled(prompt, Style::default().fg(Color::DarkGray))),
+        Line::from(Span::styled(
+            format!(" {preview}"),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" Enter: {action}  Ctrl+U: clear  Esc: cancel"),
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+pub fn render_org_override_dialog(frame: &mut Frame, account_name: &str, input: &str) {
+    let area = centered_rect(50, 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Fetch With Org ID ")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let lines = vec![
+        Line::from(Span::styled(
+            format!(" One-off fetch for {account_name}:"),
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(Span::styled(
+            format!(" {input}_"),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            " Enter: fetch  Ctrl+U: clear  Esc: cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
 }
 
 pub fn render_confirm_dialog(frame: &mut Frame, message: &str, hint: &str) {
-    let area = centered_rect(40, 5, frame.area());
+    let width = (message.chars().count() as u16 + 4).max(40);
+    let area = centered_rect(width, 5, frame.area());
     frame.render_widget(Clear, area);
 
     let block = Block::default()
@@ -124,32 +283,58 @@ pub fn render_confirm_dialog(frame: &mut Frame, message: &str, hint: &str) {
 }
 
 pub fn render_help_overlay(frame: &mut Frame) {
-    let area = centered_rect(45, 14, frame.area());
-    frame.render_widget(Clear, area);
-
-    let block = Block::default()
-        .title(" Help ")
-        .borders(Borders::ALL)
-        .style(Style::default().fg(Color::Cyan));
-
-    let inner = block.inner(area);
-    frame.render_widget(block, area);
-
-    let help_lines = vec![
+    let help_lines = [
         " j/k or Up/Down    Navigate accounts",
         " r                 Refresh all",
         " R                 Refresh selected",
+        " T                 Retry errored accounts only",
+        " Space             Pause/resume automatic polling",
+        " m                 Low-power/smooth redraw",
+        " l                 Re-detect Claude Code login",
         " s or Enter        Set active",
         " i                 Import from Claude Code",
+        " P                 Paste credential JSON",
         " a                 Add account (session key)",
+        " c                 Add account in selected account's org",
         " e                 Edit account",
+        " v                 Account details",
+        " w                 Append usage to CSV",
+        " J                 Raw API response",
+        " O                 Raw response using another org ID",
         " d/x               Delete account",
+        " Ctrl+S            Save config now",
+        " Ctrl+R            Reload config from disk",
+        " A                 Keyring audit log (if enabled)",
+        " E                 Show only errored accounts",
+        " g                 Jump to account nearest its limit",
+        " f / F             Toggle favorite / next favorite",
+        " K                 Repair OAuth credential (paste JSON)",
+        " N                 Show/hide notes file (notes_file)",
+        " z                 Focus: full-screen gauges for selected",
+        " y                 Copy selected account's usage",
+        " o                 Show/hide org ID column",
+        " W                 Write debug bundle",
+        " B                 Fast-poll selected account for a while",
+        " b                 Open claude.ai usage page in browser",
+        " n / p             Next / previous page (page_size)",
+        " H                 Hide/show help bar",
         " ?                 Toggle help",
         " q / Ctrl+C        Quit",
         "",
         " Press any key to close",
     ];
 
+    let area = centered_rect(45, help_lines.len() as u16 + 2, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Help ")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
     let text: Vec<Line> = help_lines
         .iter()
         .map(|l| {
@@ -162,3 +347,367 @@ pub fn render_help_overlay(frame: &mut Frame) {
 
     frame.render_widget(Paragraph::new(text), inner);
 }
+
+pub fn render_whats_new_overlay(frame: &mut Frame, lines: &[String]) {
+    let area = centered_rect(64, lines.len() as u16 + 4, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" What's New ")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut text: Vec<Line> = lines
+        .iter()
+        .map(|l| {
+            let color = if l.starts_with('v') { Color::White } else { Color::Gray };
+            Line::from(Span::styled(format!(" {l}"), Style::default().fg(color)))
+        })
+        .collect();
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        " Press any key to close",
+        Style::default().fg(Color::Gray),
+    )));
+
+    frame.render_widget(Paragraph::new(text), inner);
+}
+
+/// Label/value pairs shown in the details overlay for one account.
+fn detail_rows(account: &AccountState) -> Vec<(&'static str, String)> {
+    let auth = match account.config.auth_method {
+        AuthMethod::SessionKey => "Session key",
+        AuthMethod::OAuth => "OAuth",
+    };
+    let status = match &account.status {
+        AccountStatus::Idle => "Idle".to_string(),
+        AccountStatus::Ok => "OK".to_string(),
+        AccountStatus::Error(msg) => format!("Error: {msg}"),
+    };
+    let window = |util: Option<u32>,
+                  tokens: Option<TokenCounts>,
+                  resets_at: Option<&chrono::DateTime<Utc>>| {
+        let Some(pct) = util else {
+            return "--".to_string();
+        };
+        let mut text = used_and_remaining(pct, tokens);
+        if let Some(r) = resets_at {
+            text.push_str(&format!(", resets in {}", format_countdown(r, CountdownFormat::Full)));
+        }
+        text
+    };
+    let usage = account.usage.as_ref();
+    let last_fetched = account
+        .last_fetched
+        .map(|t| {
+            let ago = Utc::now().signed_duration_since(t).num_seconds();
+            format!("{}s ago", ago.max(0))
+        })
+        .unwrap_or_else(|| "never".to_string());
+
+    let mut rows = vec![
+        ("Name", account.config.name.clone()),
+        ("Org ID", account.config.org_id.clone()),
+        ("Auth", auth.to_string()),
+        ("Plan", account.config.plan.clone().unwrap_or_else(|| "--".to_string())),
+        ("Status", status),
+        (
+            "5h usage",
+            window(
+                usage.map(|u| u.utilization),
+                usage.and_then(|u| u.tokens),
+                usage.and_then(|u| u.resets_at.as_ref()),
+            ),
+        ),
+        (
+            "7d usage",
+            window(
+                usage.and_then(|u| u.weekly_utilization),
+                usage.and_then(|u| u.weekly_tokens),
+                usage.and_then(|u| u.weekly_resets_at.as_ref()),
+            ),
+        ),
+        ("5h resets", reset_times(usage.and_then(|u| u.resets_at.as_ref()))),
+        ("7d resets", reset_times(usage.and_then(|u| u.weekly_resets_at.as_ref()))),
+        ("Fetched", last_fetched),
+        ("Fetches", fetch_stats(&account.stats)),
+        (
+            "Last error",
+            account
+                .stats
+                .last_error_at
+                .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+                .unwrap_or_else(|| "--".to_string()),
+        ),
+    ];
+
+    if account.config.auth_method == AuthMethod::OAuth {
+        rows.push(("Token", token_expiry(account.cached_token.as_deref(), Utc::now())));
+    }
+    if let Some(expires) = account.config.session_key_expires {
+        let local = expires.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+        let left = if expires <= Utc::now() {
+            "expired".to_string()
+        } else {
+            format!("in {}", format_countdown(&expires, CountdownFormat::Full))
+        };
+        rows.push(("Key expires", format!("{local} ({left}, as recorded)")));
+    }
+    rows
+}
+
+/// "45% used, 55% remaining", plus the token counts behind it when the API sent them.
+fn used_and_remaining(pct: u32, tokens: Option<TokenCounts>) -> String {
+    let mut text = format!("{pct}% used, {}% remaining", 100u32.saturating_sub(pct));
+    if let Some(t) = tokens {
+        text.push_str(&format!(
+            " · {} / {} ({} left)",
+            format_tokens(t.used),
+            format_tokens(t.limit),
+            format_tokens(t.limit.saturating_sub(t.used))
+        ));
+    }
+    text
+}
+
+/// Columns of 5h history in the details overlay's trend line.
+const TREND_WIDTH: usize = 60;
+
+/// One block character per sample, scaled so 100% is a full block.
+fn sparkline(values: &[u32]) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    values
+        .iter()
+        .map(|&pct| LEVELS[(pct.min(100) as usize * (LEVELS.len() - 1)) / 100])
+        .collect()
+}
+
+/// "12 · 92% ok · avg 340ms" — attempts this session, success rate, mean latency.
+fn fetch_stats(stats: &FetchStats) -> String {
+    let Some(rate) = stats.success_rate() else {
+        return "none yet".to_string();
+    };
+    let mut text = format!("{} · {rate}% ok", stats.attempts);
+    if let Some(avg) = stats.average_latency() {
+        text.push_str(&format!(" · avg {}ms", avg.as_millis()));
+    }
+    text
+}
+
+/// Absolute reset time in both the local timezone and UTC, so nobody has to guess
+/// which clock a teammate means.
+fn reset_times(resets_at: Option<&chrono::DateTime<Utc>>) -> String {
+    match resets_at {
+        Some(r) => {
+            let local = r.with_timezone(&chrono::Local);
+            format!(
+                "{} local · {} UTC",
+                local.format("%a %b %-d %H:%M"),
+                r.format("%a %b %-d %H:%M"),
+            )
+        }
+        None => "--".to_string(),
+    }
+}
+
+/// "expires in 42m" / "expired" for a stored OAuth credential, independent of the
+/// usage-window resets.
+fn token_expiry(raw_credential: Option<&str>, now: chrono::DateTime<Utc>) -> String {
+    match raw_credential.and_then(crate::oauth::extract_expires_at) {
+        Some(expires_at) if expires_at <= now => "expired".to_string(),
+        Some(expires_at) => format!("expires in {}", format_countdown(&expires_at, CountdownFormat::Full)),
+        None => "--".to_string(),
+    }
+}
+
+pub fn render_details_overlay(frame: &mut Frame, account: &AccountState, history: Option<&HistoryBuffer>) {
+    let mut rows = detail_rows(account);
+    if let Some(history) = history.filter(|h| !h.is_empty()) {
+        rows.push(("5h trend", sparkline(&history.samples_for_width(TREND_WIDTH))));
+    }
+    let area = centered_rect(80, rows.len() as u16 + 4, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Details ")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut text: Vec<Line> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!(" {label:<10}"), Style::default().fg(Color::DarkGray)),
+                Span::styled(value, Style::default().fg(Color::White)),
+            ])
+        })
+        .collect();
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        " Press any key to close",
+        Style::default().fg(Color::Gray),
+    )));
+
+    frame.render_widget(Paragraph::new(text), inner);
+}
+
+pub fn render_keyring_log_overlay(frame: &mut Frame, entries: &[KeyringAuditEntry]) {
+    let screen = frame.area();
+    let area = centered_rect(
+        screen.width.saturating_sub(4).clamp(20, 90),
+        screen.height.saturating_sub(2).max(5),
+        screen,
+    );
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Keyring Audit ({}) ", entries.len()))
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Newest at the bottom; drop the oldest when the list doesn't fit
+    let visible = inner.height.saturating_sub(2) as usize;
+    let skip = entries.len().saturating_sub(visible);
+    let mut lines: Vec<Line> = if entries.is_empty() {
+        vec![Line::from(Span::styled(
+            " No keyring calls yet",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        entries
+            .iter()
+            .skip(skip)
+            .map(|entry| {
+                let (outcome, color) = match &entry.outcome {
+                    Ok(()) => ("ok".to_string(), Color::Green),
+                    Err(msg) => (msg.clone(), Color::Red),
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", entry.at.with_timezone(&chrono::Local).format("%H:%M:%S")),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(format!("{:<7}", entry.operation), Style::default().fg(Color::White)),
+                    Span::styled(format!("{} ", entry.account_name), Style::default().fg(Color::White)),
+                    Span::styled(outcome, Style::default().fg(color)),
+                ])
+            })
+            .collect()
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " Press any key to close",
+        Style::default().fg(Color::Gray),
+    )));
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+pub fn render_raw_json_overlay(frame: &mut Frame, view: &RawJsonView) {
+    let screen = frame.area();
+    let area = centered_rect(
+        screen.width.saturating_sub(4).max(20),
+        screen.height.saturating_sub(2).max(5),
+        screen,
+    );
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Raw response: {} ", view.account_name))
+        .title_bottom(" j/k PgUp/PgDn: scroll  Esc: close ")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+
+    let text: Vec<Line> = view
+        .body
+        .lines()
+        .map(|l| Line::from(Span::styled(l.to_string(), Style::default().fg(Color::Gray))))
+        .collect();
+
+    frame.render_widget(Paragraph::new(text).block(block).scroll((view.scroll, 0)), area);
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // =========================================================================
+    // FIX VERIFIED: masking a session key with multibyte characters near the
+    // end must not panic (byte slicing at len()-8 would split 'é').
+    // =========================================================================
+    #[test]
+    fn mask_secret_handles_multibyte_tail() {
+        let key = "sk-ant-abcdefé€🙂xyz";
+        assert_eq!(mask_secret(key), "********...efé€🙂xyz");
+    }
+
+    #[test]
+    fn paste_preview_shows_no_pasted_characters() {
+        assert_eq!(paste_preview(""), "_");
+        assert_eq!(paste_preview("sk-ant-oat01-secretsecret"), "sk-ant-… (25 chars)");
+        assert_eq!(paste_preview(r#"{"claudeAiOauth":{}}"#), "{…} JSON (20 chars)");
+    }
+
+    #[test]
+    fn reset_times_show_local_and_utc() {
+        let resets_at = chrono::DateTime::parse_from_rfc3339("2025-01-07T17:05:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let label = reset_times(Some(&resets_at));
+        assert!(label.ends_with(" local · Tue Jan 7 17:05 UTC"), "got: {label}");
+        assert_eq!(reset_times(None), "--");
+    }
+
+    #[test]
+    fn token_expiry_labels() {
+        let now = Utc::now();
+        let past = format!(
+            r#"{{"claudeAiOauth":{{"accessToken":"a","expiresAt":{}}}}}"#,
+            (now - chrono::Duration::minutes(5)).timestamp_millis()
+        );
+        assert_eq!(token_expiry(Some(&past), now), "expired");
+
+        let future = format!(
+            r#"{{"claudeAiOauth":{{"accessToken":"a","expiresAt":{}}}}}"#,
+            (now + chrono::Duration::hours(3)).timestamp_millis()
+        );
+        assert!(token_expiry(Some(&future), now).starts_with("expires in 2h"));
+
+        assert_eq!(token_expiry(Some("plain-token"), now), "--");
+        assert_eq!(token_expiry(None, now), "--");
+    }
+
+    #[test]
+    fn used_and_remaining_labels() {
+        assert_eq!(used_and_remaining(45, None), "45% used, 55% remaining");
+        assert_eq!(used_and_remaining(104, None), "104% used, 0% remaining");
+        let tokens = TokenCounts { used: 90_000, limit: 200_000 };
+        assert_eq!(
+            used_and_remaining(45, Some(tokens)),
+            "45% used, 55% remaining · 90k / 200k (110k left)"
+        );
+    }
+
+    #[test]
+    fn sparkline_scales_to_full_block_at_100() {
+        assert_eq!(sparkline(&[0, 50, 100, 250]), "▁▄██");
+    }
+
+    #[test]
+    fn mask_secret_short_value_shown_in_full() {
+        assert_eq!(mask_secret("abc"), "********...abc");
+    }
+}
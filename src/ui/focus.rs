@@ -0,0 +1,135 @@
+use chrono::Utc;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph};
+use ratatui::Frame;
+
+use super::accounts_table::{reset_text, status_label, utilization_color, Window};
+use crate::app::{AccountState, AccountStatus, AppState, ColorSupport, WindowReading};
+use crate::config::AuthMethod;
+
+/// One account over the whole screen: a gauge and countdown per window, then metadata.
+pub fn render(frame: &mut Frame, app: &AppState, account: &AccountState) {
+    let now = Utc::now();
+    let block = Block::default()
+        .title(format!(" {} ", account.config.name))
+        .title_style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(frame.area());
+    frame.render_widget(block, frame.area());
+
+    let chunks = Layout::vertical([
+        Constraint::Length(3), // 5h gauge
+        Constraint::Length(1), // 5h countdown
+        Constraint::Length(1), // spacer
+        Constraint::Length(3), // 7d gauge
+        Constraint::Length(1), // 7d countdown
+        Constraint::Length(1), // spacer
+        Constraint::Min(0),    // metadata
+        Constraint::Length(1), // hint
+    ])
+    .split(inner);
+
+    let recalculate = app.settings.recalculate_after_reset;
+    // Same readings and reset labels as the table row
+    let reset_line = |window: Window, reading: Option<WindowReading>| match (&account.usage, reading) {
+        (_, Some(WindowReading::Recalculating)) => "reset passed, recalculating…".to_string(),
+        (Some(usage), Some(_)) => match reset_text(usage, window, app.settings.countdown_format, now) {
+            text if text == "--" => "no reset scheduled".to_string(),
+            text => format!("resets {text}"),
+        },
+        _ => "no reset scheduled".to_string(),
+    };
+    let five_hour = account.five_hour_reading(recalculate, now);
+    let weekly = account.weekly_reading(recalculate, now);
+    render_window(
+        frame,
+        [chunks[0], chunks[1]],
+        " 5h window ",
+        five_hour,
+        reset_line(Window::FiveHour, five_hour),
+        app.color_support,
+    );
+    render_window(
+        frame,
+        [chunks[3], chunks[4]],
+        " 7d window ",
+        weekly,
+        reset_line(Window::Weekly, weekly),
+        app.color_support,
+    );
+
+    let (status, status_color) = match &account.status {
+        AccountStatus::Error(msg) => (format!("Error: {msg}"), Color::Red),
+        AccountStatus::Idle => ("Waiting for first fetch".to_string(), Color::DarkGray),
+        AccountStatus::Ok => status_label(account, app.logged_in_account.as_deref(), now),
+    };
+    let auth = match account.config.auth_method {
+        AuthMethod::SessionKey => "Session key",
+        AuthMethod::OAuth => "OAuth",
+    };
+    let field = |label: &str, value: String, color: Color| {
+        Line::from(vec![
+            Span::styled(format!(" {label:<8}"), Style::default().fg(Color::DarkGray)),
+            Span::styled(value, Style::default().fg(color)),
+        ])
+    };
+    let metadata = vec![
+        field("Status", status, status_color),
+        field("Org ID", account.config.org_id.clone(), Color::White),
+        field("Auth", auth.to_string(), Color::White),
+        field(
+            "Plan",
+            account.config.plan.clone().unwrap_or_else(|| "--".to_string()),
+            Color::White,
+        ),
+    ];
+    frame.render_widget(Paragraph::new(metadata), chunks[6]);
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            " Esc/z: back to table",
+            Style::default().fg(Color::DarkGray),
+        ))),
+        chunks[7],
+    );
+}
+
+fn render_window(
+    frame: &mut Frame,
+    [gauge_area, countdown_area]: [Rect; 2],
+    title: &str,
+    reading: Option<WindowReading>,
+    countdown: String,
+    colors: ColorSupport,
+) {
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let gauge = match reading {
+        Some(WindowReading::Pct(pct)) => Gauge::default()
+            .block(block)
+            .gauge_style(Style::default().fg(utilization_color(pct, colors)))
+            .percent(pct.min(100) as u16)
+            .label(format!("{pct}%")),
+        Some(WindowReading::Recalculating) => Gauge::default()
+            .block(block)
+            .gauge_style(Style::default().fg(Color::DarkGray))
+            .percent(0)
+            .label("recalc…"),
+        None => Gauge::default()
+            .block(block)
+            .gauge_style(Style::default().fg(Color::DarkGray))
+            .percent(0)
+            .label("--"),
+    };
+    frame.render_widget(gauge, gauge_area);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            countdown,
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        )))
+        .centered(),
+        countdown_area,
+    );
+}
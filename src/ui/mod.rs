@@ -1,24 +1,62 @@
 mod accounts_table;
 mod dialogs;
+mod focus;
 mod help_bar;
+mod notes_panel;
 mod status_bar;
 
 use ratatui::layout::{Constraint, Layout};
 use ratatui::Frame;
 
 use crate::app::{AppMode, AppState};
+use crate::config::CompactHeader;
+
+/// Below this terminal height `compact_header = "auto"` drops the table header and
+/// the status bar carries the column legend instead, freeing a row for account data.
+const COMPACT_HEIGHT: u16 = 12;
+
+/// Just the accounts table, over the whole frame, as of a fixed `now` (snapshots).
+pub(crate) fn draw_table_at(frame: &mut Frame, app: &AppState, now: chrono::DateTime<chrono::Utc>) {
+    accounts_table::render_at(frame, frame.area(), app, false, now);
+}
 
 pub fn draw(frame: &mut Frame, app: &AppState) {
+    if let AppMode::Focus(name) = &app.mode {
+        if let Some(account) = app.accounts.iter().find(|a| a.config.name == *name) {
+            focus::render(frame, app, account);
+            return;
+        }
+    }
+
+    let compact = match app.settings.compact_header {
+        CompactHeader::Auto => frame.area().height < COMPACT_HEIGHT,
+        CompactHeader::Always => true,
+        CompactHeader::Never => false,
+    };
+
+    let help_bar_height = if app.settings.show_help_bar { 1 } else { 0 };
+
+    let notes_height = app
+        .notes
+        .as_deref()
+        .map_or(0, |text| notes_panel::height(text, frame.area().height));
+
     let chunks = Layout::vertical([
-        Constraint::Length(1), // status bar
-        Constraint::Min(5),   // main table
-        Constraint::Length(1), // help bar
+        Constraint::Length(1),               // status bar
+        Constraint::Min(5),                  // main table
+        Constraint::Length(notes_height),    // notes panel (toggled with N)
+        Constraint::Length(help_bar_height), // help bar (hidden with H)
     ])
     .split(frame.area());
 
-    status_bar::render(frame, chunks[0], app);
-    accounts_table::render(frame, chunks[1], app);
-    help_bar::render(frame, chunks[2]);
+    status_bar::render(frame, chunks[0], app, compact);
+    accounts_table::render(frame, chunks[1], app, compact);
+    if let Some(text) = &app.notes {
+        notes_panel::render(frame, chunks[2], text);
+    }
+    if app.settings.show_help_bar {
+        help_bar::render(frame, chunks[3]);
+    }
 
     // Render modal overlays
     match &app.mode {
@@ -46,9 +84,62 @@ pub fn draw(frame: &mut Frame, app: &AppState) {
                 );
             }
         }
+        AppMode::ConfirmSwapErrored => {
+            dialogs::render_confirm_dialog(
+                frame,
+                "This account's last fetch failed — swap anyway?",
+                "y: swap anyway  n/Esc: cancel",
+            );
+        }
         AppMode::Help => {
             dialogs::render_help_overlay(frame);
         }
-        AppMode::Normal => {}
+        AppMode::ConfirmReload => {
+            let dropped = app
+                .pending_reload
+                .as_ref()
+                .map(|cfg| app.dropped_by_reload(cfg).join(", "))
+                .unwrap_or_default();
+            dialogs::render_confirm_dialog(
+                frame,
+                &format!("Reload drops unsaved: {dropped}"),
+                "y: reload anyway  n/Esc: keep (Ctrl+S to save)",
+            );
+        }
+        AppMode::WhatsNew => {
+            dialogs::render_whats_new_overlay(frame, &app.whats_new);
+        }
+        AppMode::Details => {
+            if let Some(account) = app.accounts.get(app.selected_index) {
+                dialogs::render_details_overlay(frame, account, app.history.get(&account.config.name));
+            }
+        }
+        AppMode::RawJson => {
+            if let Some(view) = &app.raw_json {
+                dialogs::render_raw_json_overlay(frame, view);
+            }
+        }
+        AppMode::PasteCredential => {
+            dialogs::render_paste_credential_dialog(frame, &app.paste_buffer, None);
+        }
+        AppMode::RepairCredential(name) => {
+            dialogs::render_paste_credential_dialog(frame, &app.paste_buffer, Some(name));
+        }
+        AppMode::OrgOverride => {
+            if let Some(account) = app.accounts.get(app.selected_index) {
+                dialogs::render_org_override_dialog(
+                    frame,
+                    &account.config.name,
+                    &app.org_override_input,
+                );
+            }
+        }
+        AppMode::KeyringLog => {
+            if let Some(audit) = &app.keyring_audit {
+                dialogs::render_keyring_log_overlay(frame, &audit.entries());
+            }
+        }
+        // Focus draws in place of everything above; reaching here means its account is gone
+        AppMode::Normal | AppMode::Focus(_) => {}
     }
 }
@@ -0,0 +1,37 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// Rows for the panel: the file plus its border, capped at a third of the screen.
+pub fn height(text: &str, screen_height: u16) -> u16 {
+    let lines = u16::try_from(text.lines().count().max(1)).unwrap_or(u16::MAX);
+    lines.saturating_add(2).min(screen_height / 3).max(3)
+}
+
+pub fn render(frame: &mut Frame, area: Rect, text: &str) {
+    let block = Block::default()
+        .title(" Notes (N to close) ")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::DarkGray));
+    let notes = Paragraph::new(text)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false })
+        .block(block);
+    frame.render_widget(notes, area);
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn height_caps_huge_files_without_overflow() {
+        assert_eq!(height("one line", 60), 3);
+        assert_eq!(height(&"x\n".repeat(70_000), 60), 20);
+    }
+}
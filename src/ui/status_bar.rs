@@ -1,20 +1,67 @@
 use chrono::Utc;
 use ratatui::layout::{Constraint, Layout, Rect};
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::Frame;
 
-use crate::app::AppState;
+use crate::app::{AppState, StatusLevel};
 
-pub fn render(frame: &mut Frame, area: Rect, app: &AppState) {
+/// Column legend shown in compact mode, where the table header is hidden.
+const COMPACT_LEGEND: &str = "# name | 5h % bar reset | 7d % bar reset | status";
+
+pub fn render(frame: &mut Frame, area: Rect, app: &AppState, compact: bool) {
     let mut left_spans = vec![
         Span::styled(" Claude Tracker", Style::default().fg(Color::Cyan)),
     ];
+    if let Some(profile) = crate::config::profile() {
+        left_spans.push(Span::styled(format!(" [{profile}]"), Style::default().fg(Color::Cyan)));
+    }
 
-    // Status message (shown next to title)
-    if let Some((msg, _)) = &app.status_message {
+    if crate::api::is_non_default_api(app.settings.api_base_url.as_deref(), &app.accounts) {
+        left_spans.push(Span::raw("  "));
+        left_spans.push(Span::styled(
+            " NON-DEFAULT API ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if app.paused {
+        left_spans.push(Span::raw("  "));
+        left_spans.push(Span::styled(
+            " PAUSED ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if app.errors_only {
+        left_spans.push(Span::raw("  "));
+        left_spans.push(Span::styled(
+            " ERRORS ONLY ",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Status message (shown next to title). In compact mode the column legend
+    // takes its place while no message is pending.
+    if let Some(status) = &app.status_message {
+        let color = match status.level {
+            StatusLevel::Info => Color::Yellow,
+            StatusLevel::Error => Color::Red,
+        };
+        left_spans.push(Span::raw("  "));
+        left_spans.push(Span::styled(status.text.clone(), Style::default().fg(color)));
+    } else if compact {
         left_spans.push(Span::raw("  "));
-        left_spans.push(Span::styled(msg.clone(), Style::default().fg(Color::Yellow)));
+        left_spans.push(Span::styled(COMPACT_LEGEND, Style::default().fg(Color::DarkGray)));
     }
 
     let left_line = Line::from(left_spans);
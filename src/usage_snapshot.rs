@@ -0,0 +1,119 @@
+//! Plain, owned view of every account's usage at one moment. Exporters and headless
+//! output take this instead of `AppState`, so they don't depend on UI or keyring state.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::app::{AccountState, AccountStatus, AppState, WindowReading};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub accounts: Vec<AccountSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSnapshot {
+    pub name: String,
+    pub org_id: String,
+    pub plan: Option<String>,
+    /// "ok", "idle" or "error".
+    pub status: &'static str,
+    pub error: Option<String>,
+    /// Reset-adjusted, as the table shows it. None until the first fetch.
+    pub five_hour_pct: Option<u32>,
+    pub five_hour_resets_at: Option<DateTime<Utc>>,
+    pub seven_day_pct: Option<u32>,
+    pub seven_day_resets_at: Option<DateTime<Utc>>,
+    pub last_fetched: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub is_logged_in: bool,
+}
+
+impl UsageSnapshot {
+    pub fn from_app(app: &AppState, now: DateTime<Utc>) -> Self {
+        Self::new(
+            &app.accounts,
+            Some(app.active_account_index),
+            app.logged_in_account.as_deref(),
+            now,
+        )
+    }
+
+    pub fn new(
+        accounts: &[AccountState],
+        active_index: Option<usize>,
+        logged_in: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let accounts = accounts
+            .iter()
+            .enumerate()
+            .map(|(i, account)| {
+                let usage = account.usage.as_ref();
+                let (status, error) = match &account.status {
+                    AccountStatus::Ok => ("ok", None),
+                    AccountStatus::Idle => ("idle", None),
+                    AccountStatus::Error(msg) => ("error", Some(msg.clone())),
+                };
+                AccountSnapshot {
+                    name: account.config.name.clone(),
+                    org_id: account.config.org_id.clone(),
+                    plan: account.config.plan.clone(),
+                    status,
+                    error,
+                    five_hour_pct: account.five_hour_reading(false, now).map(WindowReading::pct),
+                    five_hour_resets_at: usage.and_then(|u| u.resets_at),
+                    seven_day_pct: account.weekly_reading(false, now).map(WindowReading::pct),
+                    seven_day_resets_at: usage.and_then(|u| u.weekly_resets_at),
+                    last_fetched: account.last_fetched,
+                    is_active: active_index == Some(i),
+                    is_logged_in: logged_in == Some(account.config.name.as_str()),
+                }
+            })
+            .collect();
+        Self {
+            taken_at: now,
+            accounts,
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::tests::test_account;
+
+    fn account(name: &str, status: AccountStatus) -> AccountState {
+        AccountState {
+            status,
+            cached_token: Some("secret".to_string()),
+            ..test_account(name)
+        }
+    }
+
+    #[test]
+    fn snapshot_flags_active_logged_in_and_errors() {
+        let accounts = [
+            account("a", AccountStatus::Ok),
+            account("b", AccountStatus::Error("Timeout".to_string())),
+        ];
+        let snapshot = UsageSnapshot::new(&accounts, Some(1), Some("a"), Utc::now());
+
+        let a = &snapshot.accounts[0];
+        assert!(a.is_logged_in && !a.is_active);
+        assert_eq!((a.status, a.five_hour_pct), ("ok", None));
+
+        let b = &snapshot.accounts[1];
+        assert!(b.is_active && !b.is_logged_in);
+        assert_eq!(b.status, "error");
+        assert_eq!(b.error.as_deref(), Some("Timeout"));
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(!json.contains("secret"), "Snapshots never carry tokens");
+    }
+}
@@ -0,0 +1,86 @@
+//! Release notes shown once after an update, newest first. Add an entry when a
+//! release adds something users would otherwise only find in the README.
+
+/// (version, highlights) pairs, newest first.
+const RELEASES: &[(&str, &[&str])] = &[(
+    "0.2.0",
+    &[
+        "z  focus mode: full-screen gauges for one account",
+        "y  copy the selected account's usage (OSC 52 over SSH)",
+        "N  notes panel from notes_file",
+        "K  repair an OAuth account by pasting fresh credential JSON",
+        "f / F  favorites; g  jump to the account closest to its limit",
+        "m  low-power rendering; Space  pause polling",
+        "CLI: list, swap <name>, raw --name <name>, --profile <name>",
+    ],
+)];
+
+/// This binary's version.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// True if this binary is newer than `last_seen`, so the stored version should move
+/// forward. Running an older binary leaves a newer stored version alone.
+pub fn is_newer(last_seen: Option<&str>) -> bool {
+    last_seen.is_none_or(|seen| parse_version(CURRENT_VERSION) > parse_version(seen))
+}
+
+/// Highlights from every release newer than `last_seen`, newest first, each under a
+/// version heading. `None` means the tracker predates this overlay, so everything shows.
+pub fn notes_since(last_seen: Option<&str>) -> Vec<String> {
+    let seen = last_seen.map(parse_version);
+    let mut lines = Vec::new();
+    for (version, highlights) in RELEASES {
+        if seen.is_some_and(|seen| parse_version(version) <= seen) {
+            continue;
+        }
+        lines.push(format!("v{version}"));
+        lines.extend(highlights.iter().map(|h| format!("  {h}")));
+    }
+    lines
+}
+
+/// "1.2.3" as a comparable triple; missing or non-numeric parts count as 0.
+fn parse_version(version: &str) -> (u32, u32, u32) {
+    let mut parts = version
+        .trim_start_matches('v')
+        .split('.')
+        .map(|p| p.parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(parse_version("0.10.0") > parse_version("0.9.9"));
+        assert_eq!(parse_version("v1.2"), (1, 2, 0));
+    }
+
+    #[test]
+    fn only_unseen_releases_are_listed() {
+        assert!(notes_since(Some(CURRENT_VERSION)).is_empty());
+        assert!(notes_since(Some("99.0.0")).is_empty(), "Downgrades show nothing");
+        let all = notes_since(None);
+        assert_eq!(all[0], format!("v{}", RELEASES[0].0));
+        assert_eq!(notes_since(Some("0.0.1")), all);
+        assert_eq!(notes_since(Some("0.1.0")), all, "Upgrades from the first release see the notes");
+    }
+
+    #[test]
+    fn older_binaries_are_not_newer() {
+        assert!(is_newer(None));
+        assert!(is_newer(Some("0.0.1")));
+        assert!(!is_newer(Some(CURRENT_VERSION)));
+        assert!(!is_newer(Some("99.0.0")));
+    }
+}